@@ -1,8 +1,22 @@
-use crate::autocomplete::{AutocompleteState, MarkdownAutocomplete};
-use crate::models::{Note, Folder, NotebookData, FolderTreeNode};
-use crate::search::{EnhancedSearch, SearchQuery, SearchResult};
+use crate::autocomplete::{AutocompleteState, AutocompleteSuggestion, MarkdownAutocomplete};
+use crate::commands::{self, PaletteMatch};
+use crate::config;
+use crate::find::{self, FindMatch};
+use crate::fuzzy;
+use crate::journal::{self, JournalConfig};
+use crate::links::{self, Backlink, LinkIndex};
+use crate::lists::{self, EnterAction, LinePrefix};
+use crate::models::{Note, Folder, NotebookData, FolderTreeNode, NoteVisit, SavedSearch, SessionState};
+use crate::quick_open::{self, QuickOpenResult};
+use crate::search::{EnhancedSearch, MatchType, SearchQuery, SearchResult};
+use crate::tags::{self, TagTreeItem};
+use crate::snippets::{self, SnippetContext, SnippetSession, TabStop};
+use crate::table::{self, ColumnAlignment, Table};
+use crate::tasks::{self, TaskGrouping, TaskItem};
+use crate::templates;
 use uuid::Uuid;
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
+use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum AppMode {
@@ -17,6 +31,19 @@ pub enum AppMode {
     Move,
     Help,
     DeleteConfirm,
+    Rename,
+    RenameConfirm,
+    TagEdit,
+    TagBrowser,
+    TagRename,
+    QuickOpen,
+    Recent,
+    CommandPalette,
+    Tasks,
+    TemplatePicker,
+    TemplatePrompt,
+    SavedSearchEdit,
+    Find,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -24,6 +51,8 @@ pub enum FocusedPane {
     Folders,
     Editor,
     Preview,
+    Backlinks,
+    SearchResults,
 }
 
 #[derive(Debug, Clone)]
@@ -33,12 +62,33 @@ pub struct TreeItem {
     pub item_type: TreeItemType,
     pub depth: usize,
     pub expanded: bool,
+    pub section: TreeSection,
+}
+
+/// The part of the tree a row is listed in; a note can show up in several
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TreeSection {
+    Pinned,
+    SavedSearches,
+    Folders,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum TreeItemType {
     Folder,
     Note,
+    /// Header of the Pinned section
+    Pinned,
+    /// Header of the Saved Searches section
+    SavedSearches,
+    SavedSearch,
+}
+
+impl TreeItemType {
+    /// Section headers are not notebook items: they cannot be renamed, moved or deleted
+    pub fn is_section_header(&self) -> bool {
+        matches!(self, TreeItemType::Pinned | TreeItemType::SavedSearches)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -46,13 +96,58 @@ pub enum SaveStatus {
     Saved,
     Modified,
     Saving,
+    #[allow(dead_code)]
     Error,
 }
 
+/// A copy of the editor buffer used to undo an edit
+#[derive(Debug, Clone)]
+pub struct EditorSnapshot {
+    pub content: String,
+    pub cursor: (u16, u16),
+}
+
+const MAX_UNDO_LEVELS: usize = 100;
+
+/// Autocomplete trigger for `[[` note links
+const LINK_TRIGGER: &str = "[[";
+const MAX_LINK_SUGGESTIONS: usize = 6;
+
+/// Autocomplete trigger for inline `#tags`
+const TAG_TRIGGER: &str = "#";
+const MAX_TAG_SUGGESTIONS: usize = 6;
+
+const MAX_QUICK_OPEN_RESULTS: usize = 50;
+const MAX_COMMAND_HISTORY: usize = 100;
+const MAX_RECENT_NOTES: usize = 20;
+const MAX_JUMP_LIST: usize = 100;
+
+/// Pause in typing before the incremental search runs
+const SEARCH_DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// Where a search results row leads: its note, and the line and column of its match in the text
+struct SearchTarget {
+    note_id: Uuid,
+    title: String,
+    position: Option<(usize, usize)>,
+}
+
+/// What the screen showed when incremental search started, restored when it is cancelled
+#[derive(Debug, Clone)]
+pub struct SearchOrigin {
+    /// The open note with its cursor and scroll, if any
+    pub visit: Option<NoteVisit>,
+    pub focused_pane: FocusedPane,
+    pub results: Vec<SearchResult>,
+    pub result_selected: usize,
+    pub results_enabled: bool,
+}
+
 #[derive(Debug, Clone)]
 pub enum OperationResult {
     Success { message: String, icon: String },
     Error { message: String, icon: String },
+    #[allow(dead_code)]
     Info { message: String, icon: String },
 }
 
@@ -69,10 +164,21 @@ pub struct App {
     pub editor_content: String,
     pub editor_cursor: (u16, u16), // (row, col)
     pub editor_scroll: u16,
+    pub undo_stack: Vec<EditorSnapshot>,
     
     // Search
     pub search_query: String,
-    pub search_results: Vec<Note>,
+    /// Why the query in the advanced search dialog could not be parsed
+    pub search_error: Option<String>,
+    pub search_origin: Option<SearchOrigin>,
+    /// When the incremental search query last changed, until it is run
+    pub search_pending_since: Option<Instant>,
+    
+    // Find in the open note
+    /// Text found in the open note by the find bar; empty when no find is active
+    pub find_query: String,
+    /// Cursor and scroll when the find bar opened, restored on Esc
+    pub find_origin: Option<((u16, u16), u16)>,
     pub enhanced_search: EnhancedSearch,
     pub enhanced_search_results: Vec<SearchResult>,
     
@@ -88,6 +194,8 @@ pub struct App {
     // External editor
     pub external_editor: Option<String>,
     pub just_returned_from_editor: bool,
+    /// Keys are reported with the kitty keyboard protocol, so Ctrl+I is not Tab
+    pub keyboard_enhanced: bool,
     
     // Move operation
     pub move_item_id: Option<Uuid>,
@@ -98,15 +206,94 @@ pub struct App {
     pub delete_item_type: Option<TreeItemType>,
     pub delete_item_name: String,
     
+    // Rename
+    pub rename_item_id: Option<Uuid>,
+    pub rename_item_type: Option<TreeItemType>,
+    pub rename_old_name: String,
+    pub rename_new_name: String,
+    /// Notes whose links will be rewritten by a pending note rename
+    pub rename_link_notes: Vec<Uuid>,
+    
     // Preview mode
     pub preview_enabled: bool,
     
+    // Backlinks panel
+    pub backlinks_enabled: bool,
+    pub link_index: LinkIndex,
+    /// Lowercased note titles, so drawing [[links]] does not scan the notebook per link
+    pub note_titles: HashSet<String>,
+    pub backlinks: Vec<Backlink>,
+    pub backlink_selected: usize,
+    
+    // Search results panel
+    pub search_results_enabled: bool,
+    /// Index into the rows of the search results panel
+    pub search_result_selected: usize,
+    
+    // Pinned notes
+    pub pinned_collapsed: bool,
+    
+    // Saved searches
+    pub saved_searches_collapsed: bool,
+    pub saved_search_edit_id: Option<Uuid>,
+    
+    // Daily notes
+    pub journal_config: JournalConfig,
+    
+    // Templates for new notes; choice 0 is a blank note
+    pub template_choices: Vec<Uuid>,
+    pub template_selected: usize,
+    pub template_fields: Vec<String>,
+    pub template_values: Vec<String>,
+    pub pending_note_title: String,
+    
+    // Tags
+    pub tag_filter: Option<String>,
+    pub tag_suggestions: Vec<String>,
+    pub tag_suggestion_selected: usize,
+    pub tag_browser_items: Vec<TagTreeItem>,
+    pub tag_browser_selected: usize,
+    pub tag_browser_collapsed: HashSet<String>,
+    pub tag_rename_old: String,
+    
+    // Quick open
+    pub quick_open_results: Vec<QuickOpenResult>,
+    pub quick_open_selected: usize,
+    
+    // Command palette and command line completion
+    pub palette_matches: Vec<PaletteMatch>,
+    pub palette_selected: usize,
+    pub command_completions: Vec<String>,
+    pub command_completion_start: usize,
+    pub command_completion_index: Option<usize>,
+    /// Byte offset of the cursor in the command line
+    pub command_cursor: usize,
+    pub command_history: Vec<String>,
+    pub command_history_index: Option<usize>,
+    /// The line being typed before stepping into the history
+    pub command_history_draft: String,
+    
+    // Recent notes and the jump list
+    pub recent_notes: Vec<Uuid>,
+    pub recent_selected: usize,
+    pub jump_list: Vec<NoteVisit>,
+    /// Index of the current visit in the jump list
+    pub jump_index: usize,
+    
+    // Task overview
+    pub task_items: Vec<TaskItem>,
+    pub task_selected: usize,
+    pub task_grouping: TaskGrouping,
+    pub task_show_done: bool,
+    
     // Autocompletion
     pub autocomplete_state: AutocompleteState,
     pub markdown_autocomplete: MarkdownAutocomplete,
+    pub snippet_session: Option<SnippetSession>,
     
     // Visual feedback
     pub save_status: SaveStatus,
+    #[allow(dead_code)]
     pub last_operation: Option<String>,
     pub operation_result: Option<OperationResult>,
     pub operation_result_time: Option<std::time::Instant>,
@@ -126,9 +313,14 @@ impl App {
             editor_content: String::new(),
             editor_cursor: (0, 0),
             editor_scroll: 0,
+            undo_stack: Vec::new(),
             
             search_query: String::new(),
-            search_results: Vec::new(),
+            search_error: None,
+            search_origin: None,
+            search_pending_since: None,
+            find_query: String::new(),
+            find_origin: None,
             enhanced_search: EnhancedSearch::new(),
             enhanced_search_results: Vec::new(),
             
@@ -142,6 +334,7 @@ impl App {
             // Try to detect helix, then fall back to other editors
             external_editor: detect_external_editor(),
             just_returned_from_editor: false,
+            keyboard_enhanced: false,
             
             // Move operation
             move_item_id: None,
@@ -152,12 +345,76 @@ impl App {
             delete_item_type: None,
             delete_item_name: String::new(),
             
+            // Rename
+            rename_item_id: None,
+            rename_item_type: None,
+            rename_old_name: String::new(),
+            rename_new_name: String::new(),
+            rename_link_notes: Vec::new(),
+            
             // Preview mode
             preview_enabled: false,
             
+            // Backlinks panel
+            backlinks_enabled: false,
+            link_index: LinkIndex::default(),
+            note_titles: HashSet::new(),
+            backlinks: Vec::new(),
+            backlink_selected: 0,
+            search_results_enabled: false,
+            search_result_selected: 0,
+            
+            // Pinned notes
+            pinned_collapsed: false,
+            
+            // Saved searches
+            saved_searches_collapsed: false,
+            saved_search_edit_id: None,
+            
+            // Daily notes
+            journal_config: JournalConfig::default(),
+            
+            // Templates
+            template_choices: Vec::new(),
+            template_selected: 0,
+            template_fields: Vec::new(),
+            template_values: Vec::new(),
+            pending_note_title: String::new(),
+            
+            // Tags
+            tag_filter: None,
+            tag_suggestions: Vec::new(),
+            tag_suggestion_selected: 0,
+            tag_browser_items: Vec::new(),
+            tag_browser_selected: 0,
+            tag_browser_collapsed: HashSet::new(),
+            tag_rename_old: String::new(),
+            quick_open_results: Vec::new(),
+            quick_open_selected: 0,
+            palette_matches: Vec::new(),
+            palette_selected: 0,
+            command_completions: Vec::new(),
+            command_completion_start: 0,
+            command_completion_index: None,
+            command_cursor: 0,
+            command_history: Vec::new(),
+            command_history_index: None,
+            command_history_draft: String::new(),
+            recent_notes: Vec::new(),
+            recent_selected: 0,
+            jump_list: Vec::new(),
+            jump_index: 0,
+            
+            // Task overview
+            task_items: Vec::new(),
+            task_selected: 0,
+            task_grouping: TaskGrouping::Note,
+            task_show_done: true,
+            
             // Autocompletion
             autocomplete_state: AutocompleteState::new(),
             markdown_autocomplete: MarkdownAutocomplete::new(),
+            snippet_session: None,
             
             // Visual feedback
             save_status: SaveStatus::Saved,
@@ -168,20 +425,48 @@ impl App {
         
         // Create default folder structure
         app.create_default_structure();
+        app.link_index = LinkIndex::build(&app.notebook);
+        app.enhanced_search.rebuild_index(&app.notebook);
         app.refresh_tree_view();
         
         app
     }
+    
+    /// Replace the notebook with loaded data and rebuild the indexes over it
+    pub fn load_notebook(&mut self, notebook: NotebookData) {
+        self.notebook = notebook;
+        self.link_index = LinkIndex::build(&self.notebook);
+        self.enhanced_search.rebuild_index(&self.notebook);
+        self.refresh_tree_view();
+    }
 
     fn create_default_structure(&mut self) {
         // Create some default folders
         let general_folder = Folder::new("General".to_string(), None);
         let projects_folder = Folder::new("Projects".to_string(), None);
         let daily_folder = Folder::new("Daily Notes".to_string(), None);
+        let templates_folder = Folder::new(templates::TEMPLATES_FOLDER.to_string(), None);
+        
+        // A sample template for new notes
+        let mut meeting_template = Note::new("Meeting".to_string(), Some(templates_folder.id));
+        meeting_template.content = [
+            "# $NOTE_TITLE",
+            "",
+            "Date: $DATE",
+            "Attendees: ${?Attendees}",
+            "",
+            "## Agenda",
+            "- $1",
+            "",
+            "## Notes",
+            "$0",
+        ].join("\n");
         
         self.notebook.add_folder(general_folder);
         self.notebook.add_folder(projects_folder);
         self.notebook.add_folder(daily_folder);
+        self.notebook.add_folder(templates_folder);
+        self.notebook.add_note(meeting_template);
         
         // Create a sample note
         let welcome_note = Note::new("Welcome to Scribble".to_string(), None);
@@ -190,47 +475,153 @@ impl App {
 
     pub fn refresh_tree_view(&mut self) {
         self.folder_tree_items.clear();
+        self.note_titles = self.notebook.notes.values().map(|n| n.title.to_lowercase()).collect();
         let tree = self.notebook.build_folder_tree();
         
-        // Add root level notes first
-        let root_notes = self.notebook.get_folder_notes(None);
-        for note in root_notes {
-            self.folder_tree_items.push(TreeItem {
+        // Pinned notes head the tree in their own section
+        let pinned: Vec<TreeItem> = self.notebook.pinned_notes()
+            .into_iter()
+            .filter(|n| self.note_passes_filter(n))
+            .map(|note| TreeItem {
                 id: note.id,
                 name: note.title.clone(),
                 item_type: TreeItemType::Note,
-                depth: 0,
+                depth: 1,
                 expanded: false,
+                section: TreeSection::Pinned,
+            })
+            .collect();
+        if !pinned.is_empty() {
+            self.folder_tree_items.push(TreeItem {
+                id: Uuid::nil(),
+                name: format!("Pinned ({})", pinned.len()),
+                item_type: TreeItemType::Pinned,
+                depth: 0,
+                expanded: !self.pinned_collapsed,
+                section: TreeSection::Pinned,
+            });
+            if !self.pinned_collapsed {
+                self.folder_tree_items.extend(pinned);
+            }
+        }
+        
+        // Saved searches follow, each listing its current results when expanded
+        let searches = self.saved_search_items();
+        if !searches.is_empty() {
+            self.folder_tree_items.push(TreeItem {
+                id: Uuid::nil(),
+                name: "Saved Searches".to_string(),
+                item_type: TreeItemType::SavedSearches,
+                depth: 0,
+                expanded: !self.saved_searches_collapsed,
+                section: TreeSection::SavedSearches,
             });
+            if !self.saved_searches_collapsed {
+                self.folder_tree_items.extend(searches);
+            }
         }
         
+        // Then root level notes
+        let root_notes: Vec<TreeItem> = self.notebook.get_folder_notes(None)
+            .into_iter()
+            .filter(|n| self.note_passes_filter(n))
+            .map(|note| TreeItem {
+                id: note.id,
+                name: note.title.clone(),
+                item_type: TreeItemType::Note,
+                depth: 0,
+                expanded: false,
+                section: TreeSection::Folders,
+            })
+            .collect();
+        self.folder_tree_items.extend(root_notes);
+        
         // Add folder tree
         for node in tree {
             self.add_tree_node_to_items(&node);
         }
     }
 
+    /// Rows for the saved searches, by name, with the results of the expanded ones
+    fn saved_search_items(&self) -> Vec<TreeItem> {
+        let mut searches: Vec<&SavedSearch> = self.notebook.saved_searches.iter().collect();
+        searches.sort_by_key(|search| search.name.to_lowercase());
+        
+        let mut items = Vec::new();
+        for search in searches {
+            items.push(TreeItem {
+                id: search.id,
+                name: search.name.clone(),
+                item_type: TreeItemType::SavedSearch,
+                depth: 1,
+                expanded: search.expanded,
+                section: TreeSection::SavedSearches,
+            });
+            if !search.expanded {
+                continue;
+            }
+            // A query that no longer parses just lists nothing
+            let results = SearchQuery::parse(&search.query)
+                .and_then(|query| self.enhanced_search.find(&self.notebook, &query))
+                .unwrap_or_default();
+            items.extend(results
+                .iter()
+                .filter(|result| self.note_passes_filter(&result.note))
+                .map(|result| TreeItem {
+                    id: result.note.id,
+                    name: result.note.title.clone(),
+                    item_type: TreeItemType::Note,
+                    depth: 2,
+                    expanded: false,
+                    section: TreeSection::SavedSearches,
+                }));
+        }
+        items
+    }
+
+    /// Whether a note is shown under the active tag filter
+    fn note_passes_filter(&self, note: &Note) -> bool {
+        self.tag_filter.as_ref().is_none_or(|tag| tags::note_has_tag(note, tag))
+    }
+    
+    /// Whether a folder subtree holds any note shown under the tag filter
+    fn node_has_filtered_notes(&self, node: &FolderTreeNode) -> bool {
+        node.notes.iter().any(|n| self.note_passes_filter(n))
+            || node.children.iter().any(|child| self.node_has_filtered_notes(child))
+    }
+
     fn add_tree_node_to_items(&mut self, node: &FolderTreeNode) {
+        // While filtering by tag, only folders leading to tagged notes are shown, expanded
+        let filtering = self.tag_filter.is_some();
+        if filtering && !self.node_has_filtered_notes(node) {
+            return;
+        }
+        
         // Add the folder
         self.folder_tree_items.push(TreeItem {
             id: node.folder.id,
             name: node.folder.name.clone(),
             item_type: TreeItemType::Folder,
             depth: node.depth,
-            expanded: node.folder.expanded,
+            expanded: node.folder.expanded || filtering,
+            section: TreeSection::Folders,
         });
         
         // Add notes in this folder if expanded
-        if node.folder.expanded {
-            for note in &node.notes {
-                self.folder_tree_items.push(TreeItem {
+        if node.folder.expanded || filtering {
+            let notes: Vec<TreeItem> = node.notes
+                .iter()
+                .filter(|n| self.note_passes_filter(n))
+                .map(|note| TreeItem {
                     id: note.id,
                     name: note.title.clone(),
                     item_type: TreeItemType::Note,
                     depth: node.depth + 1,
                     expanded: false,
-                });
-            }
+                    section: TreeSection::Folders,
+                })
+                .collect();
+            self.folder_tree_items.extend(notes);
             
             // Add child folders recursively
             for child in &node.children {
@@ -244,13 +635,150 @@ impl App {
     }
 
     pub fn select_note(&mut self, note_id: Uuid) {
-        if let Some(note) = self.notebook.notes.get(&note_id).cloned() {
-            self.current_note = Some(note.clone());
-            self.editor_content = note.content;
-            self.editor_cursor = (0, 0);
-            self.editor_scroll = 0;
-            self.focused_pane = FocusedPane::Editor;
+        let reopening = self.current_note.as_ref().is_some_and(|n| n.id == note_id);
+        if self.load_note(note_id) && !reopening {
+            self.record_visit(note_id);
+        }
+    }
+    
+    /// Load a note into the editor at the top, without touching the jump list
+    fn load_note(&mut self, note_id: Uuid) -> bool {
+        if !self.show_note(note_id) {
+            return false;
+        }
+        self.recent_notes.retain(|&id| id != note_id);
+        self.recent_notes.insert(0, note_id);
+        self.recent_notes.truncate(MAX_RECENT_NOTES);
+        true
+    }
+    
+    /// Put a note in the editor at the top, leaving the recent notes and jump list alone
+    fn show_note(&mut self, note_id: Uuid) -> bool {
+        let Some(note) = self.notebook.notes.get(&note_id).cloned() else {
+            return false;
+        };
+        self.remember_position();
+        if self.current_note.as_ref().map(|n| n.id) != Some(note_id) {
+            self.find_query.clear();
+        }
+        self.current_note = Some(note.clone());
+        self.editor_content = note.content;
+        self.editor_cursor = (0, 0);
+        self.editor_scroll = 0;
+        self.undo_stack.clear();
+        self.snippet_session = None;
+        self.focused_pane = FocusedPane::Editor;
+        self.refresh_backlinks();
+        true
+    }
+    
+    /// Store the cursor and scroll of the open note in its jump list entry
+    fn remember_position(&mut self) {
+        let current_id = self.current_note.as_ref().map(|n| n.id);
+        if let Some(visit) = self.jump_list.get_mut(self.jump_index).filter(|v| Some(v.note_id) == current_id) {
+            visit.cursor = self.editor_cursor;
+            visit.scroll = self.editor_scroll;
+        }
+    }
+    
+    /// Add a visit after the current one, dropping any visits ahead of it
+    fn record_visit(&mut self, note_id: Uuid) {
+        if !self.jump_list.is_empty() {
+            self.jump_list.truncate(self.jump_index + 1);
+        }
+        self.jump_list.push(NoteVisit::new(note_id));
+        if self.jump_list.len() > MAX_JUMP_LIST {
+            self.jump_list.remove(0);
         }
+        self.jump_index = self.jump_list.len() - 1;
+    }
+    
+    /// Go back (-1) or forward (1) through visited notes, restoring where the cursor was
+    pub fn jump(&mut self, direction: isize) {
+        // Keep unsaved edits before switching notes
+        if self.save_status == SaveStatus::Modified {
+            if let Err(e) = self.save_current_note() {
+                self.set_message(e);
+                return;
+            }
+        }
+        self.remember_position();
+        
+        // Away from the current visit (as after a restart), going back returns to it first
+        let current_id = self.current_note.as_ref().map(|n| n.id);
+        let at_current = self.jump_list.get(self.jump_index).is_some_and(|v| Some(v.note_id) == current_id);
+        let target = if !at_current && direction < 0 && self.jump_index < self.jump_list.len() {
+            Some(self.jump_index)
+        } else {
+            self.jump_index.checked_add_signed(direction).filter(|&i| i < self.jump_list.len())
+        };
+        let Some(target) = target else {
+            let end = if direction < 0 { "oldest" } else { "newest" };
+            self.set_message(format!("Already at the {} note in the jump list", end));
+            return;
+        };
+        let visit = self.jump_list[target];
+        self.jump_index = target;
+        if !self.load_note(visit.note_id) {
+            return;
+        }
+        self.navigate_to_note(visit.note_id);
+        self.editor_cursor = visit.cursor;
+        self.editor_scroll = visit.scroll;
+        self.clamp_cursor_row();
+        self.set_message(format!("Jump {}/{}", target + 1, self.jump_list.len()));
+    }
+    
+    /// Forget a deleted note in the recent list and the jump list
+    fn forget_note_visits(&mut self, note_id: Uuid) {
+        self.recent_notes.retain(|&id| id != note_id);
+        let mut kept: Vec<NoteVisit> = Vec::with_capacity(self.jump_list.len());
+        let mut jump_index = None;
+        for (i, visit) in self.jump_list.iter().enumerate() {
+            // Visits next to each other may now be to the same note; keep the first of them
+            let repeated = kept.last().is_some_and(|last| last.note_id == visit.note_id);
+            if visit.note_id != note_id && !repeated {
+                kept.push(*visit);
+            }
+            if i == self.jump_index {
+                // A dropped current visit falls back to the one kept before it
+                jump_index = Some(kept.len().saturating_sub(1));
+            }
+        }
+        self.jump_index = jump_index.unwrap_or(kept.len()).min(kept.len().saturating_sub(1));
+        self.jump_list = kept;
+    }
+    
+    pub fn open_recent_notes(&mut self) {
+        if self.recent_notes.is_empty() {
+            self.set_message("No recently opened notes".to_string());
+            return;
+        }
+        // The open note is first; preselect the one before it
+        let current_id = self.current_note.as_ref().map(|n| n.id);
+        self.recent_selected = usize::from(self.recent_notes.len() > 1 && self.recent_notes.first() == current_id.as_ref());
+        self.mode = AppMode::Recent;
+    }
+    
+    pub fn next_recent_note(&mut self) {
+        if self.recent_selected + 1 < self.recent_notes.len() {
+            self.recent_selected += 1;
+        }
+    }
+    
+    pub fn previous_recent_note(&mut self) {
+        self.recent_selected = self.recent_selected.saturating_sub(1);
+    }
+    
+    pub fn open_selected_recent_note(&mut self) -> Result<(), String> {
+        self.mode = AppMode::Normal;
+        let note_id = *self.recent_notes.get(self.recent_selected).ok_or("No note selected")?;
+        // Keep unsaved edits before switching notes
+        if self.save_status == SaveStatus::Modified {
+            self.save_current_note()?;
+        }
+        self.open_note_by_id(note_id);
+        Ok(())
     }
     
     pub fn open_note_by_id(&mut self, note_id: Uuid) {
@@ -265,36 +793,158 @@ impl App {
     }
     
     fn navigate_to_note(&mut self, note_id: Uuid) {
-        // Find the note in the tree items and select it
-        for (index, item) in self.folder_tree_items.iter().enumerate() {
-            if item.id == note_id && item.item_type == TreeItemType::Note {
-                self.selected_folder_index = index;
-                
-                // If the note is in a folder, make sure the folder is expanded
-                if let Some(note) = self.notebook.notes.get(&note_id) {
-                    if let Some(folder_id) = note.folder_id {
-                        if let Some(folder) = self.notebook.folders.get_mut(&folder_id) {
-                            folder.expanded = true;
-                            self.refresh_tree_view(); // Refresh to show the expanded folder
-                            
-                            // Re-find the note index after refresh
-                            for (idx, item) in self.folder_tree_items.iter().enumerate() {
-                                if item.id == note_id && item.item_type == TreeItemType::Note {
-                                    self.selected_folder_index = idx;
-                                    break;
-                                }
-                            }
-                        }
-                    }
-                }
+        // Expand every folder above the note so it shows up in the tree
+        let mut folder_id = self.notebook.notes.get(&note_id).and_then(|n| n.folder_id);
+        let mut expanded_any = false;
+        let mut depth = 0;
+        while let Some(id) = folder_id {
+            // Bounded walk in case of a corrupted parent cycle
+            depth += 1;
+            if depth > self.notebook.folders.len() {
+                break;
+            }
+            let Some(folder) = self.notebook.folders.get_mut(&id) else {
                 break;
+            };
+            expanded_any |= !folder.expanded;
+            folder.expanded = true;
+            folder_id = folder.parent_id;
+        }
+        if expanded_any {
+            self.refresh_tree_view();
+        }
+        
+        // Find the note in its folder, or failing that in the Pinned section, and select it
+        let is_note = |item: &TreeItem| item.id == note_id && item.item_type == TreeItemType::Note;
+        if let Some(index) = self.folder_tree_items
+            .iter()
+            .position(|item| is_note(item) && item.section == TreeSection::Folders)
+            .or_else(|| self.folder_tree_items.iter().position(is_note))
+        {
+            self.selected_folder_index = index;
+        }
+    }
+
+    /// Read the daily notes settings, keeping the defaults if there is no journal file
+    pub fn load_journal_config(&mut self) -> Result<(), String> {
+        let path = config::journal_file();
+        if path.exists() {
+            self.journal_config = journal::load_config(&path)?;
+        }
+        Ok(())
+    }
+
+    /// The folder daily notes go in, `Some(None)` for the root.
+    /// Missing folders are created when `create` is set, otherwise the result is None.
+    fn journal_folder(&mut self, create: bool) -> Option<Option<Uuid>> {
+        let path: Vec<String> = self.journal_config.folder_path().into_iter().map(String::from).collect();
+        let mut parent_id = None;
+        for name in path {
+            let existing = self.notebook.folders
+                .values()
+                .find(|f| f.parent_id == parent_id && f.name.eq_ignore_ascii_case(&name))
+                .map(|f| f.id);
+            parent_id = match existing {
+                Some(id) => Some(id),
+                None if create => {
+                    let folder = Folder::new(name, parent_id);
+                    let id = folder.id;
+                    self.notebook.add_folder(folder);
+                    Some(id)
+                }
+                None => return None,
+            };
+        }
+        Some(parent_id)
+    }
+
+    /// Open the daily note for `date`, creating it from the journal template if needed
+    pub fn open_journal_entry(&mut self, date: chrono::NaiveDate) -> Result<(), String> {
+        if self.save_status == SaveStatus::Modified {
+            self.save_current_note()?;
+        }
+        
+        let title = self.journal_config.entry_title(date);
+        let folder_id = self.journal_folder(true).flatten();
+        
+        // Titles are unique, so an entry moved out of the journal folder is still found
+        let lowercase = title.to_lowercase();
+        let existing = self.notebook.notes
+            .values()
+            .filter(|n| n.title.to_lowercase() == lowercase)
+            .min_by_key(|n| n.folder_id != folder_id)
+            .map(|n| n.id);
+        if let Some(note_id) = existing {
+            self.open_note_by_id(note_id);
+            return Ok(());
+        }
+        
+        let context = SnippetContext {
+            note_title: title.clone(),
+            folder: folder_id
+                .and_then(|id| self.notebook.folders.get(&id))
+                .map(|f| f.name.clone())
+                .unwrap_or_default(),
+            indent: String::new(),
+            date: Some(date),
+        };
+        let expansion = snippets::expand(&self.journal_config.template.text(), &context);
+        
+        let mut note = Note::new(title.clone(), folder_id);
+        note.content = expansion.text;
+        let note_id = note.id;
+        self.link_index.update_note(&note);
+        self.enhanced_search.index_note(&note);
+        self.notebook.add_note(note);
+        self.refresh_tree_view();
+        self.open_note_by_id(note_id);
+        
+        // Start typing at the template's first tab stop
+        self.push_undo_snapshot();
+        self.mode = AppMode::Insert;
+        self.start_snippet_session(expansion.stops);
+        self.set_operation_success(format!("Created journal entry '{}'", title), Some("📅".to_string()));
+        Ok(())
+    }
+
+    /// Open the nearest earlier (negative) or later (positive) existing journal entry,
+    /// counting from the open entry or from today
+    pub fn step_journal_entry(&mut self, direction: i64) -> Result<(), String> {
+        let Some(folder_id) = self.journal_folder(false) else {
+            return Err("No journal entries yet".to_string());
+        };
+        let from = self.current_note
+            .as_ref()
+            .and_then(|n| self.journal_config.entry_date(&n.title))
+            .unwrap_or_else(|| chrono::Local::now().date_naive());
+        
+        let entries = self.notebook.notes
+            .values()
+            .filter(|n| n.folder_id == folder_id)
+            .filter_map(|n| self.journal_config.entry_date(&n.title).map(|date| (date, n.id)));
+        let target = if direction < 0 {
+            entries.filter(|(date, _)| *date < from).max()
+        } else {
+            entries.filter(|(date, _)| *date > from).min()
+        };
+        
+        match target {
+            Some((_, note_id)) => {
+                if self.save_status == SaveStatus::Modified {
+                    self.save_current_note()?;
+                }
+                self.open_note_by_id(note_id);
+                Ok(())
             }
+            None if direction < 0 => Err("No earlier journal entry".to_string()),
+            None => Err("No later journal entry".to_string()),
         }
     }
 
     pub fn create_new_note(&mut self, title: String, folder_id: Option<Uuid>) {
         let note = Note::new(title, folder_id);
         let note_id = note.id;
+        self.enhanced_search.index_note(&note);
         self.notebook.add_note(note);
         self.refresh_tree_view();
         self.select_note(note_id);
@@ -328,10 +978,117 @@ impl App {
         };
         
         let folder_id = self.pending_folder_parent;
-        self.create_new_note(title, folder_id);
+        self.input_buffer.clear();
+        self.begin_new_note(title, folder_id);
+    }
+    
+    /// Create a note, first offering the templates to start it from if there are any
+    pub fn begin_new_note(&mut self, title: String, folder_id: Option<Uuid>) {
+        let choices: Vec<Uuid> = templates::template_notes(&self.notebook).iter().map(|n| n.id).collect();
+        if choices.is_empty() {
+            self.create_new_note(title, folder_id);
+            self.pending_folder_parent = None;
+            self.mode = AppMode::Insert; // Go directly to editing the new note
+            return;
+        }
+        
+        self.pending_note_title = title;
+        self.pending_folder_parent = folder_id;
+        self.template_choices = choices;
+        self.template_selected = 0;
+        self.mode = AppMode::TemplatePicker;
+    }
+    
+    pub fn next_template(&mut self) {
+        // The blank note comes before the templates
+        if self.template_selected < self.template_choices.len() {
+            self.template_selected += 1;
+        }
+    }
+    
+    pub fn previous_template(&mut self) {
+        self.template_selected = self.template_selected.saturating_sub(1);
+    }
+    
+    /// Create the note from the chosen template, asking for its fields first
+    pub fn choose_template(&mut self) -> Result<(), String> {
+        let Some(template_id) = self.template_selected.checked_sub(1).map(|i| self.template_choices[i]) else {
+            let title = std::mem::take(&mut self.pending_note_title);
+            let folder_id = self.pending_folder_parent.take();
+            self.create_new_note(title, folder_id);
+            self.mode = AppMode::Insert;
+            return Ok(());
+        };
+        let template = self.notebook.notes.get(&template_id).ok_or("Template no longer exists")?;
+        
+        self.template_fields = templates::prompt_fields(&template.content);
+        self.template_values.clear();
+        self.input_buffer.clear();
+        if self.template_fields.is_empty() {
+            self.create_note_from_template()
+        } else {
+            self.mode = AppMode::TemplatePrompt;
+            Ok(())
+        }
+    }
+    
+    /// Record the value typed for the current template field, creating the note after the last one
+    pub fn submit_template_field(&mut self) -> Result<(), String> {
+        self.template_values.push(std::mem::take(&mut self.input_buffer).trim().to_string());
+        if self.template_values.len() < self.template_fields.len() {
+            return Ok(());
+        }
+        self.create_note_from_template()
+    }
+    
+    fn create_note_from_template(&mut self) -> Result<(), String> {
+        let template_id = self.template_choices[self.template_selected - 1];
+        let template = self.notebook.notes.get(&template_id).ok_or("Template no longer exists")?;
+        let template_title = template.title.clone();
+        let values: Vec<(String, String)> = std::mem::take(&mut self.template_fields)
+            .into_iter()
+            .zip(std::mem::take(&mut self.template_values))
+            .collect();
+        let body = templates::fill_prompts(&template.content, &values);
+        
+        let title = std::mem::take(&mut self.pending_note_title);
+        let folder_id = self.pending_folder_parent.take();
+        let context = SnippetContext {
+            note_title: title.clone(),
+            folder: folder_id
+                .and_then(|id| self.notebook.folders.get(&id))
+                .map(|f| f.name.clone())
+                .unwrap_or_default(),
+            indent: String::new(),
+            date: None,
+        };
+        let expansion = snippets::expand(&body, &context);
+        
+        let mut note = Note::new(title.clone(), folder_id);
+        note.content = expansion.text;
+        let note_id = note.id;
+        self.link_index.update_note(&note);
+        self.enhanced_search.index_note(&note);
+        self.notebook.add_note(note);
+        self.refresh_tree_view();
+        self.select_note(note_id);
+        
+        // Fill in the template's tab stops like a snippet
+        self.mode = AppMode::Insert;
+        self.start_snippet_session(expansion.stops);
+        self.set_operation_success(format!("Created '{}' from template '{}'", title, template_title), Some("📄".to_string()));
+        Ok(())
+    }
+    
+    /// Abandon the note being created from a template
+    pub fn cancel_template(&mut self) {
+        self.pending_note_title.clear();
         self.pending_folder_parent = None;
+        self.template_fields.clear();
+        self.template_values.clear();
         self.input_buffer.clear();
-        self.mode = AppMode::Insert; // Go directly to editing the new note
+        self.mode = AppMode::Normal;
+        self.set_message("New note cancelled".to_string());
     }
 
     pub fn finish_new_folder_input(&mut self) {
@@ -355,16 +1112,11 @@ impl App {
     }
 
     pub fn save_current_note(&mut self) -> Result<(), String> {
-        if let Some(ref note) = self.current_note.clone() {
+        if let Some(note_id) = self.current_note.as_ref().map(|n| n.id) {
             self.mark_saving();
             
-            let mut updated_note = note.clone();
-            updated_note.update_content(self.editor_content.clone());
-            
-            // Update the note in the notebook
-            self.notebook.notes.insert(updated_note.id, updated_note.clone());
-            self.current_note = Some(updated_note);
-            self.refresh_tree_view();
+            let updated_note = self.store_note_content(note_id, self.editor_content.clone());
+            self.current_note = updated_note;
             
             self.mark_saved();
             self.set_operation_success("Note saved successfully".to_string(), Some("💾".to_string()));
@@ -374,9 +1126,29 @@ impl App {
             Err("No note to save".to_string())
         }
     }
+    
+    /// Write new content into a note, syncing its inline tags and the link and search indexes
+    fn store_note_content(&mut self, note_id: Uuid, content: String) -> Option<Note> {
+        let mut updated_note = self.current_note.as_ref()
+            .filter(|n| n.id == note_id)
+            .or(self.notebook.notes.get(&note_id))?
+            .clone();
+        
+        // Inline #tags join the note's tags; ones deleted from the text since the last save leave them
+        updated_note.sync_inline_tags(tags::extract_hashtags(&content));
+        updated_note.update_content(content);
+        
+        // Update the note in the notebook
+        self.link_index.update_note(&updated_note);
+        self.enhanced_search.index_note(&updated_note);
+        self.notebook.notes.insert(updated_note.id, updated_note.clone());
+        self.refresh_tree_view();
+        self.refresh_backlinks();
+        Some(updated_note)
+    }
 
     pub fn start_delete_confirmation(&mut self) -> Result<(), String> {
-        if let Some(item) = self.get_selected_item().cloned() {
+        if let Some(item) = self.get_selected_item().filter(|item| !item.item_type.is_section_header()).cloned() {
             self.delete_item_id = Some(item.id);
             self.delete_item_type = Some(item.item_type.clone());
             self.delete_item_name = item.name.clone();
@@ -392,14 +1164,23 @@ impl App {
             match item_type {
                 TreeItemType::Note => {
                     self.notebook.remove_note(item_id);
+                    self.link_index.remove_note(item_id);
+                    self.enhanced_search.unindex_note(item_id);
+                    self.forget_note_visits(item_id);
                     if let Some(ref current_note) = self.current_note {
                         if current_note.id == item_id {
                             self.current_note = None;
                             self.editor_content.clear();
                         }
                     }
+                    self.refresh_backlinks();
                     self.set_message(format!("Note '{}' deleted", self.delete_item_name));
                 }
+                TreeItemType::SavedSearch => {
+                    self.notebook.saved_searches.retain(|s| s.id != item_id);
+                    self.set_message(format!("Saved search '{}' deleted", self.delete_item_name));
+                }
+                TreeItemType::Pinned | TreeItemType::SavedSearches => return Err("Sections cannot be deleted".to_string()),
                 TreeItemType::Folder => {
                     self.notebook.remove_folder(item_id)?;
                     self.set_message(format!("Folder '{}' deleted", self.delete_item_name));
@@ -444,87 +1225,588 @@ impl App {
         }
     }
 
-    pub fn search_notes(&mut self, query: String) {
-        self.search_query = query.clone();
-        
-        // Use basic search for backward compatibility
-        self.search_results = self.notebook.search_notes(&query).into_iter().cloned().collect();
-        
-        // Also perform enhanced search
-        let search_query = SearchQuery::new(query.clone());
-        match self.enhanced_search.search(&self.notebook, search_query) {
-            Ok(results) => {
-                self.enhanced_search_results = results;
-                let total_matches: usize = self.enhanced_search_results.iter()
-                    .map(|r| r.matches.len())
-                    .sum();
-                    
-                if !self.enhanced_search_results.is_empty() {
-                    // Extract needed data first to avoid borrowing issues
-                    let first_note_id = self.enhanced_search_results[0].note.id;
-                    let first_note_title = self.enhanced_search_results[0].note.title.clone();
-                    let results_count = self.enhanced_search_results.len();
-                    
-                    // Automatically navigate to and open the first search result
-                    self.open_note_by_id(first_note_id);
-                    self.set_message(format!("Found {} notes with {} matches for '{}' - Opened first result: '{}'", 
-                        results_count, total_matches, query, first_note_title));
-                } else {
-                    self.set_message(format!("No matches found for '{}'", query));
-                }
-            }
-            Err(e) => {
-                self.set_message(format!("Search error: {}", e));
-            }
-        }
-    }
-    
-    pub fn enhanced_search_notes(&mut self, query: SearchQuery) {
-        match self.enhanced_search.search(&self.notebook, query) {
-            Ok(results) => {
-                self.enhanced_search_results = results;
-                let total_matches: usize = self.enhanced_search_results.iter()
-                    .map(|r| r.matches.len())
-                    .sum();
-                    
-                if !self.enhanced_search_results.is_empty() {
-                    // Extract needed data first to avoid borrowing issues
-                    let first_note_id = self.enhanced_search_results[0].note.id;
-                    let first_note_title = self.enhanced_search_results[0].note.title.clone();
-                    let results_count = self.enhanced_search_results.len();
-                    
-                    // Automatically navigate to and open the first search result
-                    self.open_note_by_id(first_note_id);
-                    self.set_message(format!("Enhanced search found {} notes with {} matches - Opened first result: '{}'", 
-                        results_count, total_matches, first_note_title));
-                } else {
-                    self.set_message("No matches found".to_string());
-                }
-            }
-            Err(e) => {
-                self.set_message(format!("Search error: {}", e));
-            }
-        }
-    }
-    
-    pub fn get_search_history(&self) -> Vec<&String> {
-        self.enhanced_search.get_search_history()
-    }
-    
-    pub fn clear_search_history(&mut self) {
-        self.enhanced_search.clear_history();
-        self.set_message("Search history cleared".to_string());
+    /// Fold or unfold the Saved Searches section
+    pub fn toggle_saved_searches_section(&mut self) {
+        self.saved_searches_collapsed = !self.saved_searches_collapsed;
+        self.refresh_tree_view();
     }
 
-    pub fn navigate_up(&mut self) {
-        if self.selected_folder_index > 0 {
-            self.selected_folder_index -= 1;
+    /// Show or hide the results of the selected saved search
+    pub fn toggle_saved_search_expansion(&mut self) {
+        let Some(id) = self.get_selected_item().filter(|item| item.item_type == TreeItemType::SavedSearch).map(|item| item.id) else {
+            return;
+        };
+        if let Some(search) = self.notebook.saved_searches.iter_mut().find(|s| s.id == id) {
+            search.expanded = !search.expanded;
+            self.refresh_tree_view();
         }
     }
 
-    pub fn navigate_down(&mut self) {
-        if self.selected_folder_index < self.folder_tree_items.len().saturating_sub(1) {
-            self.selected_folder_index += 1;
+    /// Save the last search query as a smart folder in the tree
+    pub fn save_search(&mut self, name: &str) -> Result<(), String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Saved search name cannot be empty".to_string());
+        }
+        if self.search_query.trim().is_empty() {
+            return Err("Search first (/ or Ctrl+F), then save the query".to_string());
+        }
+        let lowercase = name.to_lowercase();
+        if self.notebook.saved_searches.iter().any(|s| s.name.to_lowercase() == lowercase) {
+            return Err(format!("A saved search named '{}' already exists", name));
+        }
+        
+        let mut search = SavedSearch::new(name.to_string(), self.search_query.clone());
+        search.expanded = true;
+        let id = search.id;
+        self.notebook.saved_searches.push(search);
+        self.saved_searches_collapsed = false;
+        self.refresh_tree_view();
+        if let Some(index) = self.folder_tree_items.iter().position(|item| item.id == id) {
+            self.selected_folder_index = index;
+        }
+        self.set_operation_success(format!("Saved search '{}' for '{}'", name, self.search_query), Some("🔍".to_string()));
+        Ok(())
+    }
+
+    /// Open the query of the selected saved search for editing
+    pub fn start_saved_search_edit(&mut self) -> Result<(), String> {
+        let id = self.get_selected_item()
+            .filter(|item| item.item_type == TreeItemType::SavedSearch)
+            .map(|item| item.id)
+            .ok_or("Select a saved search to edit its query")?;
+        let search = self.notebook.saved_searches.iter().find(|s| s.id == id).ok_or("Saved search no longer exists")?;
+        self.input_buffer = search.query.clone();
+        self.saved_search_edit_id = Some(id);
+        self.mode = AppMode::SavedSearchEdit;
+        Ok(())
+    }
+
+    pub fn submit_saved_search_edit(&mut self) -> Result<(), String> {
+        let query = self.input_buffer.trim().to_string();
+        if query.is_empty() {
+            return Err("Query cannot be empty".to_string());
+        }
+        let id = self.saved_search_edit_id.ok_or("No saved search is being edited")?;
+        // Check the query before saving it, rather than listing nothing
+        SearchQuery::parse(&query)?;
+        
+        let search = self.notebook.saved_searches.iter_mut().find(|s| s.id == id).ok_or("Saved search no longer exists")?;
+        search.query = query;
+        search.expanded = true;
+        let name = search.name.clone();
+        self.cancel_saved_search_edit();
+        self.refresh_tree_view_keeping_selection();
+        self.set_operation_success(format!("Updated saved search '{}'", name), Some("🔍".to_string()));
+        Ok(())
+    }
+
+    pub fn cancel_saved_search_edit(&mut self) {
+        self.saved_search_edit_id = None;
+        self.input_buffer.clear();
+        self.mode = AppMode::Normal;
+    }
+
+    /// Fold or unfold the Pinned section
+    pub fn toggle_pinned_section(&mut self) {
+        self.pinned_collapsed = !self.pinned_collapsed;
+        self.refresh_tree_view();
+    }
+
+    /// Pin or unpin the open note, or the selected note when the tree is focused
+    pub fn toggle_pin(&mut self) -> Result<(), String> {
+        let note_id = match self.current_note {
+            Some(ref note) if self.focused_pane != FocusedPane::Folders => note.id,
+            _ => match self.get_selected_item() {
+                Some(item) if item.item_type == TreeItemType::Note => item.id,
+                _ => return Err("Select a note to pin".to_string()),
+            },
+        };
+        let note = self.notebook.notes.get(&note_id).ok_or("Note no longer exists")?;
+        let title = note.title.clone();
+        let pinned_at = match note.pinned_at {
+            Some(_) => None,
+            None => Some(chrono::Utc::now()),
+        };
+        
+        self.set_pinned_at(note_id, pinned_at);
+        self.refresh_tree_view_keeping_selection();
+        if pinned_at.is_some() {
+            self.set_operation_success(format!("Pinned '{}'", title), Some("📌".to_string()));
+        } else {
+            self.set_message(format!("Unpinned '{}'", title));
+        }
+        Ok(())
+    }
+
+    /// Move the selected pinned note up or down within the Pinned section
+    pub fn move_pinned_note(&mut self, direction: isize) -> Result<(), String> {
+        let note_id = match self.get_selected_item() {
+            Some(item) if item.section == TreeSection::Pinned && item.item_type == TreeItemType::Note => item.id,
+            _ => return Err("Select a note in the Pinned section to reorder it".to_string()),
+        };
+        let pinned: Vec<(Uuid, Option<chrono::DateTime<chrono::Utc>>)> = self.notebook
+            .pinned_notes()
+            .into_iter()
+            .map(|note| (note.id, note.pinned_at))
+            .collect();
+        let Some(index) = pinned.iter().position(|(id, _)| *id == note_id) else {
+            return Ok(());
+        };
+        let Some(other) = index.checked_add_signed(direction).and_then(|i| pinned.get(i)) else {
+            return Ok(());
+        };
+        
+        // Swapping pin times swaps the two notes' places in the section
+        self.set_pinned_at(note_id, other.1);
+        self.set_pinned_at(other.0, pinned[index].1);
+        self.refresh_tree_view_keeping_selection();
+        Ok(())
+    }
+
+    fn set_pinned_at(&mut self, note_id: Uuid, pinned_at: Option<chrono::DateTime<chrono::Utc>>) {
+        if let Some(note) = self.notebook.notes.get_mut(&note_id) {
+            note.pinned_at = pinned_at;
+        }
+        // The open note is saved from its own copy, which must not undo the pin
+        if let Some(note) = self.current_note.as_mut().filter(|n| n.id == note_id) {
+            note.pinned_at = pinned_at;
+        }
+    }
+
+    /// Rebuild the tree and reselect the item that was selected, in the same section if it is still there
+    fn refresh_tree_view_keeping_selection(&mut self) {
+        let selected = self.get_selected_item().map(|item| (item.id, item.section));
+        self.refresh_tree_view();
+        if let Some((id, section)) = selected {
+            let index = self.folder_tree_items
+                .iter()
+                .position(|item| item.id == id && item.section == section)
+                .or_else(|| self.folder_tree_items.iter().position(|item| item.id == id));
+            if let Some(index) = index {
+                self.selected_folder_index = index;
+            }
+        }
+        self.selected_folder_index = self.selected_folder_index.min(self.folder_tree_items.len().saturating_sub(1));
+    }
+
+    pub fn search_notes(&mut self, query: String) {
+        self.search_query = query.clone();
+        
+        match self.enhanced_search.search(&self.notebook, SearchQuery::words(&query)) {
+            Ok(results) => self.show_search_results(results, &format!("'{}'", query)),
+            Err(e) => {
+                self.set_message(format!("Search error: {}", e));
+            }
+        }
+    }
+    
+    pub fn enhanced_search_notes(&mut self, query: SearchQuery) {
+        match self.enhanced_search.search(&self.notebook, query) {
+            Ok(results) => self.show_search_results(results, "the query"),
+            Err(e) => {
+                self.set_message(format!("Search error: {}", e));
+            }
+        }
+    }
+    
+    /// Fill the search results panel and open the first match
+    fn show_search_results(&mut self, results: Vec<SearchResult>, described: &str) {
+        self.enhanced_search_results = results;
+        self.search_result_selected = 0;
+        if self.enhanced_search_results.is_empty() {
+            self.set_message(format!("No matches found for {}", described));
+            return;
+        }
+        
+        let total_matches: usize = self.enhanced_search_results.iter()
+            .map(|r| r.matches.len())
+            .sum();
+        let results_count = self.enhanced_search_results.len();
+        self.search_results_enabled = true;
+        // n / N go through these results rather than an earlier find in the note
+        self.find_query.clear();
+        self.open_search_result(0);
+        self.focused_pane = FocusedPane::SearchResults;
+        self.set_message(format!("Found {} notes with {} matches for {} - n/N to step through matches", 
+            results_count, total_matches, described));
+    }
+    
+    /// Rows of the search results panel: each match, or the note alone when it matched without text
+    pub fn search_result_rows(&self) -> Vec<(usize, Option<usize>)> {
+        self.enhanced_search_results
+            .iter()
+            .enumerate()
+            .flat_map(|(i, result)| {
+                let rows: Vec<(usize, Option<usize>)> = if result.matches.is_empty() {
+                    vec![(i, None)]
+                } else {
+                    (0..result.matches.len()).map(|m| (i, Some(m))).collect()
+                };
+                rows
+            })
+            .collect()
+    }
+    
+    /// Show or hide the search results panel
+    pub fn toggle_search_results(&mut self) {
+        if self.search_results_enabled {
+            self.close_search_results();
+        } else if self.enhanced_search_results.is_empty() {
+            self.set_message("No search results - search with / or Ctrl+F".to_string());
+        } else {
+            self.search_results_enabled = true;
+            self.focused_pane = FocusedPane::SearchResults;
+        }
+    }
+    
+    pub fn close_search_results(&mut self) {
+        self.search_results_enabled = false;
+        if self.focused_pane == FocusedPane::SearchResults {
+            self.focused_pane = FocusedPane::Editor;
+        }
+    }
+    
+    pub fn next_search_result(&mut self) {
+        if self.search_result_selected + 1 < self.search_result_rows().len() {
+            self.search_result_selected += 1;
+        }
+    }
+    
+    pub fn previous_search_result(&mut self) {
+        self.search_result_selected = self.search_result_selected.saturating_sub(1);
+    }
+    
+    /// Title and tag matches have no place in the text, so their target has no position
+    fn search_result_target(&self, row: usize) -> Option<SearchTarget> {
+        let (result_index, match_index) = self.search_result_rows().get(row).copied()?;
+        let result = &self.enhanced_search_results[result_index];
+        let position = match_index
+            .map(|m| &result.matches[m])
+            .filter(|m| m.match_type == MatchType::Content)
+            .map(|m| (m.line_number, m.start_offset));
+        Some(SearchTarget { note_id: result.note.id, title: result.note.title.clone(), position })
+    }
+    
+    /// Put the cursor on a match in the open note
+    fn move_to_match(&mut self, position: Option<(usize, usize)>) {
+        if let Some((line, column)) = position {
+            self.jump_to_line(line);
+            self.editor_cursor.1 = column as u16;
+            self.clamp_cursor_column();
+        }
+    }
+    
+    /// Open the note of a search results row with the cursor on its match
+    pub fn open_search_result(&mut self, row: usize) {
+        let Some(SearchTarget { note_id, title, position }) = self.search_result_target(row) else {
+            return;
+        };
+        
+        if !self.notebook.notes.contains_key(&note_id) {
+            self.set_message(format!("'{}' no longer exists - search again", title));
+            return;
+        }
+        if self.save_status == SaveStatus::Modified {
+            if let Err(e) = self.save_current_note() {
+                self.set_message(e);
+                return;
+            }
+        }
+        
+        self.search_result_selected = row;
+        self.open_note_by_id(note_id);
+        self.move_to_match(position);
+    }
+    
+    /// Open the find bar for the open note, remembering the cursor to come back to on Esc
+    pub fn start_find(&mut self) -> Result<(), String> {
+        if self.current_note.is_none() {
+            return Err("No note open to find in".to_string());
+        }
+        self.focused_pane = FocusedPane::Editor;
+        self.find_origin = Some((self.editor_cursor, self.editor_scroll));
+        self.input_buffer.clear();
+        self.mode = AppMode::Find;
+        Ok(())
+    }
+    
+    /// Find the text typed so far, moving to its first match from where the find started
+    pub fn find_input_changed(&mut self) {
+        self.find_query = self.input_buffer.clone();
+        let Some((cursor, scroll)) = self.find_origin else {
+            return;
+        };
+        self.editor_cursor = cursor;
+        self.editor_scroll = scroll;
+        let matches = self.find_matches();
+        let from = (cursor.0 as usize, cursor.1 as usize);
+        if let Some(m) = matches.iter().find(|m| (m.line, m.start) >= from).or(matches.first()) {
+            self.move_to_find_match(*m);
+        }
+    }
+    
+    /// Enter: keep the cursor on the match, with n / N going through the rest
+    pub fn finish_find(&mut self) {
+        self.mode = AppMode::Normal;
+        self.find_origin = None;
+        self.input_buffer.clear();
+        if self.find_query.is_empty() {
+            return;
+        }
+        match self.find_position() {
+            Some((_, 0)) | None => self.set_message(format!("No matches for '{}' in this note", self.find_query)),
+            Some((index, total)) => self.set_message(format!("Match {}/{} - n/N for the next or previous, Esc to clear", index, total)),
+        }
+    }
+    
+    /// Esc: drop the find and put the cursor back
+    pub fn cancel_find(&mut self) {
+        if let Some((cursor, scroll)) = self.find_origin.take() {
+            self.editor_cursor = cursor;
+            self.editor_scroll = scroll;
+        }
+        self.find_query.clear();
+        self.input_buffer.clear();
+        self.mode = AppMode::Normal;
+    }
+    
+    /// Stop highlighting the find matches, giving n / N back to notebook search
+    pub fn clear_find(&mut self) {
+        self.find_query.clear();
+        self.set_message("Find cleared".to_string());
+    }
+    
+    pub fn find_matches(&self) -> Vec<FindMatch> {
+        find::find_all(&self.editor_content, &self.find_query)
+    }
+    
+    /// Number of matches up to the cursor and in total, as in "3/17"
+    pub fn find_position(&self) -> Option<(usize, usize)> {
+        if self.find_query.is_empty() {
+            return None;
+        }
+        let matches = self.find_matches();
+        let cursor = (self.editor_cursor.0 as usize, self.editor_cursor.1 as usize);
+        let index = matches.iter().filter(|m| (m.line, m.start) <= cursor).count();
+        Some((index, matches.len()))
+    }
+    
+    fn move_to_find_match(&mut self, m: FindMatch) {
+        self.jump_to_line(m.line);
+        self.editor_cursor.1 = m.start as u16;
+        self.clamp_cursor_column();
+    }
+    
+    /// Move the cursor to the next or previous match in the note, wrapping at the ends (n / N)
+    pub fn step_find(&mut self, direction: i32) {
+        if self.find_query.is_empty() {
+            self.set_message("Nothing to find - press / in the editor first".to_string());
+            return;
+        }
+        let matches = self.find_matches();
+        let cursor = (self.editor_cursor.0 as usize, self.editor_cursor.1 as usize);
+        let (target, wrapped) = if direction > 0 {
+            match matches.iter().find(|m| (m.line, m.start) > cursor) {
+                Some(m) => (Some(m), false),
+                None => (matches.first(), true),
+            }
+        } else {
+            match matches.iter().rev().find(|m| (m.line, m.start) < cursor) {
+                Some(m) => (Some(m), false),
+                None => (matches.last(), true),
+            }
+        };
+        let Some(&target) = target else {
+            self.set_message(format!("No matches for '{}' in this note", self.find_query));
+            return;
+        };
+        
+        self.move_to_find_match(target);
+        if wrapped && matches.len() > 1 {
+            let end = if direction > 0 { "bottom, continuing at the top" } else { "top, continuing at the bottom" };
+            self.set_message(format!("Find hit the {}", end));
+        }
+    }
+    
+    /// Open the quick search, remembering where the editor is to come back to on Esc
+    pub fn start_incremental_search(&mut self) {
+        // Unsaved edits would be lost when a match in another note is shown
+        if self.save_status == SaveStatus::Modified {
+            if let Err(e) = self.save_current_note() {
+                self.set_message(e);
+                return;
+            }
+        }
+        self.search_origin = Some(SearchOrigin {
+            visit: self.current_note.as_ref().map(|note| NoteVisit {
+                note_id: note.id,
+                cursor: self.editor_cursor,
+                scroll: self.editor_scroll,
+            }),
+            focused_pane: self.focused_pane.clone(),
+            results: self.enhanced_search_results.clone(),
+            result_selected: self.search_result_selected,
+            results_enabled: self.search_results_enabled,
+        });
+        self.search_pending_since = None;
+        self.input_buffer.clear();
+        self.mode = AppMode::Search;
+    }
+    
+    /// Run the incremental search once typing pauses
+    pub fn search_input_changed(&mut self) {
+        self.search_pending_since = Some(Instant::now());
+    }
+    
+    /// How long until the pending incremental search is due
+    pub fn pending_search_wait(&self) -> Option<Duration> {
+        self.search_pending_since.map(|since| SEARCH_DEBOUNCE.saturating_sub(since.elapsed()))
+    }
+    
+    pub fn run_pending_search(&mut self) {
+        if self.pending_search_wait() == Some(Duration::ZERO) {
+            self.search_pending_since = None;
+            self.preview_search();
+        }
+    }
+    
+    /// Show the results for the query typed so far, with the editor on the first match
+    fn preview_search(&mut self) {
+        let query = self.input_buffer.trim().to_string();
+        if query.is_empty() {
+            self.restore_search_origin();
+            return;
+        }
+        
+        let results = match self.enhanced_search.find(&self.notebook, &SearchQuery::words(&query)) {
+            Ok(results) => results,
+            Err(e) => {
+                self.set_message(format!("Search error: {}", e));
+                return;
+            }
+        };
+        let total_matches: usize = results.iter().map(|r| r.matches.len()).sum();
+        let results_count = results.len();
+        self.enhanced_search_results = results;
+        self.search_result_selected = 0;
+        self.search_results_enabled = true;
+        
+        match self.search_result_target(0) {
+            Some(SearchTarget { note_id, position, .. }) => {
+                if self.current_note.as_ref().map(|n| n.id) != Some(note_id) {
+                    self.show_note(note_id);
+                }
+                self.move_to_match(position);
+                self.set_message(format!("{} notes with {} matches", results_count, total_matches));
+            }
+            None => {
+                self.restore_search_position();
+                self.set_message(format!("No matches for '{}'", query));
+            }
+        }
+    }
+    
+    /// Go back to the note, cursor and scroll from before the incremental search
+    fn restore_search_position(&mut self) {
+        let Some(origin) = self.search_origin.clone() else {
+            return;
+        };
+        match origin.visit {
+            Some(visit) => {
+                if self.current_note.as_ref().map(|n| n.id) != Some(visit.note_id) {
+                    self.show_note(visit.note_id);
+                }
+                self.editor_cursor = visit.cursor;
+                self.editor_scroll = visit.scroll;
+                self.clamp_cursor_row();
+            }
+            None => {
+                self.current_note = None;
+                self.editor_content.clear();
+                self.editor_cursor = (0, 0);
+                self.editor_scroll = 0;
+                self.refresh_backlinks();
+            }
+        }
+        self.focused_pane = origin.focused_pane;
+    }
+    
+    /// Put back the position and the results from before the incremental search
+    fn restore_search_origin(&mut self) {
+        self.restore_search_position();
+        if let Some(origin) = self.search_origin.clone() {
+            self.enhanced_search_results = origin.results;
+            self.search_result_selected = origin.result_selected;
+            self.search_results_enabled = origin.results_enabled;
+        }
+    }
+    
+    /// Esc: leave the quick search where the editor was before it
+    pub fn cancel_incremental_search(&mut self) {
+        self.restore_search_origin();
+        self.search_origin = None;
+        self.search_pending_since = None;
+        self.input_buffer.clear();
+        self.mode = AppMode::Normal;
+    }
+    
+    /// Enter: search for the query and stay on its first match
+    pub fn finish_incremental_search(&mut self) {
+        let query = std::mem::take(&mut self.input_buffer);
+        self.mode = AppMode::Normal;
+        self.search_pending_since = None;
+        if query.trim().is_empty() {
+            self.restore_search_origin();
+            self.search_origin = None;
+            return;
+        }
+        // Open the match from the original note so it is recorded in the jump list
+        self.restore_search_position();
+        self.search_origin = None;
+        self.search_notes(query);
+        // Stay in the editor on the match rather than moving to the results panel
+        if self.focused_pane == FocusedPane::SearchResults {
+            self.focused_pane = FocusedPane::Editor;
+        }
+    }
+    
+    /// Open the next or previous match across all notes of the last search (n / N)
+    pub fn step_search_match(&mut self, direction: i32) {
+        let count = self.search_result_rows().len();
+        if count == 0 {
+            self.set_message("No search results - search with / or Ctrl+F".to_string());
+            return;
+        }
+        let row = (self.search_result_selected as i32 + direction).rem_euclid(count as i32) as usize;
+        self.open_search_result(row);
+        let wrapped = count > 1 && ((direction > 0 && row == 0) || (direction < 0 && row == count - 1));
+        let mut message = format!("Match {} of {}", row + 1, count);
+        if wrapped {
+            message.push_str(if direction > 0 { " - wrapped to the first" } else { " - wrapped to the last" });
+        }
+        self.set_message(message);
+    }
+    
+    pub fn get_search_history(&self) -> Vec<&String> {
+        self.enhanced_search.get_search_history()
+    }
+    
+    #[allow(dead_code)]
+    pub fn clear_search_history(&mut self) {
+        self.enhanced_search.clear_history();
+        self.set_message("Search history cleared".to_string());
+    }
+
+    pub fn navigate_up(&mut self) {
+        if self.selected_folder_index > 0 {
+            self.selected_folder_index -= 1;
+        }
+    }
+
+    pub fn navigate_down(&mut self) {
+        if self.selected_folder_index < self.folder_tree_items.len().saturating_sub(1) {
+            self.selected_folder_index += 1;
         }
     }
 
@@ -562,6 +1844,7 @@ impl App {
         self.set_message(message);
     }
     
+    #[allow(dead_code)]
     pub fn set_operation_info(&mut self, message: String, icon: Option<String>) {
         self.operation_result = Some(OperationResult::Info {
             message: message.clone(),
@@ -595,6 +1878,28 @@ impl App {
     
     /// Check if autocompletion should be triggered and update state
     pub fn update_autocompletion(&mut self) {
+        // An unfinished [[ offers note titles instead of markdown completions
+        let line = self.line_text(self.editor_cursor.0 as usize);
+        let col = (self.editor_cursor.1 as usize).min(line.len());
+        if let Some((open, query)) = line.get(..col).and_then(links::open_link_query) {
+            let suggestions = self.link_suggestions(query);
+            if suggestions.is_empty() {
+                self.autocomplete_state.deactivate();
+            } else {
+                self.autocomplete_state.activate(suggestions, open);
+            }
+            return;
+        }
+        
+        // #tag offers existing tags; without a match it falls through to the markdown completions
+        if let Some((hash, query)) = line.get(..col).and_then(tags::open_hashtag_query) {
+            let suggestions = self.tag_completions(query);
+            if !suggestions.is_empty() {
+                self.autocomplete_state.activate(suggestions, hash);
+                return;
+            }
+        }
+        
         if let Some(completions) = self.markdown_autocomplete.check_for_completions(
             &self.editor_content,
             self.editor_cursor.0 as usize,
@@ -612,7 +1917,7 @@ impl App {
             return false;
         }
         
-        if let Some(suggestion) = self.autocomplete_state.get_selected_suggestion() {
+        if let Some(suggestion) = self.autocomplete_state.get_selected_suggestion().cloned() {
             let lines: Vec<&str> = self.editor_content.lines().collect();
             if self.editor_cursor.0 as usize >= lines.len() {
                 return false;
@@ -623,15 +1928,40 @@ impl App {
             
             // Calculate the absolute position in the content
             let trigger_abs_pos = line_start + self.autocomplete_state.trigger_start_pos;
-            let cursor_abs_pos = line_start + self.editor_cursor.1 as usize;
+            let mut cursor_abs_pos = line_start + self.editor_cursor.1 as usize;
+            
+            // A link completion brings its own closing brackets
+            if suggestion.trigger == LINK_TRIGGER && self.editor_content[cursor_abs_pos..].starts_with("]]") {
+                cursor_abs_pos += 2;
+            }
+            
+            // Snippets are expanded into text and tab stops
+            let (completion, stops) = match suggestion.snippet {
+                Some(ref body) => {
+                    let expansion = snippets::expand(body, &self.snippet_context());
+                    (expansion.text, Some(expansion.stops))
+                }
+                None => (suggestion.completion.clone(), None),
+            };
             
             // Remove the trigger text and insert the completion
             let mut new_content = String::new();
             new_content.push_str(&self.editor_content[..trigger_abs_pos]);
-            new_content.push_str(&suggestion.completion);
+            new_content.push_str(&completion);
             new_content.push_str(&self.editor_content[cursor_abs_pos..]);
             
             self.editor_content = new_content;
+            self.autocomplete_state.deactivate();
+            self.mark_modified();
+            
+            if let Some(stops) = stops {
+                let stops = stops
+                    .into_iter()
+                    .map(|s| TabStop { start: trigger_abs_pos + s.start, end: trigger_abs_pos + s.end })
+                    .collect();
+                self.start_snippet_session(stops);
+                return true;
+            }
             
             // Update cursor position
             let completion_end_pos = trigger_abs_pos + suggestion.completion.len();
@@ -643,43 +1973,632 @@ impl App {
             
             // Convert absolute position back to line/column
             self.update_cursor_from_absolute_position(new_cursor_pos);
-            
-            self.autocomplete_state.deactivate();
-            self.mark_modified();
             return true;
         }
         
         false
     }
     
-    /// Move to next autocomplete suggestion
-    pub fn next_autocomplete_suggestion(&mut self) {
-        self.autocomplete_state.next_suggestion();
-    }
-    
-    /// Move to previous autocomplete suggestion
-    pub fn previous_autocomplete_suggestion(&mut self) {
-        self.autocomplete_state.previous_suggestion();
+    /// Note titles matching a partial `[[` link, best match first
+    fn link_suggestions(&self, query: &str) -> Vec<AutocompleteSuggestion> {
+        let current_id = self.current_note.as_ref().map(|n| n.id);
+        let mut matches: Vec<(i64, &Note)> = self.notebook.notes
+            .values()
+            .filter(|note| Some(note.id) != current_id)
+            .filter_map(|note| fuzzy::fuzzy_match(query, &note.title).map(|m| (m.score, note)))
+            .collect();
+        matches.sort_by(|(a_score, a), (b_score, b)| {
+            b_score.cmp(a_score).then_with(|| b.modified_at.cmp(&a.modified_at))
+        });
+        
+        matches
+            .into_iter()
+            .take(MAX_LINK_SUGGESTIONS)
+            .map(|(_, note)| AutocompleteSuggestion {
+                trigger: LINK_TRIGGER.to_string(),
+                completion: format!("[[{}]]", note.title),
+                description: note.title.clone(),
+                cursor_offset: 0,
+                snippet: None,
+            })
+            .collect()
     }
     
-    /// Cancel autocompletion
-    pub fn cancel_autocomplete(&mut self) {
-        self.autocomplete_state.deactivate();
+    /// Existing tags matching a partial inline `#tag`, best match first
+    fn tag_completions(&self, query: &str) -> Vec<AutocompleteSuggestion> {
+        tags::complete_tag(&self.notebook, query, &[])
+            .into_iter()
+            .take(MAX_TAG_SUGGESTIONS)
+            .map(|tag| AutocompleteSuggestion {
+                trigger: TAG_TRIGGER.to_string(),
+                completion: format!("#{}", tag),
+                description: format!("Tag #{}", tag),
+                cursor_offset: 0,
+                snippet: None,
+            })
+            .collect()
     }
     
-    /// Get the absolute character position of the start of a line
-    fn get_line_start_position(&self, line_index: usize) -> usize {
-        let lines: Vec<&str> = self.editor_content.lines().collect();
-        let mut pos = 0;
-        for i in 0..line_index.min(lines.len()) {
-            pos += lines[i].len() + 1; // +1 for the newline character
+    /// Open the note linked under the cursor, jumping to the linked heading.
+    /// Returns false if there is no link under the cursor.
+    pub fn follow_link_at_cursor(&mut self) -> bool {
+        let line = self.line_text(self.editor_cursor.0 as usize);
+        let Some(link) = links::link_at(line, self.editor_cursor.1 as usize) else {
+            return false;
+        };
+        let Some(note_id) = links::resolve(&self.notebook, &link.target) else {
+            self.set_operation_error(format!("No note named '{}'", link.target), Some("🔗".to_string()));
+            return true;
+        };
+        
+        // Keep unsaved edits before switching notes
+        if self.save_status == SaveStatus::Modified {
+            if let Err(e) = self.save_current_note() {
+                self.set_message(e);
+                return true;
+            }
+        }
+        
+        if self.current_note.as_ref().map(|n| n.id) != Some(note_id) {
+            self.open_note_by_id(note_id);
+        }
+        let heading = link.heading.as_deref().and_then(|h| links::heading_line(&self.editor_content, h));
+        match (heading, &link.heading) {
+            (Some(row), _) => self.jump_to_line(row),
+            (None, Some(h)) => self.set_message(format!("Heading '{}' not found in '{}'", h, link.target)),
+            (None, None) => {}
         }
-        pos
+        true
+    }
+    
+    /// Load user snippets from the config dir, replacing any loaded before.
+    /// Returns the number of snippets; a missing file means none.
+    pub fn load_snippets(&mut self) -> Result<usize, String> {
+        let path = config::snippets_file();
+        let snippets = if path.exists() {
+            snippets::load_snippets(&path)?
+        } else {
+            Vec::new()
+        };
+        self.markdown_autocomplete.set_snippets(snippets);
+        Ok(self.markdown_autocomplete.snippet_count())
+    }
+    
+    /// Variable values for expanding a snippet on the cursor line
+    fn snippet_context(&self) -> SnippetContext {
+        let line = self.line_text(self.editor_cursor.0 as usize);
+        let indent = &line[..line.len() - line.trim_start().len()];
+        let note = self.current_note.as_ref();
+        SnippetContext {
+            note_title: note.map(|n| n.title.clone()).unwrap_or_default(),
+            folder: note
+                .and_then(|n| n.folder_id)
+                .and_then(|id| self.notebook.folders.get(&id))
+                .map(|f| f.name.clone())
+                .unwrap_or_default(),
+            indent: indent.to_string(),
+            date: None,
+        }
+    }
+    
+    /// Put the cursor on the first tab stop of an expanded snippet,
+    /// keeping the session open while there are more stops to visit
+    fn start_snippet_session(&mut self, stops: Vec<TabStop>) {
+        let first = stops[0];
+        self.snippet_session = (stops.len() > 1).then_some(SnippetSession {
+            stops,
+            current: 0,
+            content_len: self.editor_content.len(),
+        });
+        self.update_cursor_from_absolute_position(first.start);
+        self.adjust_scroll_to_cursor();
+    }
+    
+    /// Move to the next or previous tab stop of the active snippet.
+    /// Returns false if no snippet is being filled in.
+    pub fn snippet_jump(&mut self, forward: bool) -> bool {
+        let content_len = self.editor_content.len();
+        let Some(session) = self.snippet_session.as_mut() else {
+            return false;
+        };
+        session.sync(content_len);
+        
+        session.current = if forward {
+            session.current + 1
+        } else {
+            session.current.saturating_sub(1)
+        };
+        let stop = session.stops[session.current];
+        let finished = session.current + 1 >= session.stops.len();
+        
+        if finished || stop.end > content_len {
+            self.snippet_session = None;
+        }
+        self.update_cursor_from_absolute_position(stop.start.min(content_len));
+        self.adjust_scroll_to_cursor();
+        true
+    }
+    
+    /// Remove the untouched placeholder at the cursor so typing replaces it.
+    /// Returns true if a placeholder was removed.
+    pub fn take_snippet_placeholder(&mut self) -> bool {
+        let cursor = self.cursor_byte_offset();
+        let content_len = self.editor_content.len();
+        let Some(session) = self.snippet_session.as_mut() else {
+            return false;
+        };
+        if !session.placeholder_pending(cursor, content_len) {
+            return false;
+        }
+        
+        let stop = session.stops[session.current];
+        self.editor_content.replace_range(stop.start..stop.end, "");
+        session.sync(self.editor_content.len());
+        self.mark_modified();
+        true
+    }
+    
+    /// Move to next autocomplete suggestion
+    pub fn next_autocomplete_suggestion(&mut self) {
+        self.autocomplete_state.next_suggestion();
+    }
+    
+    /// Move to previous autocomplete suggestion
+    pub fn previous_autocomplete_suggestion(&mut self) {
+        self.autocomplete_state.previous_suggestion();
+    }
+    
+    /// Cancel autocompletion
+    pub fn cancel_autocomplete(&mut self) {
+        self.autocomplete_state.deactivate();
+    }
+    
+    /// Remember the current buffer so the next edit can be undone
+    pub fn push_undo_snapshot(&mut self) {
+        if self.undo_stack.last().is_some_and(|s| s.content == self.editor_content) {
+            return;
+        }
+        self.undo_stack.push(EditorSnapshot {
+            content: self.editor_content.clone(),
+            cursor: self.editor_cursor,
+        });
+        if self.undo_stack.len() > MAX_UNDO_LEVELS {
+            self.undo_stack.remove(0);
+        }
+    }
+    
+    /// Restore the buffer to the most recent snapshot
+    pub fn undo(&mut self) {
+        // Skip snapshots identical to the current buffer (e.g. an insert session with no edits)
+        while let Some(snapshot) = self.undo_stack.pop() {
+            if snapshot.content != self.editor_content {
+                self.editor_content = snapshot.content;
+                self.editor_cursor = snapshot.cursor;
+                self.autocomplete_state.deactivate();
+                self.snippet_session = None;
+                self.adjust_scroll_to_cursor();
+                self.mark_modified();
+                self.set_message("Undid last change".to_string());
+                return;
+            }
+        }
+        self.set_message("Already at oldest change".to_string());
+    }
+    
+    /// Paste a block of text at the cursor as a single undoable edit
+    pub fn paste_text(&mut self, text: &str) {
+        let text = text.replace("\r\n", "\n").replace('\r', "\n");
+        if text.is_empty() {
+            return;
+        }
+        
+        self.push_undo_snapshot();
+        self.insert_at_cursor(&text);
+        // Pasted text is taken literally, so no completion popup
+        self.autocomplete_state.deactivate();
+        self.adjust_scroll_to_cursor();
+        
+        let line_count = text.lines().count();
+        if line_count > 1 {
+            self.set_message(format!("Pasted {} lines", line_count));
+        }
+    }
+    
+    /// Byte offset of the cursor within the editor content
+    pub fn cursor_byte_offset(&self) -> usize {
+        let row = self.editor_cursor.0 as usize;
+        let line = self.line_text(row);
+        let line_start = self.get_line_start_position(row).min(self.editor_content.len());
+        let mut col = (self.editor_cursor.1 as usize).min(line.len());
+        while !line.is_char_boundary(col) {
+            col -= 1;
+        }
+        line_start + col
+    }
+    
+    /// Insert text at the cursor and move the cursor past it
+    pub fn insert_at_cursor(&mut self, text: &str) {
+        let pos = self.cursor_byte_offset();
+        self.editor_content.insert_str(pos, text);
+        self.update_cursor_from_absolute_position(pos + text.len());
+        self.mark_modified();
+    }
+    
+    /// Delete the character before the cursor, joining lines at column 0
+    pub fn delete_before_cursor(&mut self) {
+        let pos = self.cursor_byte_offset();
+        if let Some(prev) = self.editor_content[..pos].chars().next_back() {
+            let start = pos - prev.len_utf8();
+            self.editor_content.replace_range(start..pos, "");
+            self.update_cursor_from_absolute_position(start);
+            self.mark_modified();
+        }
+    }
+    
+    /// Move the cursor one character left within the current line
+    pub fn move_cursor_left(&mut self) {
+        let pos = self.cursor_byte_offset();
+        let line_start = self.get_line_start_position(self.editor_cursor.0 as usize);
+        if pos > line_start {
+            let prev = self.editor_content[..pos].chars().next_back().map_or(1, char::len_utf8);
+            self.editor_cursor.1 = (pos - prev - line_start) as u16;
+        }
+    }
+    
+    /// Move the cursor one character right within the current line
+    pub fn move_cursor_right(&mut self) {
+        let pos = self.cursor_byte_offset();
+        if let Some(next) = self.editor_content[pos..].chars().next().filter(|&c| c != '\n') {
+            let line_start = self.get_line_start_position(self.editor_cursor.0 as usize);
+            self.editor_cursor.1 = (pos + next.len_utf8() - line_start) as u16;
+        }
+    }
+    
+    /// Insert a newline, continuing markdown lists, block quotes and indentation
+    pub fn insert_newline(&mut self) {
+        let row = self.editor_cursor.0 as usize;
+        let col = self.cursor_byte_offset() - self.get_line_start_position(row);
+        let action = {
+            let lines: Vec<&str> = self.editor_content.split('\n').collect();
+            lists::on_enter(&lines, row, col)
+        };
+        
+        match action {
+            EnterAction::Insert(text) => self.insert_at_cursor(&text),
+            EnterAction::ReplaceLine(text) => {
+                self.editor_cursor.1 = text.len() as u16;
+                self.replace_line(row, &text);
+            }
+        }
+    }
+    
+    /// Nest the list item on the cursor line one level deeper.
+    /// Returns false if the line is not a list item.
+    pub fn indent_list_item(&mut self) -> bool {
+        let row = self.editor_cursor.0 as usize;
+        let (line, new_indent) = {
+            let lines: Vec<&str> = self.editor_content.split('\n').collect();
+            match lists::indent_target(&lines, row) {
+                Some(indent) => (lines[row].to_string(), indent),
+                None => return false,
+            }
+        };
+        self.set_line_indent(row, &line, &new_indent);
+        true
+    }
+    
+    /// Move the list item on the cursor line up one nesting level, or
+    /// remove up to four spaces of indentation from a plain line
+    pub fn outdent_line(&mut self) {
+        let row = self.editor_cursor.0 as usize;
+        let (line, new_indent) = {
+            let lines: Vec<&str> = self.editor_content.split('\n').collect();
+            let line = lines.get(row).copied().unwrap_or("");
+            let prefix = LinePrefix::parse(line);
+            let new_indent = if prefix.marker.is_some() {
+                lists::outdent_target(&lines, row)
+            } else {
+                let indent = prefix.indent;
+                let keep = indent.len() - indent.len().min(4);
+                indent[..keep].to_string()
+            };
+            (line.to_string(), new_indent)
+        };
+        self.set_line_indent(row, &line, &new_indent);
+    }
+    
+    /// Replace the indentation of a line, keeping the cursor on the same text
+    fn set_line_indent(&mut self, row: usize, line: &str, new_indent: &str) {
+        let prefix = LinePrefix::parse(line);
+        let indent_start = prefix.quote.len();
+        let old_indent_len = prefix.indent.len();
+        if new_indent == prefix.indent {
+            return;
+        }
+        
+        let new_line = format!("{}{}{}", prefix.quote, new_indent, &line[indent_start + old_indent_len..]);
+        let col = self.editor_cursor.1 as usize;
+        self.editor_cursor.1 = if col >= indent_start + old_indent_len {
+            (col + new_indent.len() - old_indent_len) as u16
+        } else {
+            col.min(indent_start + new_indent.len()) as u16
+        };
+        self.replace_line(row, &new_line);
+    }
+    
+    /// Move the cursor to the start of a line and scroll it into view
+    pub fn jump_to_line(&mut self, row: usize) {
+        let last_row = self.editor_content.split('\n').count().saturating_sub(1);
+        self.editor_cursor = (row.min(last_row) as u16, 0);
+        self.adjust_scroll_to_cursor();
+    }
+    
+    /// Move the cursor up or down by a number of lines
+    pub fn move_cursor_lines(&mut self, delta: i32) {
+        let last_row = self.editor_content.split('\n').count().saturating_sub(1) as i32;
+        let row = (self.editor_cursor.0 as i32 + delta).clamp(0, last_row);
+        self.editor_cursor.0 = row as u16;
+        self.clamp_cursor_column();
+        self.adjust_scroll_to_cursor();
+    }
+    
+    /// Toggle the task checkbox on the cursor line
+    pub fn toggle_task_at_cursor(&mut self) {
+        let row = self.editor_cursor.0 as usize;
+        let today = chrono::Local::now().date_naive();
+        match lists::toggle_checkbox(self.line_text(row), today) {
+            Some(toggled) => {
+                self.push_undo_snapshot();
+                let done = LinePrefix::parse(&toggled).checkbox == Some(true);
+                self.replace_line(row, &toggled);
+                self.set_message(if done { "Task completed" } else { "Task reopened" }.to_string());
+            }
+            None => self.set_message("No list item on this line".to_string()),
+        }
+    }
+    
+    /// The GFM table around the cursor line: first line, last line and parsed table
+    pub fn table_at_cursor(&self) -> Option<(usize, usize, Table)> {
+        let lines: Vec<&str> = self.editor_content.split('\n').collect();
+        let (start, end) = table::find_table(&lines, self.editor_cursor.0 as usize)?;
+        let table = Table::parse(&lines[start..=end])?;
+        Some((start, end, table))
+    }
+    
+    /// Table cell under the cursor as (row, column), where row 0 is the header.
+    /// The separator line counts as the header.
+    pub fn table_cell_at_cursor(&self, start: usize, table: &Table) -> (usize, usize) {
+        let row = (self.editor_cursor.0 as usize - start).saturating_sub(1);
+        let col = table::cell_at(self.line_text(self.editor_cursor.0 as usize), self.editor_cursor.1 as usize);
+        (row, col.min(table.column_count() - 1))
+    }
+    
+    /// Replace the table lines with the realigned table and put the cursor in a cell
+    fn write_table(&mut self, start: usize, end: usize, table: &Table, cell: (usize, usize)) {
+        let rendered = table.render();
+        let from = self.get_line_start_position(start);
+        let to = self.get_line_start_position(end) + self.line_text(end).len();
+        self.editor_content.replace_range(from..to, &rendered.join("\n"));
+        
+        let line = if cell.0 == 0 { 0 } else { cell.0 + 1 };
+        let col = rendered.get(line).map_or(0, |l| table::cell_start(l, cell.1));
+        self.editor_cursor = ((start + line) as u16, col as u16);
+        self.adjust_scroll_to_cursor();
+        self.mark_modified();
+    }
+    
+    /// Realign the table and move to the next cell, adding a row after the last one.
+    /// Returns false if the cursor is not in a table.
+    pub fn table_next_cell(&mut self) -> bool {
+        let Some((start, end, mut table)) = self.table_at_cursor() else {
+            return false;
+        };
+        let on_separator = self.editor_cursor.0 as usize == start + 1;
+        let (row, col) = self.table_cell_at_cursor(start, &table);
+        
+        let target = if on_separator || col + 1 >= table.column_count() {
+            if row + 1 >= table.rows.len() {
+                table.insert_row(row);
+            }
+            (row + 1, 0)
+        } else {
+            (row, col + 1)
+        };
+        self.write_table(start, end, &table, target);
+        true
+    }
+    
+    /// Realign the table and move to the previous cell.
+    /// Returns false if the cursor is not in a table.
+    pub fn table_previous_cell(&mut self) -> bool {
+        let Some((start, end, table)) = self.table_at_cursor() else {
+            return false;
+        };
+        let (row, col) = self.table_cell_at_cursor(start, &table);
+        
+        let target = match (row, col) {
+            (0, 0) => (0, 0),
+            (row, 0) => (row - 1, table.column_count() - 1),
+            (row, col) => (row, col - 1),
+        };
+        self.write_table(start, end, &table, target);
+        true
+    }
+    
+    /// Run a `:table` subcommand on the table under the cursor
+    pub fn table_command(&mut self, args: &str) -> Result<String, String> {
+        let Some((start, end, mut table)) = self.table_at_cursor() else {
+            return Err("Cursor is not in a table".to_string());
+        };
+        let (row, col) = self.table_cell_at_cursor(start, &table);
+        let mut parts = args.split_whitespace();
+        
+        let (target, message) = match parts.next().unwrap_or("format") {
+            "format" => ((row, col), "Table realigned".to_string()),
+            "addrow" => {
+                table.insert_row(row);
+                ((row + 1, col), "Row added".to_string())
+            }
+            "delrow" => {
+                table.delete_row(row)?;
+                ((row.min(table.rows.len() - 1), col), "Row deleted".to_string())
+            }
+            "addcol" => {
+                table.insert_column(col);
+                ((row, col + 1), "Column added".to_string())
+            }
+            "delcol" => {
+                table.delete_column(col)?;
+                ((row, col.min(table.column_count() - 1)), "Column deleted".to_string())
+            }
+            "align" => {
+                let name = parts.next().unwrap_or("");
+                let alignment = ColumnAlignment::parse(name)
+                    .ok_or_else(|| "Usage: :table align <left|center|right|none>".to_string())?;
+                table.set_alignment(col, alignment);
+                ((row, col), format!("Column aligned {}", name))
+            }
+            other => return Err(format!("Unknown table command: {}", other)),
+        };
+        
+        self.push_undo_snapshot();
+        self.write_table(start, end, &table, target);
+        Ok(message)
+    }
+    
+    /// Open the task overview across all notes
+    pub fn open_tasks_view(&mut self) {
+        self.refresh_tasks();
+        self.task_selected = 0;
+        self.mode = AppMode::Tasks;
+        
+        let open = self.task_items.iter().filter(|t| !t.done).count();
+        let done = self.task_items.len() - open;
+        self.set_message(format!("{} open, {} done tasks", open, done));
+    }
+    
+    /// Rescan the notebook for tasks
+    pub fn refresh_tasks(&mut self) {
+        let current = self.current_note.as_ref().map(|n| (n.id, self.editor_content.as_str()));
+        let mut items = tasks::collect_tasks(&self.notebook, current);
+        tasks::sort_tasks(&self.notebook, &mut items, self.task_grouping);
+        self.task_items = items;
+        self.task_selected = self.task_selected.min(self.visible_tasks().len().saturating_sub(1));
+    }
+    
+    /// Tasks shown in the overview, honouring the done filter
+    pub fn visible_tasks(&self) -> Vec<&TaskItem> {
+        self.task_items
+            .iter()
+            .filter(|t| self.task_show_done || !t.done)
+            .collect()
+    }
+    
+    pub fn next_task(&mut self) {
+        if self.task_selected + 1 < self.visible_tasks().len() {
+            self.task_selected += 1;
+        }
+    }
+    
+    pub fn previous_task(&mut self) {
+        self.task_selected = self.task_selected.saturating_sub(1);
+    }
+    
+    pub fn toggle_task_grouping(&mut self) {
+        self.task_grouping = self.task_grouping.toggle();
+        self.refresh_tasks();
+        self.set_message(format!("Tasks grouped by {}", self.task_grouping.label()));
+    }
+    
+    pub fn toggle_done_tasks(&mut self) {
+        self.task_show_done = !self.task_show_done;
+        self.refresh_tasks();
+    }
+    
+    /// Open the note of the selected task with the cursor on its line
+    pub fn open_selected_task(&mut self) {
+        let target = self.visible_tasks()
+            .get(self.task_selected)
+            .map(|t| (t.note_id, t.line_number));
+        
+        if let Some((note_id, line_number)) = target {
+            self.mode = AppMode::Normal;
+            if self.current_note.as_ref().map(|n| n.id) != Some(note_id) {
+                self.open_note_by_id(note_id);
+            }
+            self.focused_pane = FocusedPane::Editor;
+            self.jump_to_line(line_number);
+        }
+    }
+    
+    /// Toggle the selected task in place, in whichever note it lives
+    pub fn toggle_selected_task(&mut self) {
+        let target = self.visible_tasks()
+            .get(self.task_selected)
+            .map(|t| (t.note_id, t.line_number));
+        let Some((note_id, line_number)) = target else {
+            return;
+        };
+        
+        if self.current_note.as_ref().map(|n| n.id) == Some(note_id) {
+            let cursor = self.editor_cursor;
+            self.editor_cursor = (line_number as u16, 0);
+            self.toggle_task_at_cursor();
+            self.editor_cursor = cursor;
+            self.clamp_cursor_column();
+        } else if let Some(note) = self.notebook.notes.get(&note_id) {
+            let today = chrono::Local::now().date_naive();
+            let mut lines: Vec<String> = note.content.split('\n').map(str::to_string).collect();
+            if let Some(toggled) = lines.get(line_number).and_then(|l| lists::toggle_checkbox(l, today)) {
+                lines[line_number] = toggled;
+                self.store_note_content(note_id, lines.join("\n"));
+            }
+        }
+        
+        self.refresh_tasks();
+    }
+    
+    /// Text of a line in the editor buffer
+    pub fn line_text(&self, row: usize) -> &str {
+        self.editor_content.split('\n').nth(row).unwrap_or("")
+    }
+    
+    /// Replace a whole line in the editor buffer
+    pub fn replace_line(&mut self, row: usize, text: &str) {
+        let start = self.get_line_start_position(row).min(self.editor_content.len());
+        let end = start + self.line_text(row).len();
+        self.editor_content.replace_range(start..end, text);
+        self.clamp_cursor_column();
+        self.mark_modified();
+    }
+    
+    /// Keep the cursor within the bounds of the buffer
+    fn clamp_cursor_row(&mut self) {
+        let last_row = self.editor_content.split('\n').count().saturating_sub(1);
+        self.editor_cursor.0 = self.editor_cursor.0.min(last_row as u16);
+        self.clamp_cursor_column();
+        self.adjust_scroll_to_cursor();
+    }
+    
+    /// Keep the cursor column within the bounds of its line
+    pub fn clamp_cursor_column(&mut self) {
+        let line_len = self.line_text(self.editor_cursor.0 as usize).len();
+        self.editor_cursor.1 = self.editor_cursor.1.min(line_len as u16);
+    }
+    
+    /// Get the absolute character position of the start of a line
+    fn get_line_start_position(&self, line_index: usize) -> usize {
+        self.editor_content
+            .split('\n')
+            .take(line_index)
+            .map(|line| line.len() + 1) // +1 for the newline character
+            .sum()
     }
     
     /// Update cursor position from absolute character position
     fn update_cursor_from_absolute_position(&mut self, abs_pos: usize) {
-        let lines: Vec<&str> = self.editor_content.lines().collect();
+        // split keeps the empty line after a trailing newline, unlike lines()
+        let lines: Vec<&str> = self.editor_content.split('\n').collect();
         let mut current_pos = 0;
         
         for (line_index, line) in lines.iter().enumerate() {
@@ -704,7 +2623,7 @@ impl App {
                     .map_err(|e| format!("Failed to create temp file: {}", e))?;
                 
                 // Save the current terminal state and run the external editor
-                let result = run_external_editor(editor, &temp_path);
+                let result = run_external_editor(editor, &temp_path, self.keyboard_enhanced);
                 
                 match result {
                     Ok(()) => {
@@ -762,13 +2681,11 @@ impl App {
     
     pub fn export_notes_to_directory(&self, directory: &str) -> Result<(), String> {
         use std::fs;
-        use std::path::Path;
         
-        let export_dir = Path::new(directory);
-        fs::create_dir_all(export_dir)
+        let export_dir = config::expand_home(directory);
+        fs::create_dir_all(&export_dir)
             .map_err(|e| format!("Failed to create export directory: {}", e))?;
         
-        let mut _exported_count = 0;
         for note in self.notebook.notes.values() {
             let filename = sanitize_filename(&note.title);
             let file_path = export_dir.join(format!("{}.md", filename));
@@ -783,31 +2700,27 @@ impl App {
             
             fs::write(&file_path, content)
                 .map_err(|e| format!("Failed to write note '{}': {}", note.title, e))?;
-            
-            _exported_count += 1;
         }
         
-        // Could return exported_count in future for user feedback
         Ok(())
     }
     
     pub fn import_notes_from_directory(&mut self, directory: &str) -> Result<(), String> {
         use std::fs;
-        use std::path::Path;
         
-        let import_dir = Path::new(directory);
+        let import_dir = config::expand_home(directory);
         if !import_dir.exists() {
             return Err("Import directory does not exist".to_string());
         }
         
         let mut imported_count = 0;
-        for entry in fs::read_dir(import_dir)
+        for entry in fs::read_dir(&import_dir)
             .map_err(|e| format!("Failed to read import directory: {}", e))?
         {
             let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
             let path = entry.path();
             
-            if path.is_file() && path.extension().map_or(false, |ext| ext == "md") {
+            if path.is_file() && path.extension().is_some_and(|ext| ext == "md") {
                 match self.import_note_from_file(&path) {
                     Ok(_) => imported_count += 1,
                     Err(e) => eprintln!("Warning: Failed to import {}: {}", path.display(), e),
@@ -842,183 +2755,998 @@ impl App {
         } else {
             (filename, content)
         };
-        
-        // Check if note already exists
-        let existing_note = self.notebook.notes.values()
-            .find(|note| note.title == title);
-        
-        if existing_note.is_some() {
-            return Err(format!("Note with title '{}' already exists", title));
+        
+        // Check if note already exists
+        let existing_note = self.notebook.notes.values()
+            .find(|note| note.title == title);
+        
+        if existing_note.is_some() {
+            return Err(format!("Note with title '{}' already exists", title));
+        }
+        
+        let mut note = Note::new(title, None);
+        note.content = note_content;
+        self.link_index.update_note(&note);
+        self.enhanced_search.index_note(&note);
+        self.notebook.add_note(note);
+        
+        Ok(())
+    }
+    
+    pub fn create_backup(&self) -> Result<(), String> {
+        let storage = crate::storage::Storage::new()
+            .map_err(|e| format!("Failed to initialize storage: {}", e))?;
+        
+        match storage.backup_data() {
+            Ok(_backup_path) => {
+                Ok(())
+            }
+            Err(e) => Err(format!("Backup failed: {}", e))
+        }
+    }
+    
+    pub fn start_move_item(&mut self) {
+        let movable = |item: &&TreeItem| matches!(item.item_type, TreeItemType::Note | TreeItemType::Folder);
+        if let Some(selected_item) = self.get_selected_item().filter(movable).cloned() {
+            self.move_item_id = Some(selected_item.id);
+            self.move_item_type = Some(selected_item.item_type.clone());
+            self.mode = AppMode::Move;
+            
+            let item_name = &selected_item.name;
+            let item_type_str = match selected_item.item_type {
+                TreeItemType::Folder => "folder",
+                _ => "note",
+            };
+            self.set_message(format!("Moving {} '{}' - select destination folder or press Esc to cancel", item_type_str, item_name));
+        } else {
+            self.set_message("Nothing selected to move".to_string());
+        }
+    }
+    
+    pub fn cancel_move(&mut self) {
+        self.move_item_id = None;
+        self.move_item_type = None;
+        self.mode = AppMode::Normal;
+        self.set_message("Move cancelled".to_string());
+    }
+    
+    pub fn execute_move(&mut self) -> Result<(), String> {
+        let move_id = self.move_item_id.ok_or("No item selected for moving")?;
+        let move_type = self.move_item_type.as_ref().ok_or("No item type selected")?;
+        
+        // Get the selected destination
+        if let Some(selected_item) = self.get_selected_item() {
+            let destination_folder_id = match selected_item.item_type {
+                TreeItemType::Folder => Some(selected_item.id),
+                TreeItemType::Note => {
+                    // Find the parent folder of the selected note
+                    if let Some(note) = self.notebook.notes.get(&selected_item.id) {
+                        note.folder_id
+                    } else {
+                        None
+                    }
+                },
+                _ => return Err("Choose a folder as the destination".to_string()),
+            };
+            
+            match move_type {
+                TreeItemType::Note => {
+                    self.move_note(move_id, destination_folder_id)?;
+                },
+                TreeItemType::Folder => {
+                    self.move_folder(move_id, destination_folder_id)?;
+                },
+                _ => return Err("Nothing to move".to_string()),
+            }
+            
+            // Reset move state
+            self.move_item_id = None;
+            self.move_item_type = None;
+            self.mode = AppMode::Normal;
+            self.refresh_tree_view();
+            
+            let dest_name = if let Some(dest_id) = destination_folder_id {
+                if let Some(folder) = self.notebook.folders.get(&dest_id) {
+                    folder.name.clone()
+                } else {
+                    "Unknown".to_string()
+                }
+            } else {
+                "Root".to_string()
+            };
+            
+            self.set_operation_success(format!("Item moved to '{}'!", dest_name), Some("📁".to_string()));
+            Ok(())
+        } else {
+            Err("No destination selected".to_string())
+        }
+    }
+    
+    fn move_note(&mut self, note_id: Uuid, destination_folder_id: Option<Uuid>) -> Result<(), String> {
+        if let Some(note) = self.notebook.notes.get_mut(&note_id) {
+            // Check if we're actually moving to a different location
+            if note.folder_id == destination_folder_id {
+                return Err("Note is already in this location".to_string());
+            }
+            
+            // Update the note's folder_id
+            note.folder_id = destination_folder_id;
+            note.modified_at = chrono::Utc::now();
+            self.enhanced_search.index_note(note);
+            
+            // Update current note if it's the one being moved
+            if let Some(ref current_note) = self.current_note {
+                if current_note.id == note_id {
+                    self.current_note = Some(note.clone());
+                }
+            }
+            
+            Ok(())
+        } else {
+            Err("Note not found".to_string())
+        }
+    }
+    
+    fn move_folder(&mut self, folder_id: Uuid, destination_folder_id: Option<Uuid>) -> Result<(), String> {
+        // Check for circular dependency
+        if let Some(dest_id) = destination_folder_id {
+            if self.is_folder_ancestor(folder_id, dest_id) {
+                return Err("Cannot move folder into its own subfolder".to_string());
+            }
+        }
+        
+        if let Some(folder) = self.notebook.folders.get_mut(&folder_id) {
+            // Check if we're actually moving to a different location
+            if folder.parent_id == destination_folder_id {
+                return Err("Folder is already in this location".to_string());
+            }
+            
+            // Update folder hierarchy
+            if folder.parent_id.is_none() {
+                // Remove from root folders
+                self.notebook.root_folder_ids.retain(|&id| id != folder_id);
+            }
+            
+            folder.parent_id = destination_folder_id;
+            
+            if destination_folder_id.is_none() {
+                // Add to root folders
+                if !self.notebook.root_folder_ids.contains(&folder_id) {
+                    self.notebook.root_folder_ids.push(folder_id);
+                }
+            }
+            
+            Ok(())
+        } else {
+            Err("Folder not found".to_string())
+        }
+    }
+    
+    fn is_folder_ancestor(&self, ancestor_id: Uuid, descendant_id: Uuid) -> bool {
+        if ancestor_id == descendant_id {
+            return true;
+        }
+        
+        if let Some(descendant) = self.notebook.folders.get(&descendant_id) {
+            if let Some(parent_id) = descendant.parent_id {
+                return self.is_folder_ancestor(ancestor_id, parent_id);
+            }
+        }
+        
+        false
+    }
+    
+    pub fn quit(&mut self) {
+        self.should_quit = true;
+    }
+    
+    /// The item a rename applies to: the open note when a note pane is focused,
+    /// otherwise the selected tree item
+    fn rename_target(&self) -> Option<(Uuid, TreeItemType, String)> {
+        match self.current_note {
+            Some(ref note) if self.focused_pane != FocusedPane::Folders => {
+                Some((note.id, TreeItemType::Note, note.title.clone()))
+            }
+            _ => self.get_selected_item()
+                .filter(|item| !item.item_type.is_section_header())
+                .map(|item| (item.id, item.item_type.clone(), item.name.clone())),
+        }
+    }
+    
+    /// Open the rename dialog for the current item, prefilled with its name
+    pub fn start_rename(&mut self) -> Result<(), String> {
+        let (id, item_type, name) = self.rename_target().ok_or("Nothing to rename")?;
+        self.rename_item_id = Some(id);
+        self.rename_item_type = Some(item_type);
+        self.rename_old_name = name.clone();
+        self.input_buffer = name;
+        self.mode = AppMode::Rename;
+        Ok(())
+    }
+    
+    /// Validate the new name and rename, asking first if links would be rewritten
+    pub fn submit_rename(&mut self, new_name: &str) -> Result<(), String> {
+        if self.rename_item_id.is_none() {
+            let (id, item_type, name) = self.rename_target().ok_or("Nothing to rename")?;
+            self.rename_item_id = Some(id);
+            self.rename_item_type = Some(item_type);
+            self.rename_old_name = name;
+        }
+        let (Some(id), Some(item_type)) = (self.rename_item_id, self.rename_item_type.clone()) else {
+            return Err("Nothing to rename".to_string());
+        };
+        
+        let new_name = new_name.trim();
+        if new_name.is_empty() {
+            return Err("Name cannot be empty".to_string());
+        }
+        if new_name == self.rename_old_name {
+            self.cancel_rename();
+            self.set_message("Name unchanged".to_string());
+            return Ok(());
+        }
+        
+        let lowercase = new_name.to_lowercase();
+        match item_type {
+            TreeItemType::Note => {
+                if !links::is_linkable_title(new_name) {
+                    return Err("Note titles cannot contain '#', '|', '[[' or ']]'".to_string());
+                }
+                // Links resolve by title, so titles must be unique across the notebook
+                if self.notebook.notes.values().any(|n| n.id != id && n.title.to_lowercase() == lowercase) {
+                    return Err(format!("A note named '{}' already exists", new_name));
+                }
+            }
+            TreeItemType::Folder => {
+                let parent_id = self.notebook.folders.get(&id).and_then(|f| f.parent_id);
+                let taken = self.notebook.folders.values()
+                    .any(|f| f.id != id && f.parent_id == parent_id && f.name.to_lowercase() == lowercase);
+                if taken {
+                    return Err(format!("A folder named '{}' already exists here", new_name));
+                }
+            }
+            TreeItemType::SavedSearch => {
+                if self.notebook.saved_searches.iter().any(|s| s.id != id && s.name.to_lowercase() == lowercase) {
+                    return Err(format!("A saved search named '{}' already exists", new_name));
+                }
+            }
+            TreeItemType::Pinned | TreeItemType::SavedSearches => return Err("Nothing to rename".to_string()),
+        }
+        
+        self.rename_new_name = new_name.to_string();
+        self.input_buffer.clear();
+        
+        if item_type == TreeItemType::Note {
+            // Renaming only changes case: links still resolve, nothing to rewrite
+            let case_only = self.rename_old_name.to_lowercase() == lowercase;
+            self.rename_link_notes = if case_only {
+                Vec::new()
+            } else {
+                self.link_index.sources_linking_to(&self.rename_old_name)
+            };
+            if !self.rename_link_notes.is_empty() {
+                self.mode = AppMode::RenameConfirm;
+                return Ok(());
+            }
+        }
+        
+        self.confirm_rename(false)
+    }
+    
+    /// Apply the pending rename, optionally rewriting links to a renamed note
+    pub fn confirm_rename(&mut self, update_links: bool) -> Result<(), String> {
+        let (Some(id), Some(item_type)) = (self.rename_item_id, self.rename_item_type.clone()) else {
+            return Err("Nothing to rename".to_string());
+        };
+        let old_name = std::mem::take(&mut self.rename_old_name);
+        let new_name = std::mem::take(&mut self.rename_new_name);
+        let link_notes = std::mem::take(&mut self.rename_link_notes);
+        self.rename_item_id = None;
+        self.rename_item_type = None;
+        self.mode = AppMode::Normal;
+        
+        match item_type {
+            TreeItemType::Folder => {
+                let folder = self.notebook.folders.get_mut(&id).ok_or("Folder no longer exists")?;
+                folder.rename(new_name.clone());
+                self.set_operation_success(format!("Renamed folder '{}' to '{}'", old_name, new_name), Some("✏️".to_string()));
+            }
+            TreeItemType::Note => {
+                // Rewrite from saved content so the open note's edits are included
+                if self.save_status == SaveStatus::Modified {
+                    self.save_current_note()?;
+                }
+                
+                let mut updated = 0;
+                if update_links {
+                    for note_id in &link_notes {
+                        if let Some(note) = self.notebook.notes.get_mut(note_id) {
+                            if let Some(content) = links::rewrite_links(&note.content, &old_name, &new_name) {
+                                note.update_content(content);
+                                self.link_index.update_note(note);
+                                self.enhanced_search.index_note(note);
+                                updated += 1;
+                            }
+                        }
+                    }
+                }
+                
+                let note = self.notebook.notes.get_mut(&id).ok_or("Note no longer exists")?;
+                note.rename(new_name.clone());
+                self.enhanced_search.index_note(note);
+                
+                // Reload the open note if it was renamed or its links were rewritten
+                let current_id = self.current_note.as_ref().map(|n| n.id);
+                if let Some(current) = current_id.and_then(|cid| self.notebook.notes.get(&cid)).cloned() {
+                    if current.content != self.editor_content {
+                        self.push_undo_snapshot();
+                        self.editor_content = current.content.clone();
+                        self.clamp_cursor_row();
+                    }
+                    self.current_note = Some(current);
+                }
+                self.refresh_backlinks();
+                
+                let message = if update_links {
+                    format!("Renamed '{}' to '{}', updated links in {} notes", old_name, new_name, updated)
+                } else {
+                    format!("Renamed '{}' to '{}'", old_name, new_name)
+                };
+                self.set_operation_success(message, Some("✏️".to_string()));
+            }
+            TreeItemType::SavedSearch => {
+                let search = self.notebook.saved_searches.iter_mut().find(|s| s.id == id).ok_or("Saved search no longer exists")?;
+                search.name = new_name.clone();
+                self.set_operation_success(format!("Renamed saved search '{}' to '{}'", old_name, new_name), Some("✏️".to_string()));
+            }
+            TreeItemType::Pinned | TreeItemType::SavedSearches => return Err("Nothing to rename".to_string()),
+        }
+        
+        self.refresh_tree_view();
+        Ok(())
+    }
+    
+    pub fn cancel_rename(&mut self) {
+        self.rename_item_id = None;
+        self.rename_item_type = None;
+        self.rename_old_name.clear();
+        self.rename_new_name.clear();
+        self.rename_link_notes.clear();
+        self.input_buffer.clear();
+        self.mode = AppMode::Normal;
+    }
+    
+    /// Open the tag editor for the current note, prefilled with its tags
+    pub fn start_tag_edit(&mut self) -> Result<(), String> {
+        let note = self.current_note.as_ref().ok_or("No note selected")?;
+        self.input_buffer = note.tags.join(", ");
+        if !self.input_buffer.is_empty() {
+            self.input_buffer.push_str(", ");
+        }
+        self.mode = AppMode::TagEdit;
+        self.update_tag_suggestions();
+        Ok(())
+    }
+    
+    /// Suggest existing tags for the tag being typed in the tag editor
+    pub fn update_tag_suggestions(&mut self) {
+        let (done, partial) = split_last_tag(&self.input_buffer);
+        self.tag_suggestions = if partial.is_empty() {
+            Vec::new()
+        } else {
+            tags::complete_tag(&self.notebook, partial, &tags::parse_tag_list(done))
+        };
+        self.tag_suggestion_selected = 0;
+    }
+    
+    pub fn next_tag_suggestion(&mut self) {
+        if !self.tag_suggestions.is_empty() {
+            self.tag_suggestion_selected = (self.tag_suggestion_selected + 1) % self.tag_suggestions.len();
+        }
+    }
+    
+    pub fn previous_tag_suggestion(&mut self) {
+        if !self.tag_suggestions.is_empty() {
+            self.tag_suggestion_selected = self.tag_suggestion_selected
+                .checked_sub(1)
+                .unwrap_or(self.tag_suggestions.len() - 1);
+        }
+    }
+    
+    /// Replace the tag being typed with the selected suggestion.
+    /// Returns false if there is no suggestion.
+    pub fn accept_tag_suggestion(&mut self) -> bool {
+        let Some(tag) = self.tag_suggestions.get(self.tag_suggestion_selected).cloned() else {
+            return false;
+        };
+        let (done, _) = split_last_tag(&self.input_buffer);
+        self.input_buffer = format!("{}{}, ", done, tag);
+        self.update_tag_suggestions();
+        true
+    }
+    
+    /// Save the tags typed in the tag editor to the current note
+    pub fn finish_tag_edit(&mut self) {
+        let new_tags = tags::parse_tag_list(&self.input_buffer);
+        self.input_buffer.clear();
+        self.tag_suggestions.clear();
+        self.mode = AppMode::Normal;
+        
+        let Some(note_id) = self.current_note.as_ref().map(|n| n.id) else {
+            return;
+        };
+        let Some(note) = self.notebook.notes.get_mut(&note_id) else {
+            return;
+        };
+        let removed: Vec<String> = note.tags.iter().filter(|t| !new_tags.contains(t)).cloned().collect();
+        if removed.is_empty() && new_tags.iter().all(|t| note.tags.contains(t)) {
+            self.set_message("Tags unchanged".to_string());
+            return;
+        }
+        
+        for tag in &removed {
+            note.remove_tag(tag);
+        }
+        for tag in new_tags {
+            note.add_tag(tag);
+        }
+        self.enhanced_search.index_note(note);
+        
+        let tags = note.tags.clone();
+        let inline_tags = note.inline_tags.clone();
+        if let Some(ref mut current) = self.current_note {
+            current.tags = tags.clone();
+            current.inline_tags = inline_tags;
+        }
+        self.refresh_tree_view();
+        
+        let message = if tags.is_empty() {
+            "Removed all tags".to_string()
+        } else {
+            format!("Tags: {}", tags.iter().map(|t| format!("#{}", t)).collect::<Vec<_>>().join(" "))
+        };
+        self.set_operation_success(message, Some("🏷".to_string()));
+    }
+    
+    pub fn cancel_tag_edit(&mut self) {
+        self.input_buffer.clear();
+        self.tag_suggestions.clear();
+        self.mode = AppMode::Normal;
+    }
+    
+    /// Open the tag browser, selecting the active filter if there is one
+    pub fn open_tag_browser(&mut self) {
+        // Reveal the filtered tag if it sits under a collapsed parent
+        if let Some(ref filter) = self.tag_filter {
+            self.tag_browser_collapsed.retain(|path| !tags::is_within(filter, path) || path == filter);
+        }
+        self.refresh_tag_browser(self.tag_filter.clone());
+        self.mode = AppMode::TagBrowser;
+    }
+    
+    /// Rebuild the tag tree, selecting `select` if it is visible
+    fn refresh_tag_browser(&mut self, select: Option<String>) {
+        self.tag_browser_items = tags::tag_tree(&self.notebook, &self.tag_browser_collapsed);
+        self.tag_browser_selected = select
+            .and_then(|path| self.tag_browser_items.iter().position(|item| item.path == path))
+            .unwrap_or(self.tag_browser_selected)
+            .min(self.tag_browser_items.len().saturating_sub(1));
+    }
+    
+    /// Collapse the selected tag, or move to its parent if it is already collapsed or a leaf
+    pub fn collapse_tag_in_browser(&mut self) {
+        let Some(item) = self.tag_browser_items.get(self.tag_browser_selected).cloned() else {
+            return;
+        };
+        if item.has_children && self.tag_browser_collapsed.insert(item.path.clone()) {
+            self.refresh_tag_browser(Some(item.path));
+        } else if let Some((parent, _)) = item.path.rsplit_once('/') {
+            let parent = parent.to_string();
+            self.refresh_tag_browser(Some(parent));
+        }
+    }
+    
+    pub fn expand_tag_in_browser(&mut self) {
+        let Some(item) = self.tag_browser_items.get(self.tag_browser_selected).cloned() else {
+            return;
+        };
+        if self.tag_browser_collapsed.remove(&item.path) {
+            self.refresh_tag_browser(Some(item.path));
+        }
+    }
+    
+    pub fn next_tag_in_browser(&mut self) {
+        if self.tag_browser_selected + 1 < self.tag_browser_items.len() {
+            self.tag_browser_selected += 1;
+        }
+    }
+    
+    pub fn previous_tag_in_browser(&mut self) {
+        self.tag_browser_selected = self.tag_browser_selected.saturating_sub(1);
+    }
+    
+    /// Filter the tree to the notes carrying the selected tag
+    pub fn filter_by_selected_tag(&mut self) {
+        self.mode = AppMode::Normal;
+        let Some(TagTreeItem { path: tag, count, .. }) = self.tag_browser_items.get(self.tag_browser_selected).cloned() else {
+            return;
+        };
+        self.tag_filter = Some(tag.clone());
+        self.refresh_tree_view();
+        self.selected_folder_index = 0;
+        self.focused_pane = FocusedPane::Folders;
+        self.set_message(format!("Showing {} notes tagged #{}", count, tag));
+    }
+    
+    pub fn clear_tag_filter(&mut self) {
+        if self.tag_filter.take().is_some() {
+            self.refresh_tree_view();
+            self.selected_folder_index = self.selected_folder_index.min(self.folder_tree_items.len().saturating_sub(1));
+            self.set_message("Tag filter cleared".to_string());
+        }
+    }
+    
+    /// Ask for a new name for the tag selected in the tag browser
+    pub fn start_tag_rename(&mut self) {
+        if let Some(item) = self.tag_browser_items.get(self.tag_browser_selected) {
+            self.tag_rename_old = item.path.clone();
+            self.input_buffer = item.path.clone();
+            self.mode = AppMode::TagRename;
+        }
+    }
+    
+    /// Rename a tag and every tag nested below it, in note tags and in inline #tags
+    pub fn submit_tag_rename(&mut self, input: &str) -> Result<(), String> {
+        let old = self.tag_rename_old.clone();
+        let new = tags::normalize_tag(input).ok_or_else(|| format!("'{}' is not a valid tag", input.trim()))?;
+        if new == old {
+            self.cancel_tag_rename();
+            return Ok(());
+        }
+        
+        // Rewrite from saved content so the open note's edits are included
+        if self.save_status == SaveStatus::Modified {
+            self.save_current_note()?;
+        }
+        
+        let mut updated = 0;
+        for note in self.notebook.notes.values_mut() {
+            let content = tags::rewrite_hashtags(&note.content, &old, &new);
+            if content.is_none() && !note.tags.iter().any(|t| tags::is_within(t, &old)) {
+                continue;
+            }
+            
+            let mut renamed: Vec<String> = Vec::new();
+            for tag in &note.tags {
+                let tag = tags::retag(tag, &old, &new).unwrap_or_else(|| tag.clone());
+                if !renamed.contains(&tag) {
+                    renamed.push(tag);
+                }
+            }
+            if let Some(content) = content {
+                note.update_content(content);
+            }
+            note.set_tags(renamed);
+            note.inline_tags = note.inline_tags.iter()
+                .map(|tag| tags::retag(tag, &old, &new).unwrap_or_else(|| tag.clone()))
+                .collect();
+            self.link_index.update_note(note);
+            self.enhanced_search.index_note(note);
+            updated += 1;
+        }
+        
+        // Reload the open note if it was retagged
+        let current_id = self.current_note.as_ref().map(|n| n.id);
+        if let Some(current) = current_id.and_then(|cid| self.notebook.notes.get(&cid)).cloned() {
+            if current.content != self.editor_content {
+                self.push_undo_snapshot();
+                self.editor_content = current.content.clone();
+                self.clamp_cursor_row();
+            }
+            self.current_note = Some(current);
+        }
+        
+        if let Some(filter) = self.tag_filter.as_ref().and_then(|f| tags::retag(f, &old, &new)) {
+            self.tag_filter = Some(filter);
+        }
+        if let Some(collapsed) = self.tag_browser_collapsed.take(&old) {
+            self.tag_browser_collapsed.insert(tags::retag(&collapsed, &old, &new).unwrap_or(collapsed));
+        }
+        self.refresh_tree_view();
+        self.refresh_tag_browser(Some(new.clone()));
+        
+        self.tag_rename_old.clear();
+        self.input_buffer.clear();
+        self.mode = AppMode::TagBrowser;
+        self.set_operation_success(format!("Renamed #{} to #{} in {} notes", old, new, updated), Some("🏷".to_string()));
+        Ok(())
+    }
+    
+    /// Leave the tag rename prompt, back to the tag browser
+    pub fn cancel_tag_rename(&mut self) {
+        self.tag_rename_old.clear();
+        self.input_buffer.clear();
+        self.mode = AppMode::TagBrowser;
+    }
+    
+    /// Open the quick-open finder, listing recently modified notes first
+    pub fn open_quick_open(&mut self) {
+        self.input_buffer.clear();
+        self.quick_open_selected = 0;
+        self.update_quick_open();
+        self.mode = AppMode::QuickOpen;
+    }
+    
+    /// Re-rank the notes against the typed query
+    pub fn update_quick_open(&mut self) {
+        self.quick_open_results = quick_open::find_notes(&self.notebook, &self.input_buffer, &self.recent_notes, MAX_QUICK_OPEN_RESULTS);
+        self.quick_open_selected = 0;
+    }
+    
+    pub fn next_quick_open_result(&mut self) {
+        if self.quick_open_selected + 1 < self.quick_open_results.len() {
+            self.quick_open_selected += 1;
+        }
+    }
+    
+    pub fn previous_quick_open_result(&mut self) {
+        self.quick_open_selected = self.quick_open_selected.saturating_sub(1);
+    }
+    
+    /// Open the selected note, revealing it in the tree
+    pub fn open_selected_quick_open(&mut self) -> Result<(), String> {
+        let note_id = self.quick_open_results
+            .get(self.quick_open_selected)
+            .map(|r| r.note_id)
+            .ok_or("No matching note")?;
+        self.close_quick_open();
+        
+        // Keep unsaved edits before switching notes
+        if self.save_status == SaveStatus::Modified {
+            self.save_current_note()?;
+        }
+        self.open_note_by_id(note_id);
+        Ok(())
+    }
+    
+    pub fn close_quick_open(&mut self) {
+        self.input_buffer.clear();
+        self.quick_open_results.clear();
+        self.mode = AppMode::Normal;
+    }
+    
+    pub fn open_command_palette(&mut self) {
+        self.input_buffer.clear();
+        self.update_command_palette();
+        self.mode = AppMode::CommandPalette;
+    }
+    
+    pub fn update_command_palette(&mut self) {
+        self.palette_matches = commands::palette_matches(&self.input_buffer);
+        self.palette_selected = 0;
+    }
+    
+    pub fn next_palette_match(&mut self) {
+        if self.palette_selected + 1 < self.palette_matches.len() {
+            self.palette_selected += 1;
+        }
+    }
+    
+    pub fn previous_palette_match(&mut self) {
+        self.palette_selected = self.palette_selected.saturating_sub(1);
+    }
+    
+    pub fn close_command_palette(&mut self) {
+        self.input_buffer.clear();
+        self.palette_matches.clear();
+        self.mode = AppMode::Normal;
+    }
+    
+    /// Run the selected command. Commands that need an argument go to the
+    /// command line with their name filled in.
+    pub fn run_selected_palette_command(&mut self) {
+        let Some(command) = self.palette_matches.get(self.palette_selected).map(|m| m.command) else {
+            return;
+        };
+        self.close_command_palette();
+        if command.requires_argument() {
+            self.start_command_line(format!("{} ", command.name));
+        } else {
+            (command.run)(self, "");
         }
-        
-        let mut note = Note::new(title, None);
-        note.content = note_content;
-        self.notebook.add_note(note);
-        
-        Ok(())
     }
     
-    pub fn create_backup(&self) -> Result<(), String> {
-        let storage = crate::storage::Storage::new()
-            .map_err(|e| format!("Failed to initialize storage: {}", e))?;
+    /// Open the `:` command line with some text already typed
+    pub fn start_command_line(&mut self, initial: String) {
+        self.command_cursor = initial.len();
+        self.command_buffer = initial;
+        self.command_history_index = None;
+        self.command_line_edited();
+        self.mode = AppMode::Command;
+    }
+    
+    /// Take the typed command line, remembering it in the history
+    pub fn submit_command_line(&mut self) -> String {
+        let line = std::mem::take(&mut self.command_buffer);
+        self.cancel_command_line();
         
-        match storage.backup_data() {
-            Ok(_backup_path) => {
-                Ok(())
+        let trimmed = line.trim();
+        if !trimmed.is_empty() {
+            self.command_history.retain(|entry| entry != trimmed);
+            self.command_history.push(trimmed.to_string());
+            if self.command_history.len() > MAX_COMMAND_HISTORY {
+                self.command_history.remove(0);
             }
-            Err(e) => Err(format!("Backup failed: {}", e))
         }
+        line
     }
     
-    pub fn start_move_item(&mut self) {
-        if let Some(selected_item) = self.get_selected_item().cloned() {
-            self.move_item_id = Some(selected_item.id);
-            self.move_item_type = Some(selected_item.item_type.clone());
-            self.mode = AppMode::Move;
-            
-            let item_name = &selected_item.name;
-            let item_type_str = match selected_item.item_type {
-                TreeItemType::Note => "note",
-                TreeItemType::Folder => "folder",
-            };
-            self.set_message(format!("Moving {} '{}' - select destination folder or press Esc to cancel", item_type_str, item_name));
-        } else {
-            self.set_message("Nothing selected to move".to_string());
+    /// Leave the command line without running or remembering it
+    pub fn cancel_command_line(&mut self) {
+        self.command_buffer.clear();
+        self.command_cursor = 0;
+        self.command_history_index = None;
+        self.command_history_draft.clear();
+        self.command_line_edited();
+    }
+    
+    /// Forget completion candidates once the line changes other than by Tab
+    fn command_line_edited(&mut self) {
+        self.command_completions.clear();
+        self.command_completion_index = None;
+    }
+    
+    pub fn command_insert_char(&mut self, c: char) {
+        self.command_buffer.insert(self.command_cursor, c);
+        self.command_cursor += c.len_utf8();
+        self.command_line_edited();
+    }
+    
+    pub fn command_insert_str(&mut self, text: &str) {
+        self.command_buffer.insert_str(self.command_cursor, text);
+        self.command_cursor += text.len();
+        self.command_line_edited();
+    }
+    
+    pub fn command_backspace(&mut self) {
+        if let Some(c) = self.command_buffer[..self.command_cursor].chars().next_back() {
+            self.command_cursor -= c.len_utf8();
+            self.command_buffer.remove(self.command_cursor);
+            self.command_line_edited();
         }
     }
     
-    pub fn cancel_move(&mut self) {
-        self.move_item_id = None;
-        self.move_item_type = None;
-        self.mode = AppMode::Normal;
-        self.set_message("Move cancelled".to_string());
+    pub fn command_delete(&mut self) {
+        if self.command_cursor < self.command_buffer.len() {
+            self.command_buffer.remove(self.command_cursor);
+            self.command_line_edited();
+        }
     }
     
-    pub fn execute_move(&mut self) -> Result<(), String> {
-        let move_id = self.move_item_id.ok_or("No item selected for moving")?;
-        let move_type = self.move_item_type.as_ref().ok_or("No item type selected")?;
-        
-        // Get the selected destination
-        if let Some(selected_item) = self.get_selected_item() {
-            let destination_folder_id = match selected_item.item_type {
-                TreeItemType::Folder => Some(selected_item.id),
-                TreeItemType::Note => {
-                    // Find the parent folder of the selected note
-                    if let Some(note) = self.notebook.notes.get(&selected_item.id) {
-                        note.folder_id
-                    } else {
-                        None
-                    }
-                },
-            };
-            
-            match move_type {
-                TreeItemType::Note => {
-                    self.move_note(move_id, destination_folder_id)?;
-                },
-                TreeItemType::Folder => {
-                    self.move_folder(move_id, destination_folder_id)?;
-                },
-            }
-            
-            // Reset move state
-            self.move_item_id = None;
-            self.move_item_type = None;
-            self.mode = AppMode::Normal;
-            self.refresh_tree_view();
-            
-            let dest_name = if let Some(dest_id) = destination_folder_id {
-                if let Some(folder) = self.notebook.folders.get(&dest_id) {
-                    folder.name.clone()
-                } else {
-                    "Unknown".to_string()
-                }
-            } else {
-                "Root".to_string()
-            };
-            
-            self.set_operation_success(format!("Item moved to '{}'!", dest_name), Some("📁".to_string()));
-            Ok(())
-        } else {
-            Err("No destination selected".to_string())
+    pub fn command_cursor_left(&mut self) {
+        if let Some(c) = self.command_buffer[..self.command_cursor].chars().next_back() {
+            self.command_cursor -= c.len_utf8();
         }
     }
     
-    fn move_note(&mut self, note_id: Uuid, destination_folder_id: Option<Uuid>) -> Result<(), String> {
-        if let Some(note) = self.notebook.notes.get_mut(&note_id) {
-            // Check if we're actually moving to a different location
-            if note.folder_id == destination_folder_id {
-                return Err("Note is already in this location".to_string());
-            }
-            
-            // Update the note's folder_id
-            note.folder_id = destination_folder_id;
-            note.modified_at = chrono::Utc::now();
-            
-            // Update current note if it's the one being moved
-            if let Some(ref current_note) = self.current_note {
-                if current_note.id == note_id {
-                    self.current_note = Some(note.clone());
-                }
+    pub fn command_cursor_right(&mut self) {
+        if let Some(c) = self.command_buffer[self.command_cursor..].chars().next() {
+            self.command_cursor += c.len_utf8();
+        }
+    }
+    
+    pub fn command_cursor_home(&mut self) {
+        self.command_cursor = 0;
+    }
+    
+    pub fn command_cursor_end(&mut self) {
+        self.command_cursor = self.command_buffer.len();
+    }
+    
+    /// Delete the word before the cursor, along with the spaces after it (Ctrl-W)
+    pub fn command_delete_word(&mut self) {
+        let before = &self.command_buffer[..self.command_cursor];
+        let word_end = before.trim_end().len();
+        let word_start = before[..word_end].rfind(char::is_whitespace).map_or(0, |i| i + 1);
+        self.command_buffer.replace_range(word_start..self.command_cursor, "");
+        self.command_cursor = word_start;
+        self.command_line_edited();
+    }
+    
+    /// Delete everything before the cursor (Ctrl-U)
+    pub fn command_delete_to_start(&mut self) {
+        self.command_buffer.replace_range(..self.command_cursor, "");
+        self.command_cursor = 0;
+        self.command_line_edited();
+    }
+    
+    /// Step back through earlier command lines, keeping what was being typed
+    pub fn command_history_previous(&mut self) {
+        let index = match self.command_history_index {
+            Some(0) => return,
+            Some(index) => index - 1,
+            None if self.command_history.is_empty() => return,
+            None => {
+                self.command_history_draft = self.command_buffer.clone();
+                self.command_history.len() - 1
             }
-            
-            Ok(())
+        };
+        self.command_history_index = Some(index);
+        self.command_buffer = self.command_history[index].clone();
+        self.command_cursor = self.command_buffer.len();
+        self.command_line_edited();
+    }
+    
+    /// Step forward through the history, back to the line being typed
+    pub fn command_history_next(&mut self) {
+        let Some(index) = self.command_history_index else {
+            return;
+        };
+        if index + 1 < self.command_history.len() {
+            self.command_history_index = Some(index + 1);
+            self.command_buffer = self.command_history[index + 1].clone();
         } else {
-            Err("Note not found".to_string())
+            self.command_history_index = None;
+            self.command_buffer = std::mem::take(&mut self.command_history_draft);
         }
+        self.command_cursor = self.command_buffer.len();
+        self.command_line_edited();
     }
     
-    fn move_folder(&mut self, folder_id: Uuid, destination_folder_id: Option<Uuid>) -> Result<(), String> {
-        // Check for circular dependency
-        if let Some(dest_id) = destination_folder_id {
-            if self.is_folder_ancestor(folder_id, dest_id) {
-                return Err("Cannot move folder into its own subfolder".to_string());
-            }
+    /// Complete the word before the cursor. A single candidate is filled in;
+    /// several are extended to their common prefix and listed, and further
+    /// presses of Tab cycle through them.
+    pub fn complete_command_line(&mut self) {
+        if !self.command_completions.is_empty() {
+            let index = self.command_completion_index.map_or(0, |i| (i + 1) % self.command_completions.len());
+            let candidate = self.command_completions[index].clone();
+            self.command_buffer.replace_range(self.command_completion_start..self.command_cursor, &candidate);
+            self.command_cursor = self.command_completion_start + candidate.len();
+            self.command_completion_index = Some(index);
+            return;
         }
         
-        if let Some(folder) = self.notebook.folders.get_mut(&folder_id) {
-            // Check if we're actually moving to a different location
-            if folder.parent_id == destination_folder_id {
-                return Err("Folder is already in this location".to_string());
-            }
-            
-            // Update folder hierarchy
-            if folder.parent_id.is_none() {
-                // Remove from root folders
-                self.notebook.root_folder_ids.retain(|&id| id != folder_id);
+        let completion = commands::complete(self, &self.command_buffer[..self.command_cursor]);
+        match completion.candidates.len() {
+            0 => {}
+            1 => {
+                let mut candidate = completion.candidates[0].clone();
+                // Leave directories open for the next path segment; anything else
+                // but a command without arguments is followed by a space
+                let is_directory = candidate.ends_with('/');
+                let completed_line = format!("{}{}", &self.command_buffer[..completion.start], candidate);
+                let takes_no_args = commands::find(completed_line.trim()).is_some_and(|c| c.args.is_empty());
+                if !is_directory && !takes_no_args {
+                    candidate.push(' ');
+                }
+                self.command_buffer.replace_range(completion.start..self.command_cursor, &candidate);
+                self.command_cursor = completion.start + candidate.len();
             }
-            
-            folder.parent_id = destination_folder_id;
-            
-            if destination_folder_id.is_none() {
-                // Add to root folders
-                if !self.notebook.root_folder_ids.contains(&folder_id) {
-                    self.notebook.root_folder_ids.push(folder_id);
+            _ => {
+                let prefix = commands::common_prefix(&completion.candidates).to_string();
+                if prefix.len() > self.command_cursor - completion.start {
+                    self.command_buffer.replace_range(completion.start..self.command_cursor, &prefix);
+                    self.command_cursor = completion.start + prefix.len();
                 }
+                self.command_completion_start = completion.start;
+                self.command_completions = completion.candidates;
             }
-            
-            Ok(())
-        } else {
-            Err("Folder not found".to_string())
         }
     }
     
-    fn is_folder_ancestor(&self, ancestor_id: Uuid, descendant_id: Uuid) -> bool {
-        if ancestor_id == descendant_id {
-            return true;
+    /// State to keep for the next session
+    pub fn session_state(&self) -> SessionState {
+        let mut jump_list = self.jump_list.clone();
+        let current_id = self.current_note.as_ref().map(|n| n.id);
+        if let Some(visit) = jump_list.get_mut(self.jump_index).filter(|v| Some(v.note_id) == current_id) {
+            visit.cursor = self.editor_cursor;
+            visit.scroll = self.editor_scroll;
         }
+        SessionState {
+            command_history: self.command_history.clone(),
+            recent_notes: self.recent_notes.clone(),
+            jump_list,
+            jump_index: self.jump_index,
+            pinned_collapsed: self.pinned_collapsed,
+            saved_searches_collapsed: self.saved_searches_collapsed,
+        }
+    }
+    
+    /// Restore the previous session, dropping notes that no longer exist
+    pub fn restore_session(&mut self, session: SessionState) {
+        self.command_history = session.command_history;
+        let excess = self.command_history.len().saturating_sub(MAX_COMMAND_HISTORY);
+        self.command_history.drain(..excess);
         
-        if let Some(descendant) = self.notebook.folders.get(&descendant_id) {
-            if let Some(parent_id) = descendant.parent_id {
-                return self.is_folder_ancestor(ancestor_id, parent_id);
+        self.recent_notes = session.recent_notes;
+        self.recent_notes.retain(|id| self.notebook.notes.contains_key(id));
+        self.recent_notes.truncate(MAX_RECENT_NOTES);
+        
+        self.jump_list = session.jump_list;
+        self.jump_index = session.jump_index;
+        let missing: Vec<Uuid> = self.jump_list
+            .iter()
+            .map(|v| v.note_id)
+            .filter(|id| !self.notebook.notes.contains_key(id))
+            .collect();
+        for note_id in missing {
+            self.forget_note_visits(note_id);
+        }
+        self.jump_index = self.jump_index.min(self.jump_list.len().saturating_sub(1));
+        
+        self.pinned_collapsed = session.pinned_collapsed;
+        self.saved_searches_collapsed = session.saved_searches_collapsed;
+        self.refresh_tree_view();
+    }
+    
+    /// Show or hide the backlinks panel
+    pub fn toggle_backlinks(&mut self) {
+        self.backlinks_enabled = !self.backlinks_enabled;
+        if self.backlinks_enabled {
+            self.refresh_backlinks();
+            self.set_message(format!("Backlinks shown - {} linking lines", self.backlinks.len()));
+        } else {
+            if self.focused_pane == FocusedPane::Backlinks {
+                self.focused_pane = FocusedPane::Editor;
+            }
+            self.set_message("Backlinks hidden".to_string());
+        }
+    }
+    
+    /// Look up the notes linking to the current note in the link index
+    pub fn refresh_backlinks(&mut self) {
+        self.backlinks = match self.current_note {
+            Some(ref note) => self.link_index.backlinks(&self.notebook, note.id),
+            None => Vec::new(),
+        };
+        self.backlink_selected = self.backlink_selected.min(self.backlinks.len().saturating_sub(1));
+    }
+    
+    pub fn next_backlink(&mut self) {
+        if self.backlink_selected + 1 < self.backlinks.len() {
+            self.backlink_selected += 1;
+        }
+    }
+    
+    pub fn previous_backlink(&mut self) {
+        self.backlink_selected = self.backlink_selected.saturating_sub(1);
+    }
+    
+    /// Open the selected linking note with the cursor on the linking line
+    pub fn open_selected_backlink(&mut self) {
+        let Some(backlink) = self.backlinks.get(self.backlink_selected).cloned() else {
+            return;
+        };
+        if self.save_status == SaveStatus::Modified {
+            if let Err(e) = self.save_current_note() {
+                self.set_message(e);
+                return;
             }
         }
         
-        false
+        self.open_note_by_id(backlink.source_id);
+        self.jump_to_line(backlink.line_number);
+        self.backlink_selected = 0;
+        self.set_message(format!("Opened '{}' from backlinks", backlink.source_title));
     }
     
-    pub fn quit(&mut self) {
-        self.should_quit = true;
+    /// Move focus to the next visible pane
+    pub fn focus_next_pane(&mut self) {
+        let mut panes = vec![FocusedPane::Folders, FocusedPane::Editor];
+        if self.preview_enabled {
+            panes.push(FocusedPane::Preview);
+        }
+        if self.search_results_enabled {
+            panes.push(FocusedPane::SearchResults);
+        }
+        if self.backlinks_enabled {
+            panes.push(FocusedPane::Backlinks);
+        }
+        // A pane that was hidden falls back to the editor
+        self.focused_pane = match panes.iter().position(|p| *p == self.focused_pane) {
+            Some(i) => panes[(i + 1) % panes.len()].clone(),
+            None => FocusedPane::Editor,
+        };
     }
     
     /// Toggle markdown preview mode
@@ -1041,6 +3769,7 @@ impl App {
     /// Scroll editor up by one line
     pub fn scroll_up(&mut self) {
         self.editor_scroll = self.editor_scroll.saturating_sub(1);
+        self.keep_cursor_in_view();
     }
     
     /// Scroll editor down by one line
@@ -1049,11 +3778,13 @@ impl App {
         if self.editor_scroll < content_lines.saturating_sub(1) {
             self.editor_scroll += 1;
         }
+        self.keep_cursor_in_view();
     }
     
     /// Scroll editor up by half a page (Ctrl+U)
     pub fn scroll_half_page_up(&mut self) {
         self.editor_scroll = self.editor_scroll.saturating_sub(10);
+        self.keep_cursor_in_view();
     }
     
     /// Scroll editor down by half a page (Ctrl+D)
@@ -1061,11 +3792,13 @@ impl App {
         let content_lines = self.editor_content.lines().count() as u16;
         let new_scroll = self.editor_scroll + 10;
         self.editor_scroll = new_scroll.min(content_lines.saturating_sub(1));
+        self.keep_cursor_in_view();
     }
     
     /// Scroll editor up by a full page
     pub fn scroll_page_up(&mut self) {
         self.editor_scroll = self.editor_scroll.saturating_sub(20);
+        self.keep_cursor_in_view();
     }
     
     /// Scroll editor down by a full page
@@ -1073,17 +3806,20 @@ impl App {
         let content_lines = self.editor_content.lines().count() as u16;
         let new_scroll = self.editor_scroll + 20;
         self.editor_scroll = new_scroll.min(content_lines.saturating_sub(1));
+        self.keep_cursor_in_view();
     }
     
     /// Jump to top of editor
     pub fn scroll_to_top(&mut self) {
         self.editor_scroll = 0;
+        self.keep_cursor_in_view();
     }
     
     /// Jump to bottom of editor
     pub fn scroll_to_bottom(&mut self) {
         let content_lines = self.editor_content.lines().count() as u16;
         self.editor_scroll = content_lines.saturating_sub(1);
+        self.keep_cursor_in_view();
     }
     
     /// Ensure cursor is visible after scrolling
@@ -1100,6 +3836,18 @@ impl App {
             self.editor_scroll = self.editor_cursor.0.saturating_sub(visible_height - 1);
         }
     }
+    
+    /// Move the cursor into the visible lines after scrolling past it
+    pub fn keep_cursor_in_view(&mut self) {
+        let visible_height = 20; // Same approximation as adjust_scroll_to_cursor
+        let last_visible = self.editor_scroll + visible_height - 1;
+        let row = self.editor_cursor.0.clamp(self.editor_scroll, last_visible);
+        if row != self.editor_cursor.0 {
+            let last_row = self.editor_content.split('\n').count().saturating_sub(1) as u16;
+            self.editor_cursor.0 = row.min(last_row);
+            self.clamp_cursor_column();
+        }
+    }
 }
 
 impl Default for App {
@@ -1175,9 +3923,10 @@ fn sanitize_filename(filename: &str) -> String {
         .to_string()
 }
 
-fn run_external_editor(editor: &str, file_path: &std::path::PathBuf) -> Result<(), String> {
+fn run_external_editor(editor: &str, file_path: &std::path::PathBuf, keyboard_enhanced: bool) -> Result<(), String> {
     use crossterm::{
         execute,
+        event::{DisableBracketedPaste, EnableBracketedPaste, KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags},
         terminal::{disable_raw_mode, enable_raw_mode, Clear, ClearType},
         cursor::Show
     };
@@ -1189,8 +3938,12 @@ fn run_external_editor(editor: &str, file_path: &std::path::PathBuf) -> Result<(
     // Disable raw mode first
     disable_raw_mode().map_err(|e| format!("Failed to disable raw mode: {}", e))?;
     
-    // Clear screen and show cursor
-    execute!(stdout, Clear(ClearType::All), Show)
+    // Clear screen and show cursor; the editor manages its own paste mode and key reporting
+    if keyboard_enhanced {
+        execute!(stdout, PopKeyboardEnhancementFlags)
+            .map_err(|e| format!("Failed to reset keyboard mode: {}", e))?;
+    }
+    execute!(stdout, DisableBracketedPaste, Clear(ClearType::All), Show)
         .map_err(|e| format!("Failed to clear screen: {}", e))?;
     
     // Flush to ensure terminal is ready
@@ -1212,8 +3965,12 @@ fn run_external_editor(editor: &str, file_path: &std::path::PathBuf) -> Result<(
     enable_raw_mode().map_err(|e| format!("Failed to re-enable raw mode: {}", e))?;
     
     // Clear and reset for our TUI
-    execute!(stdout, Clear(ClearType::All))
+    execute!(stdout, Clear(ClearType::All), EnableBracketedPaste)
         .map_err(|e| format!("Failed to clear screen for TUI: {}", e))?;
+    if keyboard_enhanced {
+        execute!(stdout, PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES))
+            .map_err(|e| format!("Failed to restore keyboard mode: {}", e))?;
+    }
     
     if status.success() {
         Ok(())
@@ -1221,3 +3978,248 @@ fn run_external_editor(editor: &str, file_path: &std::path::PathBuf) -> Result<(
         Err(format!("{} exited with code {:?}", editor, status.code()))
     }
 }
+
+/// Split a tag list into the finished part and the tag still being typed
+fn split_last_tag(input: &str) -> (&str, &str) {
+    let split = input
+        .rfind(|c: char| c == ',' || c.is_whitespace())
+        .map_or(0, |i| i + 1);
+    (&input[..split], &input[split..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An app holding only the given notes, each as (title, content)
+    fn app_with_notes(notes: &[(&str, &str)]) -> (App, Vec<Uuid>) {
+        let mut notebook = NotebookData::new();
+        let ids = notes.iter()
+            .map(|(title, content)| {
+                let mut note = Note::new(title.to_string(), None);
+                note.content = content.to_string();
+                let id = note.id;
+                notebook.add_note(note);
+                id
+            })
+            .collect();
+        let mut app = App::new();
+        app.load_notebook(notebook);
+        (app, ids)
+    }
+
+    #[test]
+    fn scrolling_past_the_cursor_keeps_it_on_screen() {
+        let content = (0..100).map(|i| format!("line {}", i)).collect::<Vec<_>>().join("\n");
+        let (mut app, ids) = app_with_notes(&[("Long", &content)]);
+        app.select_note(ids[0]);
+        app.focused_pane = FocusedPane::Editor;
+        app.editor_cursor = (2, 4);
+
+        app.scroll_half_page_down();
+        app.scroll_page_down();
+        assert_eq!(app.editor_scroll, 30);
+        assert_eq!(app.editor_cursor, (30, 4));
+
+        app.scroll_to_top();
+        assert!(app.editor_cursor.0 < 20, "cursor left at row {}", app.editor_cursor.0);
+        assert!(app.editor_cursor.0 >= app.editor_scroll);
+    }
+
+    #[test]
+    fn toggling_a_task_in_another_note_saves_it_like_the_editor() {
+        let (mut app, ids) = app_with_notes(&[("Open", "nothing here"), ("Chores", "- [ ] water plants #garden")]);
+        app.select_note(ids[0]);
+        app.open_tasks_view();
+        app.toggle_selected_task();
+
+        let chores = &app.notebook.notes[&ids[1]];
+        assert!(chores.content.starts_with("- [x] water plants #garden ✅ "));
+        assert_eq!(chores.tags, vec!["garden".to_string()]);
+        // The completion stamp is only found if the note was re-indexed
+        let year = chrono::Local::now().format("%Y").to_string();
+        let results = app.enhanced_search.find(&app.notebook, &SearchQuery::words(&year)).unwrap();
+        assert_eq!(results.iter().map(|r| r.note.id).collect::<Vec<_>>(), vec![ids[1]]);
+    }
+
+    #[test]
+    fn renaming_a_note_rejects_titles_that_would_break_its_links() {
+        let (mut app, ids) = app_with_notes(&[("Plan", "# Plan"), ("Log", "see [[Plan]]")]);
+        app.select_note(ids[0]);
+        app.start_rename().unwrap();
+        for bad in ["Plan#B", "Plan|B", "Plan [[B", "Plan]]B", "Plan]"] {
+            assert!(app.submit_rename(bad).is_err(), "accepted '{}'", bad);
+        }
+        assert_eq!(app.notebook.notes[&ids[0]].title, "Plan");
+        assert_eq!(app.notebook.notes[&ids[1]].content, "see [[Plan]]");
+    }
+
+    #[test]
+    fn saving_drops_only_the_tags_that_came_from_deleted_hashtags() {
+        let (mut app, ids) = app_with_notes(&[("Note", "")]);
+        app.select_note(ids[0]);
+        app.input_buffer = "manual, shared".to_string();
+        app.finish_tag_edit();
+
+        app.editor_content = "#inline and #shared".to_string();
+        app.save_current_note().unwrap();
+        assert_eq!(app.notebook.notes[&ids[0]].tags, vec!["manual", "shared", "inline"]);
+
+        app.editor_content = "no tags left".to_string();
+        app.save_current_note().unwrap();
+        assert_eq!(app.notebook.notes[&ids[0]].tags, vec!["manual", "shared"]);
+    }
+
+    fn current_id(app: &App) -> Option<Uuid> {
+        app.current_note.as_ref().map(|n| n.id)
+    }
+
+    #[test]
+    fn jumping_back_and_forward_restores_the_cursor() {
+        let (mut app, ids) = app_with_notes(&[("One", "a\nb\nc"), ("Two", "x\ny"), ("Three", "")]);
+        app.select_note(ids[0]);
+        app.editor_cursor = (2, 1);
+        app.select_note(ids[1]);
+        app.editor_cursor = (1, 0);
+
+        app.jump(-1);
+        assert_eq!((current_id(&app), app.editor_cursor), (Some(ids[0]), (2, 1)));
+        app.jump(-1);
+        assert_eq!(current_id(&app), Some(ids[0]), "already at the oldest visit");
+        app.jump(1);
+        assert_eq!((current_id(&app), app.editor_cursor), (Some(ids[1]), (1, 0)));
+
+        // Opening a note after going back drops the visits ahead
+        app.jump(-1);
+        app.select_note(ids[2]);
+        app.jump(1);
+        assert_eq!(current_id(&app), Some(ids[2]));
+        app.jump(-1);
+        assert_eq!(current_id(&app), Some(ids[0]));
+    }
+
+    #[test]
+    fn recent_notes_and_visits_survive_a_restart() {
+        let (mut app, ids) = app_with_notes(&[("One", ""), ("Two", ""), ("Three", "")]);
+        for id in [ids[0], ids[1], ids[2], ids[0]] {
+            app.select_note(id);
+        }
+        assert_eq!(app.recent_notes, vec![ids[0], ids[2], ids[1]]);
+        let saved = serde_json::to_string(&app.session_state()).unwrap();
+
+        let mut restarted = App::new();
+        restarted.load_notebook(app.notebook.clone());
+        restarted.restore_session(serde_json::from_str(&saved).unwrap());
+        assert_eq!(restarted.recent_notes, app.recent_notes);
+        // Nothing is open yet, so going back first reopens the last visit
+        restarted.jump(-1);
+        assert_eq!(current_id(&restarted), Some(ids[0]));
+        restarted.jump(-1);
+        assert_eq!(current_id(&restarted), Some(ids[2]));
+    }
+
+    #[test]
+    fn pinned_notes_keep_their_order_when_saved() {
+        let (mut app, ids) = app_with_notes(&[("One", ""), ("Two", ""), ("Three", "")]);
+        for id in [ids[2], ids[0]] {
+            app.select_note(id);
+            app.toggle_pin().unwrap();
+        }
+        let pinned_titles = |notebook: &NotebookData| -> Vec<String> {
+            notebook.pinned_notes().iter().map(|n| n.title.clone()).collect()
+        };
+        assert_eq!(pinned_titles(&app.notebook), vec!["Three", "One"]);
+
+        app.focused_pane = FocusedPane::Folders;
+        app.selected_folder_index = app.folder_tree_items.iter()
+            .position(|item| item.section == TreeSection::Pinned && item.id == ids[0])
+            .unwrap();
+        app.move_pinned_note(-1).unwrap();
+        assert_eq!(pinned_titles(&app.notebook), vec!["One", "Three"]);
+        assert_eq!(app.get_selected_item().map(|item| item.id), Some(ids[0]));
+
+        let saved: NotebookData = serde_json::from_str(&serde_json::to_string(&app.notebook).unwrap()).unwrap();
+        assert_eq!(pinned_titles(&saved), vec!["One", "Three"]);
+
+        app.toggle_pin().unwrap();
+        assert_eq!(pinned_titles(&app.notebook), vec!["Three"]);
+    }
+
+    #[test]
+    fn saved_searches_list_their_notes_and_can_be_deleted() {
+        let (mut app, ids) = app_with_notes(&[("Garden", "tomatoes"), ("Kitchen", "more tomatoes"), ("Desk", "pens")]);
+        assert!(app.save_search("Tomatoes").is_err(), "nothing searched yet");
+        app.search_notes("tomatoes".to_string());
+        app.save_search("Tomatoes").unwrap();
+        assert!(app.save_search("tomatoes").is_err(), "names are unique");
+
+        let listed = |app: &App| -> HashSet<Uuid> {
+            app.folder_tree_items.iter()
+                .filter(|item| item.section == TreeSection::SavedSearches && item.item_type == TreeItemType::Note)
+                .map(|item| item.id)
+                .collect()
+        };
+        assert_eq!(app.get_selected_item().map(|item| item.item_type.clone()), Some(TreeItemType::SavedSearch));
+        assert_eq!(listed(&app), HashSet::from([ids[0], ids[1]]));
+
+        // Saved searches are stored with the notebook and listed again when it is loaded
+        let saved: NotebookData = serde_json::from_str(&serde_json::to_string(&app.notebook).unwrap()).unwrap();
+        let mut reloaded = App::new();
+        reloaded.load_notebook(saved);
+        assert_eq!(listed(&reloaded), HashSet::from([ids[0], ids[1]]));
+
+        app.start_delete_confirmation().unwrap();
+        app.confirm_delete().unwrap();
+        assert!(app.notebook.saved_searches.is_empty());
+        assert!(listed(&app).is_empty());
+    }
+
+    #[test]
+    fn the_results_panel_selects_and_opens_each_match() {
+        let (mut app, _) = app_with_notes(&[("Alpha", "one match"), ("Beta", "match\nanother match")]);
+        app.search_notes("match".to_string());
+        assert!(app.search_results_enabled);
+        assert_eq!(app.focused_pane, FocusedPane::SearchResults);
+        let rows = app.search_result_rows();
+        assert_eq!(rows.len(), 3);
+
+        for _ in 0..5 {
+            app.next_search_result();
+        }
+        assert_eq!(app.search_result_selected, 2);
+        app.open_search_result(app.search_result_selected);
+        let (result, m) = rows[2];
+        let result = &app.enhanced_search_results[result];
+        let found = &result.matches[m.unwrap()];
+        assert_eq!(current_id(&app), Some(result.note.id));
+        assert_eq!(app.editor_cursor, (found.line_number as u16, found.start_offset as u16));
+
+        for _ in 0..5 {
+            app.previous_search_result();
+        }
+        assert_eq!(app.search_result_selected, 0);
+    }
+
+    #[test]
+    fn cancelling_a_search_as_you_type_restores_the_view() {
+        let (mut app, ids) = app_with_notes(&[("Home", "line\nline\nline"), ("Away", "a needle")]);
+        app.select_note(ids[0]);
+        app.editor_cursor = (2, 1);
+
+        app.start_incremental_search();
+        app.input_buffer = "needle".to_string();
+        app.search_input_changed();
+        app.run_pending_search();
+        assert_eq!(current_id(&app), Some(ids[0]), "waits for typing to pause");
+        app.search_pending_since = Instant::now().checked_sub(SEARCH_DEBOUNCE);
+        app.run_pending_search();
+        assert_eq!((current_id(&app), app.editor_cursor), (Some(ids[1]), (0, 2)));
+
+        app.cancel_incremental_search();
+        assert_eq!(app.mode, AppMode::Normal);
+        assert_eq!((current_id(&app), app.editor_cursor), (Some(ids[0]), (2, 1)));
+        assert!(!app.search_results_enabled);
+        // Previewed matches are not visits
+        assert_eq!(app.recent_notes, vec![ids[0]]);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::snippets::Snippet;
 use std::collections::HashMap;
 
 #[derive(Debug, Clone)]
@@ -6,6 +7,7 @@ pub struct AutocompleteSuggestion {
     pub completion: String,    // What gets inserted
     pub description: String,   // User-friendly description
     pub cursor_offset: i16,    // Where to position cursor after insertion (negative = back from end)
+    pub snippet: Option<String>, // User snippet body, expanded with tab stops instead of `completion`
 }
 
 #[derive(Debug, Clone)]
@@ -62,12 +64,14 @@ impl AutocompleteState {
 
 pub struct MarkdownAutocomplete {
     suggestions: HashMap<String, Vec<AutocompleteSuggestion>>,
+    snippets: Vec<AutocompleteSuggestion>,
 }
 
 impl MarkdownAutocomplete {
     pub fn new() -> Self {
         let mut autocomplete = Self {
             suggestions: HashMap::new(),
+            snippets: Vec::new(),
         };
         autocomplete.initialize_suggestions();
         autocomplete
@@ -80,6 +84,7 @@ impl MarkdownAutocomplete {
             completion: "# ".to_string(),
             description: "Heading 1".to_string(),
             cursor_offset: 0,
+            snippet: None,
         });
 
         self.add_suggestion("## ", AutocompleteSuggestion {
@@ -87,6 +92,7 @@ impl MarkdownAutocomplete {
             completion: "## ".to_string(),
             description: "Heading 2".to_string(),
             cursor_offset: 0,
+            snippet: None,
         });
 
         self.add_suggestion("### ", AutocompleteSuggestion {
@@ -94,6 +100,7 @@ impl MarkdownAutocomplete {
             completion: "### ".to_string(),
             description: "Heading 3".to_string(),
             cursor_offset: 0,
+            snippet: None,
         });
 
         // Lists
@@ -102,6 +109,7 @@ impl MarkdownAutocomplete {
             completion: "- ".to_string(),
             description: "Bullet list item".to_string(),
             cursor_offset: 0,
+            snippet: None,
         });
 
         self.add_suggestion("* ", AutocompleteSuggestion {
@@ -109,6 +117,7 @@ impl MarkdownAutocomplete {
             completion: "* ".to_string(),
             description: "Bullet list item (alt)".to_string(),
             cursor_offset: 0,
+            snippet: None,
         });
 
         self.add_suggestion("1. ", AutocompleteSuggestion {
@@ -116,6 +125,7 @@ impl MarkdownAutocomplete {
             completion: "1. ".to_string(),
             description: "Numbered list item".to_string(),
             cursor_offset: 0,
+            snippet: None,
         });
 
         // Checkboxes
@@ -124,6 +134,7 @@ impl MarkdownAutocomplete {
             completion: "- [ ] ".to_string(),
             description: "Todo checkbox (unchecked)".to_string(),
             cursor_offset: 0,
+            snippet: None,
         });
 
         self.add_suggestion("- [x] ", AutocompleteSuggestion {
@@ -131,6 +142,7 @@ impl MarkdownAutocomplete {
             completion: "- [x] ".to_string(),
             description: "Todo checkbox (checked)".to_string(),
             cursor_offset: 0,
+            snippet: None,
         });
 
         // Code blocks
@@ -139,6 +151,7 @@ impl MarkdownAutocomplete {
             completion: "```\n\n```".to_string(),
             description: "Code block".to_string(),
             cursor_offset: -4, // Position cursor inside the code block
+            snippet: None,
         });
 
         self.add_suggestion("`", AutocompleteSuggestion {
@@ -146,6 +159,7 @@ impl MarkdownAutocomplete {
             completion: "``".to_string(),
             description: "Inline code".to_string(),
             cursor_offset: -1, // Position cursor between the backticks
+            snippet: None,
         });
 
         // Emphasis
@@ -154,6 +168,7 @@ impl MarkdownAutocomplete {
             completion: "****".to_string(),
             description: "Bold text".to_string(),
             cursor_offset: -2,
+            snippet: None,
         });
 
         self.add_suggestion("*", AutocompleteSuggestion {
@@ -161,6 +176,7 @@ impl MarkdownAutocomplete {
             completion: "**".to_string(),
             description: "Italic text".to_string(),
             cursor_offset: -1,
+            snippet: None,
         });
 
         // Links and images
@@ -169,6 +185,7 @@ impl MarkdownAutocomplete {
             completion: "[](url)".to_string(),
             description: "Link".to_string(),
             cursor_offset: -5, // Position cursor at the beginning of link text
+            snippet: None,
         });
 
         self.add_suggestion("![", AutocompleteSuggestion {
@@ -176,6 +193,7 @@ impl MarkdownAutocomplete {
             completion: "![alt text](image.png)".to_string(),
             description: "Image".to_string(),
             cursor_offset: -17, // Position cursor at alt text
+            snippet: None,
         });
 
         // Blockquotes
@@ -184,6 +202,7 @@ impl MarkdownAutocomplete {
             completion: "> ".to_string(),
             description: "Blockquote".to_string(),
             cursor_offset: 0,
+            snippet: None,
         });
 
         // Tables
@@ -192,6 +211,7 @@ impl MarkdownAutocomplete {
             completion: "| Header 1 | Header 2 |\n|----------|----------|\n| Cell 1   | Cell 2   |".to_string(),
             description: "Table".to_string(),
             cursor_offset: -49, // Position cursor at first header
+            snippet: None,
         });
 
         // Horizontal rule
@@ -200,13 +220,39 @@ impl MarkdownAutocomplete {
             completion: "---".to_string(),
             description: "Horizontal rule".to_string(),
             cursor_offset: 0,
+            snippet: None,
         });
     }
 
+    /// Replace the user snippets, which trigger on a whole word before the cursor
+    pub fn set_snippets(&mut self, snippets: Vec<Snippet>) {
+        self.snippets = snippets
+            .into_iter()
+            .map(|snippet| {
+                let body = snippet.body.text();
+                AutocompleteSuggestion {
+                    description: if snippet.description.is_empty() {
+                        format!("Snippet: {}", snippet.trigger)
+                    } else {
+                        snippet.description
+                    },
+                    trigger: snippet.trigger,
+                    completion: body.clone(),
+                    cursor_offset: 0,
+                    snippet: Some(body),
+                }
+            })
+            .collect();
+    }
+
+    pub fn snippet_count(&self) -> usize {
+        self.snippets.len()
+    }
+
     fn add_suggestion(&mut self, trigger: &str, suggestion: AutocompleteSuggestion) {
         self.suggestions
             .entry(trigger.to_string())
-            .or_insert_with(Vec::new)
+            .or_default()
             .push(suggestion);
     }
 
@@ -230,6 +276,22 @@ impl MarkdownAutocomplete {
         // Extract text from start of line up to cursor
         let line_up_to_cursor = &current_line[..col];
         
+        // User snippets trigger on the whole word before the cursor
+        let word_start = line_up_to_cursor
+            .rfind(char::is_whitespace)
+            .map_or(0, |i| i + line_up_to_cursor[i..].chars().next().map_or(1, char::len_utf8));
+        let word = &line_up_to_cursor[word_start..];
+        if !word.is_empty() {
+            let matches: Vec<AutocompleteSuggestion> = self.snippets
+                .iter()
+                .filter(|s| s.trigger == word)
+                .cloned()
+                .collect();
+            if !matches.is_empty() {
+                return Some((matches, word_start));
+            }
+        }
+        
         // Only trigger at the beginning of a line or after whitespace
         let should_trigger = line_up_to_cursor.is_empty() 
             || line_up_to_cursor.chars().all(|c| c.is_whitespace())
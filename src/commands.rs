@@ -0,0 +1,1000 @@
+use crate::app::{App, AppMode, FocusedPane, SaveStatus, TreeItemType};
+use crate::config;
+use crate::fuzzy;
+use crate::links;
+use std::fs;
+use std::path::PathBuf;
+
+/// What a command's argument is, for completion
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArgKind {
+    None,
+    Text,
+    Directory,
+    Note,
+    Choice(&'static [&'static str]),
+}
+
+/// An action that can be run from the `:` command line or the command palette
+pub struct Command {
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    pub description: &'static str,
+    pub keybinding: Option<&'static str>,
+    /// Argument hint such as `<dir>`; angle brackets mark a required argument
+    pub args: &'static str,
+    pub arg_kind: ArgKind,
+    pub run: fn(&mut App, &str),
+}
+
+impl Command {
+    pub fn requires_argument(&self) -> bool {
+        self.args.starts_with('<')
+    }
+
+    /// The command as typed, with its argument hint
+    pub fn usage(&self) -> String {
+        if self.args.is_empty() {
+            format!(":{}", self.name)
+        } else {
+            format!(":{} {}", self.name, self.args)
+        }
+    }
+}
+
+pub static COMMANDS: &[Command] = &[
+    Command {
+        name: "write",
+        aliases: &["w"],
+        description: "Save the open note",
+        keybinding: Some("Ctrl+S"),
+        args: "",
+        arg_kind: ArgKind::None,
+        run: write,
+    },
+    Command {
+        name: "quit",
+        aliases: &["q"],
+        description: "Quit Scribble",
+        keybinding: Some("q"),
+        args: "",
+        arg_kind: ArgKind::None,
+        run: quit,
+    },
+    Command {
+        name: "wq",
+        aliases: &[],
+        description: "Save the open note and quit",
+        keybinding: None,
+        args: "",
+        arg_kind: ArgKind::None,
+        run: write_quit,
+    },
+    Command {
+        name: "open",
+        aliases: &["o"],
+        description: "Open a note by title, or find one with quick open",
+        keybinding: Some("Ctrl+P"),
+        args: "[note]",
+        arg_kind: ArgKind::Note,
+        run: open,
+    },
+    Command {
+        name: "recent",
+        aliases: &[],
+        description: "List recently opened notes",
+        keybinding: Some("R"),
+        args: "",
+        arg_kind: ArgKind::None,
+        run: recent,
+    },
+    Command {
+        name: "back",
+        aliases: &[],
+        description: "Go back to the previously visited note",
+        keybinding: Some("Ctrl+O"),
+        args: "",
+        arg_kind: ArgKind::None,
+        run: jump_back,
+    },
+    Command {
+        name: "forward",
+        aliases: &[],
+        description: "Go forward to the next visited note",
+        keybinding: Some("Ctrl+N"),
+        args: "",
+        arg_kind: ArgKind::None,
+        run: jump_forward,
+    },
+    Command {
+        name: "today",
+        aliases: &["daily"],
+        description: "Open or create today's journal entry",
+        keybinding: Some("D"),
+        args: "",
+        arg_kind: ArgKind::None,
+        run: today,
+    },
+    Command {
+        name: "yesterday",
+        aliases: &[],
+        description: "Open or create yesterday's journal entry",
+        keybinding: None,
+        args: "",
+        arg_kind: ArgKind::None,
+        run: yesterday,
+    },
+    Command {
+        name: "tomorrow",
+        aliases: &[],
+        description: "Open or create tomorrow's journal entry",
+        keybinding: None,
+        args: "",
+        arg_kind: ArgKind::None,
+        run: tomorrow,
+    },
+    Command {
+        name: "prevday",
+        aliases: &[],
+        description: "Open the previous journal entry",
+        keybinding: Some("["),
+        args: "",
+        arg_kind: ArgKind::None,
+        run: previous_journal_entry,
+    },
+    Command {
+        name: "nextday",
+        aliases: &[],
+        description: "Open the next journal entry",
+        keybinding: Some("]"),
+        args: "",
+        arg_kind: ArgKind::None,
+        run: next_journal_entry,
+    },
+    Command {
+        name: "new",
+        aliases: &[],
+        description: "Create a note in the selected folder",
+        keybinding: Some("n"),
+        args: "[title]",
+        arg_kind: ArgKind::Text,
+        run: new_note,
+    },
+    Command {
+        name: "folder",
+        aliases: &[],
+        description: "Create a top-level folder",
+        keybinding: Some("f"),
+        args: "[name]",
+        arg_kind: ArgKind::Text,
+        run: new_folder,
+    },
+    Command {
+        name: "subfolder",
+        aliases: &[],
+        description: "Create a folder inside the selected folder",
+        keybinding: Some("F"),
+        args: "[name]",
+        arg_kind: ArgKind::Text,
+        run: new_subfolder,
+    },
+    Command {
+        name: "rename",
+        aliases: &[],
+        description: "Rename the open note or the selected item",
+        keybinding: Some("r"),
+        args: "[name]",
+        arg_kind: ArgKind::Text,
+        run: rename,
+    },
+    Command {
+        name: "delete",
+        aliases: &[],
+        description: "Delete the selected note or folder",
+        keybinding: Some("d"),
+        args: "",
+        arg_kind: ArgKind::None,
+        run: delete,
+    },
+    Command {
+        name: "move",
+        aliases: &[],
+        description: "Move the selected note or folder",
+        keybinding: Some("m"),
+        args: "",
+        arg_kind: ArgKind::None,
+        run: move_item,
+    },
+    Command {
+        name: "edit",
+        aliases: &[],
+        description: "Edit the open note in insert mode",
+        keybinding: Some("i"),
+        args: "",
+        arg_kind: ArgKind::None,
+        run: edit,
+    },
+    Command {
+        name: "external",
+        aliases: &[],
+        description: "Edit the open note in $EDITOR",
+        keybinding: Some("e"),
+        args: "",
+        arg_kind: ArgKind::None,
+        run: external,
+    },
+    Command {
+        name: "undo",
+        aliases: &[],
+        description: "Undo the last change to the open note",
+        keybinding: Some("u"),
+        args: "",
+        arg_kind: ArgKind::None,
+        run: undo,
+    },
+    Command {
+        name: "search",
+        aliases: &[],
+        description: "Search notes",
+        keybinding: Some("/ (outside editor)"),
+        args: "",
+        arg_kind: ArgKind::None,
+        run: search,
+    },
+    Command {
+        name: "find",
+        aliases: &[],
+        description: "Advanced search with regex:, case: and fuzzy: prefixes",
+        keybinding: Some("Ctrl+F"),
+        args: "",
+        arg_kind: ArgKind::None,
+        run: advanced_search,
+    },
+    Command {
+        name: "findnote",
+        aliases: &[],
+        description: "Find in the open note",
+        keybinding: Some("/ (editor)"),
+        args: "",
+        arg_kind: ArgKind::None,
+        run: find_in_note,
+    },
+    Command {
+        name: "findnext",
+        aliases: &[],
+        description: "Move to the next match of the find in the open note",
+        keybinding: Some("n (after /)"),
+        args: "",
+        arg_kind: ArgKind::None,
+        run: next_find_match,
+    },
+    Command {
+        name: "findprev",
+        aliases: &[],
+        description: "Move to the previous match of the find in the open note",
+        keybinding: Some("N (after /)"),
+        args: "",
+        arg_kind: ArgKind::None,
+        run: previous_find_match,
+    },
+    Command {
+        name: "results",
+        aliases: &[],
+        description: "Toggle the results panel of the last search",
+        keybinding: None,
+        args: "",
+        arg_kind: ArgKind::None,
+        run: search_results,
+    },
+    Command {
+        name: "nextmatch",
+        aliases: &[],
+        description: "Open the next match of the last search",
+        keybinding: Some("n (results)"),
+        args: "",
+        arg_kind: ArgKind::None,
+        run: next_match,
+    },
+    Command {
+        name: "prevmatch",
+        aliases: &[],
+        description: "Open the previous match of the last search",
+        keybinding: Some("N (results)"),
+        args: "",
+        arg_kind: ArgKind::None,
+        run: previous_match,
+    },
+    Command {
+        name: "replace",
+        aliases: &[],
+        description: "Find and replace in the open note",
+        keybinding: Some("Ctrl+R"),
+        args: "",
+        arg_kind: ArgKind::None,
+        run: replace,
+    },
+    Command {
+        name: "tags",
+        aliases: &[],
+        description: "Edit the tags of the open note",
+        keybinding: Some("t"),
+        args: "",
+        arg_kind: ArgKind::None,
+        run: edit_tags,
+    },
+    Command {
+        name: "tagbrowser",
+        aliases: &[],
+        description: "Browse tags and filter the tree by one",
+        keybinding: Some("T"),
+        args: "",
+        arg_kind: ArgKind::None,
+        run: tag_browser,
+    },
+    Command {
+        name: "pin",
+        aliases: &["unpin"],
+        description: "Pin or unpin the note to the top of the tree",
+        keybinding: Some("p"),
+        args: "",
+        arg_kind: ArgKind::None,
+        run: toggle_pin,
+    },
+    Command {
+        name: "task",
+        aliases: &[],
+        description: "Toggle the task checkbox on the cursor line",
+        keybinding: Some("x"),
+        args: "",
+        arg_kind: ArgKind::None,
+        run: toggle_task,
+    },
+    Command {
+        name: "savesearch",
+        aliases: &[],
+        description: "Save the last search as a smart folder in the tree",
+        keybinding: None,
+        args: "<name>",
+        arg_kind: ArgKind::Text,
+        run: save_search,
+    },
+    Command {
+        name: "editsearch",
+        aliases: &[],
+        description: "Edit the query of the selected saved search",
+        keybinding: Some("E"),
+        args: "",
+        arg_kind: ArgKind::None,
+        run: edit_saved_search,
+    },
+    Command {
+        name: "tasks",
+        aliases: &[],
+        description: "Show open tasks across all notes",
+        keybinding: None,
+        args: "",
+        arg_kind: ArgKind::None,
+        run: tasks,
+    },
+    Command {
+        name: "table",
+        aliases: &[],
+        description: "Edit the table under the cursor",
+        keybinding: None,
+        args: "[format|addrow|delrow|addcol|delcol|align]",
+        arg_kind: ArgKind::Choice(&["format", "addrow", "delrow", "addcol", "delcol", "align left", "align center", "align right", "align none"]),
+        run: table,
+    },
+    Command {
+        name: "preview",
+        aliases: &[],
+        description: "Toggle the live markdown preview",
+        keybinding: Some("Ctrl+M"),
+        args: "",
+        arg_kind: ArgKind::None,
+        run: preview,
+    },
+    Command {
+        name: "backlinks",
+        aliases: &[],
+        description: "Toggle the backlinks panel",
+        keybinding: Some("Ctrl+B"),
+        args: "",
+        arg_kind: ArgKind::None,
+        run: backlinks,
+    },
+    Command {
+        name: "snippets",
+        aliases: &[],
+        description: "Reload user snippets",
+        keybinding: None,
+        args: "",
+        arg_kind: ArgKind::None,
+        run: snippets,
+    },
+    Command {
+        name: "export",
+        aliases: &[],
+        description: "Export all notes as markdown files",
+        keybinding: None,
+        args: "[dir]",
+        arg_kind: ArgKind::Directory,
+        run: export,
+    },
+    Command {
+        name: "import",
+        aliases: &[],
+        description: "Import markdown files from a directory",
+        keybinding: None,
+        args: "<dir>",
+        arg_kind: ArgKind::Directory,
+        run: import,
+    },
+    Command {
+        name: "backup",
+        aliases: &[],
+        description: "Back up the notebook",
+        keybinding: None,
+        args: "",
+        arg_kind: ArgKind::None,
+        run: backup,
+    },
+    Command {
+        name: "help",
+        aliases: &[],
+        description: "Show keyboard shortcuts",
+        keybinding: Some("?"),
+        args: "",
+        arg_kind: ArgKind::None,
+        run: help,
+    },
+];
+
+/// Look up a command by name or alias
+pub fn find(name: &str) -> Option<&'static Command> {
+    COMMANDS.iter().find(|c| c.name == name || c.aliases.contains(&name))
+}
+
+/// Split a command line into the command name and its argument text
+fn split_command_line(line: &str) -> (&str, &str) {
+    let line = line.trim_start();
+    match line.split_once(char::is_whitespace) {
+        Some((name, args)) => (name, args.trim()),
+        None => (line.trim_end(), ""),
+    }
+}
+
+/// Run a `:` command line
+pub fn execute(app: &mut App, line: &str) {
+    let (name, args) = split_command_line(line);
+    if name.is_empty() {
+        return;
+    }
+    match find(name) {
+        Some(command) if command.requires_argument() && args.is_empty() => {
+            app.set_message(format!("Usage: {}", command.usage()));
+        }
+        Some(command) => (command.run)(app, args),
+        None => app.set_message(format!("Unknown command: {}", name)),
+    }
+}
+
+/// A command matching the palette query, with the matched chars of its name
+pub struct PaletteMatch {
+    pub command: &'static Command,
+    pub name_positions: Vec<usize>,
+    pub score: i64,
+}
+
+/// Matches on the description count for less than matches on the name
+const DESCRIPTION_MATCH_PENALTY: i64 = 32;
+
+/// Commands whose name or description fuzzy match the query, best first
+pub fn palette_matches(query: &str) -> Vec<PaletteMatch> {
+    let mut matches: Vec<PaletteMatch> = COMMANDS
+        .iter()
+        .filter_map(|command| {
+            let name = fuzzy::fuzzy_match(query, command.name);
+            let description = fuzzy::fuzzy_match(query, command.description)
+                .map(|m| m.score - DESCRIPTION_MATCH_PENALTY);
+            match (name, description) {
+                (Some(n), Some(d)) if d > n.score => Some(PaletteMatch { command, name_positions: Vec::new(), score: d }),
+                (Some(n), _) => Some(PaletteMatch { command, name_positions: n.positions, score: n.score }),
+                (None, Some(d)) => Some(PaletteMatch { command, name_positions: Vec::new(), score: d }),
+                (None, None) => None,
+            }
+        })
+        .collect();
+    // Stable sort keeps registry order for equal scores
+    matches.sort_by_key(|m| std::cmp::Reverse(m.score));
+    matches
+}
+
+/// Completion candidates for the word being typed on a command line
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Completion {
+    /// Byte offset in the line where the completed word starts
+    pub start: usize,
+    pub candidates: Vec<String>,
+}
+
+/// Complete the command name, or the argument of a known command
+pub fn complete(app: &App, line: &str) -> Completion {
+    let leading = line.len() - line.trim_start().len();
+    let Some(name_end) = line[leading..].find(char::is_whitespace).map(|i| leading + i) else {
+        let typed = &line[leading..];
+        let candidates = COMMANDS
+            .iter()
+            .map(|c| c.name)
+            .filter(|name| name.starts_with(typed))
+            .map(str::to_string)
+            .collect();
+        return Completion { start: leading, candidates };
+    };
+
+    let Some(command) = find(&line[leading..name_end]) else {
+        return Completion::default();
+    };
+    let start = name_end + (line.len() - name_end - line[name_end..].trim_start().len());
+    let arg = &line[start..];
+    let candidates = match command.arg_kind {
+        ArgKind::None | ArgKind::Text => Vec::new(),
+        ArgKind::Directory => complete_directory(arg),
+        ArgKind::Note => {
+            let typed = arg.to_lowercase();
+            let mut titles: Vec<String> = app.notebook.notes
+                .values()
+                .filter(|note| note.title.to_lowercase().starts_with(&typed))
+                .map(|note| note.title.clone())
+                .collect();
+            titles.sort_by_key(|title| title.to_lowercase());
+            titles
+        }
+        ArgKind::Choice(choices) => choices
+            .iter()
+            .filter(|choice| choice.starts_with(arg))
+            .map(|choice| choice.to_string())
+            .collect(),
+    };
+    Completion { start, candidates }
+}
+
+/// Directories matching a partly typed path, each ending in `/`
+fn complete_directory(typed: &str) -> Vec<String> {
+    let (dir_part, prefix) = match typed.rfind('/') {
+        Some(slash) => typed.split_at(slash + 1),
+        None => ("", typed),
+    };
+    let dir = match dir_part {
+        "" => PathBuf::from("."),
+        _ => config::expand_home(dir_part),
+    };
+
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+    let mut candidates: Vec<String> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| name.starts_with(prefix) && (prefix.starts_with('.') || !name.starts_with('.')))
+        .map(|name| format!("{}{}/", dir_part, name))
+        .collect();
+    candidates.sort();
+    candidates
+}
+
+/// Longest prefix shared by every candidate
+pub fn common_prefix(candidates: &[String]) -> &str {
+    let Some(first) = candidates.first() else {
+        return "";
+    };
+    let mut len = first.len();
+    for candidate in &candidates[1..] {
+        len = first
+            .char_indices()
+            .zip(candidate.chars())
+            .take_while(|((_, a), b)| a == b)
+            .last()
+            .map_or(0, |((i, a), _)| i + a.len_utf8())
+            .min(len);
+    }
+    &first[..len]
+}
+
+/// Usage and description of the command being typed, for the command line
+pub fn hint(line: &str) -> Option<String> {
+    let (name, _) = split_command_line(line);
+    let command = find(name)?;
+    Some(format!("{} - {}", command.usage(), command.description))
+}
+
+fn write(app: &mut App, _args: &str) {
+    if let Err(e) = app.save_current_note() {
+        app.set_message(e);
+    }
+}
+
+fn quit(app: &mut App, _args: &str) {
+    app.quit();
+}
+
+fn write_quit(app: &mut App, _args: &str) {
+    if let Err(e) = app.save_current_note() {
+        app.set_message(e);
+    } else {
+        app.quit();
+    }
+}
+
+fn open(app: &mut App, args: &str) {
+    if args.is_empty() {
+        app.open_quick_open();
+        return;
+    }
+    let Some(note_id) = links::resolve(&app.notebook, args) else {
+        app.set_message(format!("No note named '{}'", args));
+        return;
+    };
+    // Keep unsaved edits before switching notes
+    if app.save_status == SaveStatus::Modified {
+        if let Err(e) = app.save_current_note() {
+            app.set_message(e);
+            return;
+        }
+    }
+    app.open_note_by_id(note_id);
+}
+
+/// Open the journal entry `days` away from today
+fn open_journal_day(app: &mut App, days: i64) {
+    let date = chrono::Local::now().date_naive() + chrono::Duration::days(days);
+    if let Err(e) = app.open_journal_entry(date) {
+        app.set_message(e);
+    }
+}
+
+pub fn today(app: &mut App, _args: &str) {
+    open_journal_day(app, 0);
+}
+
+fn yesterday(app: &mut App, _args: &str) {
+    open_journal_day(app, -1);
+}
+
+fn tomorrow(app: &mut App, _args: &str) {
+    open_journal_day(app, 1);
+}
+
+pub fn previous_journal_entry(app: &mut App, _args: &str) {
+    if let Err(e) = app.step_journal_entry(-1) {
+        app.set_message(e);
+    }
+}
+
+pub fn next_journal_entry(app: &mut App, _args: &str) {
+    if let Err(e) = app.step_journal_entry(1) {
+        app.set_message(e);
+    }
+}
+
+fn recent(app: &mut App, _args: &str) {
+    app.open_recent_notes();
+}
+
+fn jump_back(app: &mut App, _args: &str) {
+    app.jump(-1);
+}
+
+fn jump_forward(app: &mut App, _args: &str) {
+    app.jump(1);
+}
+
+/// The selected folder, or none for a selected note (new notes go to the root)
+fn selected_folder(app: &App) -> Option<uuid::Uuid> {
+    app.get_selected_item()
+        .filter(|item| item.item_type == TreeItemType::Folder)
+        .map(|item| item.id)
+}
+
+pub fn new_note(app: &mut App, args: &str) {
+    let folder_id = selected_folder(app);
+    if args.is_empty() {
+        app.start_new_note_input(folder_id);
+    } else {
+        app.begin_new_note(args.to_string(), folder_id);
+    }
+}
+
+fn new_folder(app: &mut App, args: &str) {
+    if args.is_empty() {
+        app.start_new_folder_input(None);
+    } else {
+        app.create_new_folder(args.to_string(), None);
+    }
+}
+
+pub fn new_subfolder(app: &mut App, args: &str) {
+    // Inside the selected folder, or next to the selected note
+    let parent_id = app.get_selected_item().and_then(|item| match item.item_type {
+        TreeItemType::Folder => Some(item.id),
+        TreeItemType::Note => app.notebook.notes.get(&item.id).and_then(|note| note.folder_id),
+        TreeItemType::Pinned | TreeItemType::SavedSearches | TreeItemType::SavedSearch => None,
+    });
+    if args.is_empty() {
+        app.start_new_folder_input(parent_id);
+    } else {
+        app.create_new_folder(args.to_string(), parent_id);
+    }
+}
+
+fn rename(app: &mut App, args: &str) {
+    let result = if args.is_empty() {
+        app.start_rename()
+    } else {
+        app.submit_rename(args)
+    };
+    if let Err(e) = result {
+        app.cancel_rename();
+        app.set_message(e);
+    }
+}
+
+fn delete(app: &mut App, _args: &str) {
+    if let Err(e) = app.start_delete_confirmation() {
+        app.set_message(e);
+    }
+}
+
+fn move_item(app: &mut App, _args: &str) {
+    app.start_move_item();
+}
+
+pub fn edit(app: &mut App, _args: &str) {
+    if app.current_note.is_some() {
+        // The whole insert session is undone as one change
+        app.push_undo_snapshot();
+        app.mode = AppMode::Insert;
+        app.focused_pane = FocusedPane::Editor;
+    } else {
+        app.set_message("No note selected".to_string());
+    }
+}
+
+fn external(app: &mut App, _args: &str) {
+    if let Err(e) = app.open_in_external_editor() {
+        app.set_message(e);
+    }
+}
+
+fn undo(app: &mut App, _args: &str) {
+    if app.current_note.is_some() {
+        app.undo();
+    } else {
+        app.set_message("No note selected".to_string());
+    }
+}
+
+pub fn search(app: &mut App, _args: &str) {
+    app.start_incremental_search();
+}
+
+pub fn advanced_search(app: &mut App, _args: &str) {
+    app.mode = AppMode::SearchAdvanced;
+    app.input_buffer.clear();
+}
+
+pub fn replace(app: &mut App, _args: &str) {
+    if app.current_note.is_some() {
+        app.mode = AppMode::SearchReplace;
+        app.input_buffer.clear();
+    } else {
+        app.set_message("No note selected for replace".to_string());
+    }
+}
+
+fn edit_tags(app: &mut App, _args: &str) {
+    if let Err(e) = app.start_tag_edit() {
+        app.set_message(e);
+    }
+}
+
+fn tag_browser(app: &mut App, _args: &str) {
+    app.open_tag_browser();
+}
+
+pub fn find_in_note(app: &mut App, _args: &str) {
+    if let Err(e) = app.start_find() {
+        app.set_message(e);
+    }
+}
+
+pub fn next_find_match(app: &mut App, _args: &str) {
+    app.step_find(1);
+}
+
+pub fn previous_find_match(app: &mut App, _args: &str) {
+    app.step_find(-1);
+}
+
+fn search_results(app: &mut App, _args: &str) {
+    app.toggle_search_results();
+}
+
+pub fn next_match(app: &mut App, _args: &str) {
+    app.step_search_match(1);
+}
+
+pub fn previous_match(app: &mut App, _args: &str) {
+    app.step_search_match(-1);
+}
+
+fn save_search(app: &mut App, args: &str) {
+    if let Err(e) = app.save_search(args) {
+        app.set_message(e);
+    }
+}
+
+pub fn edit_saved_search(app: &mut App, _args: &str) {
+    if let Err(e) = app.start_saved_search_edit() {
+        app.set_message(e);
+    }
+}
+
+pub fn toggle_pin(app: &mut App, _args: &str) {
+    if let Err(e) = app.toggle_pin() {
+        app.set_message(e);
+    }
+}
+
+pub fn toggle_task(app: &mut App, _args: &str) {
+    if app.current_note.is_some() {
+        app.toggle_task_at_cursor();
+    } else {
+        app.set_message("No note selected".to_string());
+    }
+}
+
+fn tasks(app: &mut App, _args: &str) {
+    app.open_tasks_view();
+}
+
+fn table(app: &mut App, args: &str) {
+    match app.table_command(args) {
+        Ok(message) => app.set_message(message),
+        Err(e) => app.set_message(e),
+    }
+}
+
+fn preview(app: &mut App, _args: &str) {
+    app.toggle_preview();
+}
+
+fn backlinks(app: &mut App, _args: &str) {
+    app.toggle_backlinks();
+}
+
+fn snippets(app: &mut App, _args: &str) {
+    let path = crate::config::snippets_file();
+    match app.load_snippets() {
+        Ok(0) if !path.exists() => app.set_message(format!("No snippets file at {}", path.display())),
+        Ok(count) => app.set_operation_success(format!("Loaded {} snippets", count), Some("✂".to_string())),
+        Err(e) => app.set_operation_error(e, Some("🚨".to_string())),
+    }
+}
+
+fn export(app: &mut App, args: &str) {
+    let result = if args.is_empty() {
+        app.export_all_notes().map(|_| "All notes exported successfully".to_string())
+    } else {
+        app.export_notes_to_directory(args).map(|_| format!("Notes exported to '{}'", args))
+    };
+    match result {
+        Ok(message) => app.set_operation_success(message, Some("📦".to_string())),
+        Err(e) => app.set_operation_error(format!("Export failed: {}", e), Some("🚨".to_string())),
+    }
+}
+
+fn import(app: &mut App, args: &str) {
+    match app.import_notes_from_directory(args) {
+        Ok(_) => app.set_operation_success(format!("Notes imported from '{}'", args), Some("📦".to_string())),
+        Err(e) => app.set_operation_error(format!("Import failed: {}", e), Some("🚨".to_string())),
+    }
+}
+
+fn backup(app: &mut App, _args: &str) {
+    match app.create_backup() {
+        Ok(_) => app.set_operation_success("Backup created successfully".to_string(), Some("💾".to_string())),
+        Err(e) => app.set_operation_error(format!("Backup failed: {}", e), Some("🚨".to_string())),
+    }
+}
+
+fn help(app: &mut App, _args: &str) {
+    app.mode = AppMode::Help;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Note, NotebookData};
+
+    fn app_with_titles(titles: &[&str]) -> App {
+        let mut notebook = NotebookData::new();
+        for title in titles {
+            notebook.add_note(Note::new(title.to_string(), None));
+        }
+        let mut app = App::new();
+        app.load_notebook(notebook);
+        app
+    }
+
+    #[test]
+    fn command_lines_split_into_name_and_trimmed_arguments() {
+        assert_eq!(split_command_line("  export   ~/notes  "), ("export", "~/notes"));
+        assert_eq!(split_command_line("wq  "), ("wq", ""));
+        assert_eq!(split_command_line("open My Note"), ("open", "My Note"));
+        assert_eq!(split_command_line(""), ("", ""));
+    }
+
+    #[test]
+    fn command_names_complete_from_the_registry() {
+        let app = app_with_titles(&[]);
+        assert_eq!(complete(&app, "expo"), Completion { start: 0, candidates: vec!["export".to_string()] });
+        assert_eq!(complete(&app, "  backu").start, 2);
+        assert!(complete(&app, "zzz").candidates.is_empty());
+    }
+
+    #[test]
+    fn arguments_complete_by_kind() {
+        let app = app_with_titles(&["Groceries", "garden plans", "Work"]);
+        let notes = complete(&app, "open g");
+        assert_eq!(notes.start, 5);
+        assert_eq!(notes.candidates, vec!["garden plans", "Groceries"]);
+
+        let choices = complete(&app, "table align c");
+        assert_eq!(choices.start, 6);
+        assert_eq!(choices.candidates, vec!["align center"]);
+
+        assert!(complete(&app, "new G").candidates.is_empty());
+        assert_eq!(complete(&app, "nosuch arg"), Completion::default());
+    }
+
+    #[test]
+    fn directories_complete_with_a_trailing_slash() {
+        let root = std::env::temp_dir().join(format!("scribble-complete-{}", uuid::Uuid::new_v4()));
+        for dir in ["notes", "novels", ".hidden"] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        fs::write(root.join("notes.txt"), "").unwrap();
+        let base = format!("{}/", root.display());
+
+        assert_eq!(complete_directory(&format!("{}no", base)), vec![format!("{}notes/", base), format!("{}novels/", base)]);
+        assert_eq!(complete_directory(&base).len(), 2, "hidden directories need a typed dot");
+        assert_eq!(complete_directory(&format!("{}.h", base)), vec![format!("{}.hidden/", base)]);
+        assert!(complete_directory(&format!("{}missing/", base)).is_empty());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn common_prefix_stops_at_the_first_difference() {
+        let words = |list: &[&str]| list.iter().map(|w| w.to_string()).collect::<Vec<_>>();
+        assert_eq!(common_prefix(&words(&["notes/", "novels/"])), "no");
+        assert_eq!(common_prefix(&words(&["café", "cafè"])), "caf");
+        assert_eq!(common_prefix(&words(&["same"])), "same");
+        assert_eq!(common_prefix(&[]), "");
+    }
+
+    #[test]
+    fn palette_prefers_name_matches_over_descriptions() {
+        let matches = palette_matches("exp");
+        assert_eq!(matches[0].command.name, "export");
+        assert_eq!(matches[0].name_positions, vec![0, 1, 2]);
+
+        // "Quit Scribble" only matches through the description
+        let quit = palette_matches("scribble");
+        assert_eq!(quit[0].command.name, "quit");
+        assert!(quit[0].name_positions.is_empty());
+        assert!(palette_matches("zzzz").is_empty());
+    }
+}
@@ -0,0 +1,39 @@
+use std::path::PathBuf;
+
+/// Directory for user configuration such as snippets
+pub fn config_dir() -> PathBuf {
+    if let Some(config_dir) = dirs::config_dir() {
+        config_dir.join("scribble")
+    } else if let Some(home_dir) = dirs::home_dir() {
+        // Fallback to home directory if config_dir is not available
+        home_dir.join(".scribble")
+    } else {
+        PathBuf::from(".scribble")
+    }
+}
+
+/// Location of the user snippets file
+pub fn snippets_file() -> PathBuf {
+    config_dir().join("snippets.json")
+}
+
+/// Location of the daily notes settings
+pub fn journal_file() -> PathBuf {
+    config_dir().join("journal.json")
+}
+
+/// A path as typed by the user, with a leading `~` standing for the home directory
+pub fn expand_home(path: &str) -> PathBuf {
+    let rest = match path.strip_prefix('~') {
+        Some("") => "",
+        Some(rest) => match rest.strip_prefix('/') {
+            Some(rest) => rest,
+            None => return PathBuf::from(path),
+        },
+        None => return PathBuf::from(path),
+    };
+    match dirs::home_dir() {
+        Some(home_dir) => home_dir.join(rest),
+        None => PathBuf::from(path),
+    }
+}
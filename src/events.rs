@@ -1,78 +1,154 @@
 use crate::app::{App, AppMode, FocusedPane, TreeItemType};
+use crate::commands;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, Event};
 
 pub fn handle_event(app: &mut App, event: Event) -> Result<(), Box<dyn std::error::Error>> {
-    if let Event::Key(key) = event {
-        match app.mode {
-            AppMode::Normal => handle_normal_mode(app, key),
-            AppMode::Insert => handle_insert_mode(app, key),
-            AppMode::Search => handle_search_mode(app, key),
-            AppMode::SearchAdvanced => handle_advanced_search_mode(app, key),
-            AppMode::SearchReplace => handle_replace_mode(app, key),
-            AppMode::Command => handle_command_mode(app, key),
-            AppMode::InputNote => handle_input_note_mode(app, key),
-            AppMode::InputFolder => handle_input_folder_mode(app, key),
-            AppMode::Move => handle_move_mode(app, key),
-            AppMode::Help => handle_help_mode(app, key),
-            AppMode::DeleteConfirm => handle_delete_confirm_mode(app, key),
-        }
+    match event {
+        Event::Key(key) => handle_key_event(app, key),
+        Event::Paste(text) => handle_paste(app, &text),
+        _ => {}
     }
     Ok(())
 }
 
+fn handle_key_event(app: &mut App, key: KeyEvent) {
+    match app.mode {
+        AppMode::Normal => handle_normal_mode(app, key),
+        AppMode::Insert => handle_insert_mode(app, key),
+        AppMode::Search => handle_search_mode(app, key),
+        AppMode::Find => handle_find_mode(app, key),
+        AppMode::SearchAdvanced => handle_advanced_search_mode(app, key),
+        AppMode::SearchReplace => handle_replace_mode(app, key),
+        AppMode::Command => handle_command_mode(app, key),
+        AppMode::InputNote => handle_input_note_mode(app, key),
+        AppMode::InputFolder => handle_input_folder_mode(app, key),
+        AppMode::Move => handle_move_mode(app, key),
+        AppMode::Help => handle_help_mode(app, key),
+        AppMode::DeleteConfirm => handle_delete_confirm_mode(app, key),
+        AppMode::Rename => handle_rename_mode(app, key),
+        AppMode::TagEdit => handle_tag_edit_mode(app, key),
+        AppMode::TagBrowser => handle_tag_browser_mode(app, key),
+        AppMode::TagRename => handle_tag_rename_mode(app, key),
+        AppMode::QuickOpen => handle_quick_open_mode(app, key),
+        AppMode::CommandPalette => handle_command_palette_mode(app, key),
+        AppMode::Recent => handle_recent_mode(app, key),
+        AppMode::RenameConfirm => handle_rename_confirm_mode(app, key),
+        AppMode::Tasks => handle_tasks_mode(app, key),
+        AppMode::TemplatePicker => handle_template_picker_mode(app, key),
+        AppMode::TemplatePrompt => handle_template_prompt_mode(app, key),
+        AppMode::SavedSearchEdit => handle_saved_search_edit_mode(app, key),
+    }
+}
+
+/// Handle a bracketed paste: the whole block arrives as one event
+fn handle_paste(app: &mut App, text: &str) {
+    match app.mode {
+        AppMode::Insert => app.paste_text(text),
+        AppMode::Search => {
+            app.input_buffer.push_str(&flatten_paste(text));
+            app.search_input_changed();
+        }
+        AppMode::Find => {
+            app.input_buffer.push_str(&flatten_paste(text));
+            app.find_input_changed();
+        }
+        // Single-line inputs take the pasted text with line breaks flattened
+        AppMode::SearchAdvanced | AppMode::SearchReplace
+        | AppMode::InputNote | AppMode::InputFolder | AppMode::Rename | AppMode::TagRename
+        | AppMode::TemplatePrompt | AppMode::SavedSearchEdit => {
+            app.input_buffer.push_str(&flatten_paste(text));
+        }
+        AppMode::Command => {
+            app.command_insert_str(&flatten_paste(text));
+        }
+        AppMode::TagEdit => {
+            app.input_buffer.push_str(&flatten_paste(text));
+            app.update_tag_suggestions();
+        }
+        AppMode::QuickOpen => {
+            app.input_buffer.push_str(&flatten_paste(text));
+            app.update_quick_open();
+        }
+        AppMode::CommandPalette => {
+            app.input_buffer.push_str(&flatten_paste(text));
+            app.update_command_palette();
+        }
+        _ => {}
+    }
+}
+
+fn flatten_paste(text: &str) -> String {
+    text.lines().map(str::trim_end).collect::<Vec<_>>().join(" ")
+}
+
 fn handle_normal_mode(app: &mut App, key: KeyEvent) {
+    if app.focused_pane == FocusedPane::Backlinks && handle_backlinks_pane(app, key) {
+        return;
+    }
+    if app.focused_pane == FocusedPane::SearchResults && handle_search_results_pane(app, key) {
+        return;
+    }
     
     match key.code {
         // Navigation
+        // In the editor pane these move the cursor; the preview pane just scrolls
         KeyCode::Char('j') | KeyCode::Down => {
-            if (app.focused_pane == FocusedPane::Editor || app.focused_pane == FocusedPane::Preview) && app.current_note.is_some() {
+            if is_editing_note(app) {
+                app.move_cursor_lines(1);
+            } else if is_viewing_note(app) {
                 app.scroll_down();
             } else {
                 app.navigate_down();
             }
         },
+        // Command palette (Ctrl+K)
+        KeyCode::Char('k') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.open_command_palette();
+        }
         KeyCode::Char('k') | KeyCode::Up => {
-            if (app.focused_pane == FocusedPane::Editor || app.focused_pane == FocusedPane::Preview) && app.current_note.is_some() {
+            if is_editing_note(app) {
+                app.move_cursor_lines(-1);
+            } else if is_viewing_note(app) {
                 app.scroll_up();
             } else {
                 app.navigate_up();
             }
         },
         KeyCode::Char('g') => {
-            if (app.focused_pane == FocusedPane::Editor || app.focused_pane == FocusedPane::Preview) && app.current_note.is_some() {
+            if is_editing_note(app) {
+                app.jump_to_line(0);
+            } else if is_viewing_note(app) {
                 app.scroll_to_top();
             } else {
                 app.navigate_to_top();
             }
         },
         KeyCode::Char('G') => {
-            if (app.focused_pane == FocusedPane::Editor || app.focused_pane == FocusedPane::Preview) && app.current_note.is_some() {
+            if is_editing_note(app) {
+                app.jump_to_line(usize::MAX);
+            } else if is_viewing_note(app) {
                 app.scroll_to_bottom();
             } else {
                 app.navigate_to_bottom();
             }
         },
+        KeyCode::Char('h') | KeyCode::Left if is_editing_note(app) => {
+            app.move_cursor_left();
+        }
+        KeyCode::Char('l') | KeyCode::Right if is_editing_note(app) => {
+            app.move_cursor_right();
+        }
         
         // Pane switching
         KeyCode::Tab => {
-            app.focused_pane = if app.preview_enabled {
-                match app.focused_pane {
-                    FocusedPane::Folders => FocusedPane::Editor,
-                    FocusedPane::Editor => FocusedPane::Preview,
-                    FocusedPane::Preview => FocusedPane::Folders,
-                }
-            } else {
-                match app.focused_pane {
-                    FocusedPane::Folders => FocusedPane::Editor,
-                    FocusedPane::Editor => FocusedPane::Folders,
-                    FocusedPane::Preview => FocusedPane::Editor, // Fallback if preview gets disabled
-                }
-            };
+            app.focus_next_pane();
         }
         
         // Actions
         KeyCode::Enter => {
-            if let Some(item) = app.get_selected_item() {
+            if is_editing_note(app) && app.follow_link_at_cursor() {
+                // Followed the [[link]] under the cursor
+            } else if let Some(item) = app.get_selected_item() {
                 match item.item_type {
                     TreeItemType::Note => {
                         app.select_note(item.id);
@@ -80,24 +156,40 @@ fn handle_normal_mode(app: &mut App, key: KeyEvent) {
                     TreeItemType::Folder => {
                         app.toggle_folder_expansion();
                     }
+                    TreeItemType::Pinned => {
+                        app.toggle_pinned_section();
+                    }
+                    TreeItemType::SavedSearches => {
+                        app.toggle_saved_searches_section();
+                    }
+                    TreeItemType::SavedSearch => {
+                        app.toggle_saved_search_expansion();
+                    }
                 }
             }
         }
         
-        // Create new items
-        KeyCode::Char('n') => {
-            let folder_id = if let Some(item) = app.get_selected_item() {
-                match item.item_type {
-                    TreeItemType::Folder => Some(item.id),
-                    TreeItemType::Note => None, // Create in root
-                }
-            } else {
-                None
-            };
-            
-            app.start_new_note_input(folder_id);
+        // Jump back and forward through visited notes (Ctrl+O / Ctrl+N). Most terminals send
+        // Ctrl+I as Tab, so Ctrl+I only arrives where the keyboard protocol tells them apart
+        KeyCode::Char('o') if key.modifiers.contains(KeyModifiers::CONTROL) => app.jump(-1),
+        KeyCode::Char('n') | KeyCode::Char('i') if key.modifiers.contains(KeyModifiers::CONTROL) => app.jump(1),
+        
+        // Step through the matches of a find in the open note
+        KeyCode::Char('n') if is_editing_note(app) && !app.find_query.is_empty() => commands::next_find_match(app, ""),
+        KeyCode::Char('N') if is_editing_note(app) && !app.find_query.is_empty() => commands::previous_find_match(app, ""),
+        KeyCode::Esc if is_editing_note(app) && !app.find_query.is_empty() => app.clear_find(),
+        
+        // Step through the matches of the last search while its results are shown
+        KeyCode::Char('n') if app.search_results_enabled && app.focused_pane != FocusedPane::Folders => {
+            commands::next_match(app, "")
+        }
+        KeyCode::Char('N') if app.search_results_enabled && app.focused_pane != FocusedPane::Folders => {
+            commands::previous_match(app, "")
         }
         
+        // Create new items
+        KeyCode::Char('n') => commands::new_note(app, ""),
+        
         KeyCode::Char('f') if !key.modifiers.contains(KeyModifiers::CONTROL) => {
             // Default behavior: create folder at root level
             // Use Shift+F to create subfolder in selected folder
@@ -106,36 +198,31 @@ fn handle_normal_mode(app: &mut App, key: KeyEvent) {
         }
         
         // Create subfolder (Shift+F)
-        KeyCode::Char('F') => {
-            let parent_id = if let Some(item) = app.get_selected_item() {
-                match item.item_type {
-                    TreeItemType::Folder => Some(item.id),
-                    TreeItemType::Note => {
-                        // Find the parent folder of the selected note
-                        if let Some(note) = app.notebook.notes.get(&item.id) {
-                            note.folder_id
-                        } else {
-                            None
-                        }
-                    },
-                }
-            } else {
-                None
-            };
-            
-            app.start_new_folder_input(parent_id);
-        }
+        KeyCode::Char('F') => commands::new_subfolder(app, ""),
+        
+        // Recently opened notes
+        KeyCode::Char('R') => app.open_recent_notes(),
+        
+        // Daily notes: today's entry, then the previous and next entries
+        KeyCode::Char('D') => commands::today(app, ""),
+        KeyCode::Char('[') => commands::previous_journal_entry(app, ""),
+        KeyCode::Char(']') => commands::next_journal_entry(app, ""),
         
         // Edit mode
-        KeyCode::Char('i') => {
+        KeyCode::Char('i') => commands::edit(app, ""),
+        
+        // Undo the last change to the open note
+        KeyCode::Char('u') if !key.modifiers.contains(KeyModifiers::CONTROL) => {
             if app.current_note.is_some() {
-                app.mode = AppMode::Insert;
-                app.focused_pane = FocusedPane::Editor;
+                app.undo();
             } else {
                 app.set_message("No note selected".to_string());
             }
         }
         
+        // Toggle the task checkbox on the cursor line
+        KeyCode::Char('x') => commands::toggle_task(app, ""),
+        
         // External editor
         KeyCode::Char('e') => {
             if let Err(e) = app.open_in_external_editor() {
@@ -156,31 +243,50 @@ fn handle_normal_mode(app: &mut App, key: KeyEvent) {
         }
         
         // Search
-        KeyCode::Char('/') => {
-            app.mode = AppMode::Search;
-            app.input_buffer.clear();
-        }
+        // In the editor, / finds in the open note; elsewhere it searches every note
+        KeyCode::Char('/') if is_editing_note(app) => commands::find_in_note(app, ""),
+        KeyCode::Char('/') => commands::search(app, ""),
         
         // Advanced search (Ctrl+F)
-        KeyCode::Char('f') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-            app.mode = AppMode::SearchAdvanced;
-            app.input_buffer.clear();
+        KeyCode::Char('f') if key.modifiers.contains(KeyModifiers::CONTROL) => commands::advanced_search(app, ""),
+        
+        // Edit the tags of the open note
+        KeyCode::Char('t') => {
+            if let Err(e) = app.start_tag_edit() {
+                app.set_message(e);
+            }
         }
         
-        // Search and replace (Ctrl+R)
-        KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-            if app.current_note.is_some() {
-                app.mode = AppMode::SearchReplace;
-                app.input_buffer.clear();
-            } else {
-                app.set_message("No note selected for replace".to_string());
+        // Browse tags and filter the tree by one
+        KeyCode::Char('T') => {
+            app.open_tag_browser();
+        }
+        
+        // Edit the query of the selected saved search
+        KeyCode::Char('E') => commands::edit_saved_search(app, ""),
+        
+        // Pin the open or selected note; J/K reorder the Pinned section
+        KeyCode::Char('p') if !key.modifiers.contains(KeyModifiers::CONTROL) => commands::toggle_pin(app, ""),
+        KeyCode::Char('J') | KeyCode::Char('K') if app.focused_pane == FocusedPane::Folders => {
+            let direction = if key.code == KeyCode::Char('J') { 1 } else { -1 };
+            if let Err(e) = app.move_pinned_note(direction) {
+                app.set_message(e);
+            }
+        }
+        
+        // Rename the open note or the selected tree item
+        KeyCode::Char('r') if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+            if let Err(e) = app.start_rename() {
+                app.set_message(e);
             }
         }
         
+        // Search and replace (Ctrl+R)
+        KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => commands::replace(app, ""),
+        
         // Commands
         KeyCode::Char(':') => {
-            app.mode = AppMode::Command;
-            app.command_buffer.clear();
+            app.start_command_line(String::new());
         }
         
         // Save (Ctrl+S)
@@ -195,28 +301,30 @@ fn handle_normal_mode(app: &mut App, key: KeyEvent) {
             app.toggle_preview();
         }
         
+        // Quick open (Ctrl+P)
+        KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.open_quick_open();
+        }
+        
+        // Toggle backlinks panel
+        KeyCode::Char('b') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.toggle_backlinks();
+        }
+        
         // Scrolling controls (Ctrl+U for half page up, Ctrl+D for half page down)
-        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-            if (app.focused_pane == FocusedPane::Editor || app.focused_pane == FocusedPane::Preview) && app.current_note.is_some() {
-                app.scroll_half_page_up();
-            }
+        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) && is_viewing_note(app) => {
+            app.scroll_half_page_up();
         }
-        KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-            if (app.focused_pane == FocusedPane::Editor || app.focused_pane == FocusedPane::Preview) && app.current_note.is_some() {
-                app.scroll_half_page_down();
-            }
+        KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) && is_viewing_note(app) => {
+            app.scroll_half_page_down();
         }
         
         // Page Up/Down for scrolling
-        KeyCode::PageUp => {
-            if (app.focused_pane == FocusedPane::Editor || app.focused_pane == FocusedPane::Preview) && app.current_note.is_some() {
-                app.scroll_page_up();
-            }
+        KeyCode::PageUp if is_viewing_note(app) => {
+            app.scroll_page_up();
         }
-        KeyCode::PageDown => {
-            if (app.focused_pane == FocusedPane::Editor || app.focused_pane == FocusedPane::Preview) && app.current_note.is_some() {
-                app.scroll_page_down();
-            }
+        KeyCode::PageDown if is_viewing_note(app) => {
+            app.scroll_page_down();
         }
         
         // Quit
@@ -231,6 +339,43 @@ fn handle_normal_mode(app: &mut App, key: KeyEvent) {
     }
 }
 
+/// Keys for the focused backlinks panel. Returns false for keys it leaves to normal mode.
+fn handle_backlinks_pane(app: &mut App, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Char('j') | KeyCode::Down => app.next_backlink(),
+        KeyCode::Char('k') | KeyCode::Up => app.previous_backlink(),
+        KeyCode::Char('g') => app.backlink_selected = 0,
+        KeyCode::Char('G') => app.backlink_selected = app.backlinks.len().saturating_sub(1),
+        KeyCode::Enter => app.open_selected_backlink(),
+        _ => return false,
+    }
+    true
+}
+
+/// Keys for the focused search results panel. Returns false for keys it leaves to normal mode.
+fn handle_search_results_pane(app: &mut App, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Char('j') | KeyCode::Down => app.next_search_result(),
+        KeyCode::Char('k') | KeyCode::Up => app.previous_search_result(),
+        KeyCode::Char('g') => app.search_result_selected = 0,
+        KeyCode::Char('G') => app.search_result_selected = app.search_result_rows().len().saturating_sub(1),
+        KeyCode::Enter => app.open_search_result(app.search_result_selected),
+        KeyCode::Esc => app.close_search_results(),
+        _ => return false,
+    }
+    true
+}
+
+/// Whether the editor or preview pane is focused with a note loaded
+fn is_viewing_note(app: &App) -> bool {
+    (app.focused_pane == FocusedPane::Editor || app.focused_pane == FocusedPane::Preview) && app.current_note.is_some()
+}
+
+/// Whether the editor pane is focused with a note loaded
+fn is_editing_note(app: &App) -> bool {
+    app.focused_pane == FocusedPane::Editor && app.current_note.is_some()
+}
+
 fn handle_insert_mode(app: &mut App, key: KeyEvent) {
     match key.code {
         KeyCode::Esc => {
@@ -238,6 +383,7 @@ fn handle_insert_mode(app: &mut App, key: KeyEvent) {
                 app.cancel_autocomplete();
             } else {
                 app.mode = AppMode::Normal;
+                app.snippet_session = None;
                 // Auto-save on exit insert mode
                 if let Err(e) = app.save_current_note() {
                     app.set_message(e);
@@ -248,20 +394,26 @@ fn handle_insert_mode(app: &mut App, key: KeyEvent) {
         KeyCode::Tab => {
             if app.autocomplete_state.active {
                 app.apply_autocomplete();
-            } else {
-                app.editor_content.push_str("    "); // 4 spaces
-                app.mark_modified();
-                update_cursor_position(app);
+            } else if !app.snippet_jump(true) && !app.table_next_cell() && !app.indent_list_item() {
+                app.insert_at_cursor("    "); // 4 spaces
                 app.update_autocompletion();
             }
         }
         
+        KeyCode::BackTab => {
+            app.cancel_autocomplete();
+            if !app.snippet_jump(false) && !app.table_previous_cell() {
+                app.outdent_line();
+            }
+        }
+        
         KeyCode::Up => {
             if app.autocomplete_state.active {
                 app.previous_autocomplete_suggestion();
             } else {
                 if app.editor_cursor.0 > 0 {
                     app.editor_cursor.0 -= 1;
+                    app.clamp_cursor_column();
                     app.adjust_scroll_to_cursor();
                 }
             }
@@ -274,6 +426,7 @@ fn handle_insert_mode(app: &mut App, key: KeyEvent) {
                 let lines = app.editor_content.lines().count() as u16;
                 if app.editor_cursor.0 < lines.saturating_sub(1) {
                     app.editor_cursor.0 += 1;
+                    app.clamp_cursor_column();
                     app.adjust_scroll_to_cursor();
                 }
             }
@@ -296,12 +449,14 @@ fn handle_insert_mode(app: &mut App, key: KeyEvent) {
                     'd' => {
                         app.scroll_half_page_down();
                     }
+                    'z' => {
+                        app.undo();
+                    }
                     _ => {}
                 }
             } else {
-                app.editor_content.push(c);
-                app.mark_modified();
-                update_cursor_position(app);
+                app.take_snippet_placeholder();
+                app.insert_at_cursor(c.encode_utf8(&mut [0; 4]));
                 app.update_autocompletion();
             }
         }
@@ -310,22 +465,18 @@ fn handle_insert_mode(app: &mut App, key: KeyEvent) {
             if app.autocomplete_state.active {
                 app.apply_autocomplete();
             } else {
-                app.editor_content.push('\n');
-                app.mark_modified();
-                app.editor_cursor.0 += 1;
-                app.editor_cursor.1 = 0;
+                app.insert_newline();
                 app.adjust_scroll_to_cursor();
                 app.update_autocompletion();
             }
         }
         
         KeyCode::Backspace => {
-            if !app.editor_content.is_empty() {
-                app.editor_content.pop();
-                app.mark_modified();
-                update_cursor_position(app);
-                app.update_autocompletion();
+            if !app.take_snippet_placeholder() {
+                app.delete_before_cursor();
             }
+            app.adjust_scroll_to_cursor();
+            app.update_autocompletion();
         }
         
         // Page Up/Down scrolling in insert mode
@@ -337,21 +488,13 @@ fn handle_insert_mode(app: &mut App, key: KeyEvent) {
         }
         
         KeyCode::Left => {
-            if app.editor_cursor.1 > 0 {
-                app.editor_cursor.1 -= 1;
-                app.update_autocompletion();
-            }
+            app.move_cursor_left();
+            app.update_autocompletion();
         }
         
         KeyCode::Right => {
-            let current_line = app.editor_content
-                .lines()
-                .nth(app.editor_cursor.0 as usize)
-                .unwrap_or("");
-            if (app.editor_cursor.1 as usize) < current_line.len() {
-                app.editor_cursor.1 += 1;
-                app.update_autocompletion();
-            }
+            app.move_cursor_right();
+            app.update_autocompletion();
         }
         
         _ => {}
@@ -360,101 +503,84 @@ fn handle_insert_mode(app: &mut App, key: KeyEvent) {
 
 fn handle_search_mode(app: &mut App, key: KeyEvent) {
     match key.code {
-        KeyCode::Esc => {
-            app.mode = AppMode::Normal;
-            app.input_buffer.clear();
-        }
+        KeyCode::Esc => app.cancel_incremental_search(),
         
-        KeyCode::Enter => {
-            if !app.input_buffer.is_empty() {
-                app.search_notes(app.input_buffer.clone());
-            }
-            app.mode = AppMode::Normal;
-            app.input_buffer.clear();
-        }
+        KeyCode::Enter => app.finish_incremental_search(),
         
         KeyCode::Char(c) => {
             app.input_buffer.push(c);
+            app.search_input_changed();
         }
         
         KeyCode::Backspace => {
             app.input_buffer.pop();
+            app.search_input_changed();
         }
         
         _ => {}
     }
 }
 
+fn handle_find_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Esc => app.cancel_find(),
+        KeyCode::Enter => app.finish_find(),
+        KeyCode::Char(c) => {
+            app.input_buffer.push(c);
+            app.find_input_changed();
+        }
+        KeyCode::Backspace => {
+            app.input_buffer.pop();
+            app.find_input_changed();
+        }
+        _ => {}
+    }
+}
+
 fn handle_command_mode(app: &mut App, key: KeyEvent) {
     match key.code {
         KeyCode::Esc => {
             app.mode = AppMode::Normal;
-            app.command_buffer.clear();
+            app.cancel_command_line();
         }
         
         KeyCode::Enter => {
-            execute_command(app, &app.command_buffer.clone());
+            // Leave command mode first so commands can open their own dialogs
             app.mode = AppMode::Normal;
-            app.command_buffer.clear();
+            let line = app.submit_command_line();
+            commands::execute(app, &line);
+        }
+        
+        KeyCode::Tab => {
+            app.complete_command_line();
         }
         
+        KeyCode::Up => app.command_history_previous(),
+        KeyCode::Down => app.command_history_next(),
+        KeyCode::Left => app.command_cursor_left(),
+        KeyCode::Right => app.command_cursor_right(),
+        KeyCode::Home => app.command_cursor_home(),
+        KeyCode::End => app.command_cursor_end(),
+        
+        // Readline-style line editing
+        KeyCode::Char('a') if key.modifiers.contains(KeyModifiers::CONTROL) => app.command_cursor_home(),
+        KeyCode::Char('e') if key.modifiers.contains(KeyModifiers::CONTROL) => app.command_cursor_end(),
+        KeyCode::Char('w') if key.modifiers.contains(KeyModifiers::CONTROL) => app.command_delete_word(),
+        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => app.command_delete_to_start(),
+        
         KeyCode::Char(c) => {
-            app.command_buffer.push(c);
+            app.command_insert_char(c);
         }
         
         KeyCode::Backspace => {
-            app.command_buffer.pop();
+            app.command_backspace();
         }
         
-        _ => {}
-    }
-}
-
-fn execute_command(app: &mut App, command: &str) {
-    let command = command.trim();
-    match command {
-        "q" | "quit" => app.quit(),
-        "w" | "write" => {
-            if let Err(e) = app.save_current_note() {
-                app.set_message(e);
-            }
-        }
-        "wq" => {
-            if let Err(e) = app.save_current_note() {
-                app.set_message(e);
-            } else {
-                app.quit();
-            }
-        }
-        "export" => {
-            match app.export_all_notes() {
-                Ok(_) => app.set_operation_success("All notes exported successfully".to_string(), Some("📦".to_string())),
-                Err(e) => app.set_operation_error(format!("Export failed: {}", e), Some("🚨".to_string())),
-            }
-        },
-        "backup" => {
-            match app.create_backup() {
-                Ok(_) => app.set_operation_success("Backup created successfully".to_string(), Some("💾".to_string())),
-                Err(e) => app.set_operation_error(format!("Backup failed: {}", e), Some("🚨".to_string())),
-            }
-        },
-        _ => {
-            if command.starts_with("export ") {
-                let path = command.strip_prefix("export ").unwrap_or("").trim();
-                match app.export_notes_to_directory(path) {
-                    Ok(_) => app.set_operation_success(format!("Notes exported to '{}'", path), Some("📦".to_string())),
-                    Err(e) => app.set_operation_error(format!("Export failed: {}", e), Some("🚨".to_string())),
-                }
-            } else if command.starts_with("import ") {
-                let path = command.strip_prefix("import ").unwrap_or("").trim();
-                match app.import_notes_from_directory(path) {
-                    Ok(_) => app.set_operation_success(format!("Notes imported from '{}'", path), Some("📦".to_string())),
-                    Err(e) => app.set_operation_error(format!("Import failed: {}", e), Some("🚨".to_string())),
-                }
-            } else {
-                app.set_message(format!("Unknown command: {}", command));
-            }
+        KeyCode::Delete => {
+            app.command_delete();
         }
+        
+        _ => {}
     }
 }
 
@@ -502,20 +628,6 @@ fn handle_input_folder_mode(app: &mut App, key: KeyEvent) {
     }
 }
 
-fn update_cursor_position(app: &mut App) {
-    let lines: Vec<&str> = app.editor_content.lines().collect();
-    let current_line_index = app.editor_cursor.0 as usize;
-    
-    if current_line_index < lines.len() {
-        let current_line = lines[current_line_index];
-        app.editor_cursor.1 = current_line.len() as u16;
-    } else {
-        // We're at the end, position cursor after the last line
-        app.editor_cursor.0 = lines.len() as u16;
-        app.editor_cursor.1 = 0;
-    }
-}
-
 fn handle_advanced_search_mode(app: &mut App, key: KeyEvent) {
     use crate::search::SearchQuery;
     
@@ -523,36 +635,37 @@ fn handle_advanced_search_mode(app: &mut App, key: KeyEvent) {
         KeyCode::Esc => {
             app.mode = AppMode::Normal;
             app.input_buffer.clear();
+            app.search_error = None;
         }
         
         KeyCode::Enter => {
             if !app.input_buffer.is_empty() {
-                let mut query = SearchQuery::new(app.input_buffer.clone());
-                
-                // Check for special modifiers in the query
-                if app.input_buffer.starts_with("regex:") {
-                    let pattern = app.input_buffer.strip_prefix("regex:").unwrap_or("").trim();
-                    query = SearchQuery::new(pattern.to_string()).with_regex();
-                } else if app.input_buffer.starts_with("case:") {
-                    let pattern = app.input_buffer.strip_prefix("case:").unwrap_or("").trim();
-                    query = SearchQuery::new(pattern.to_string()).case_sensitive();
+                match SearchQuery::parse(&app.input_buffer) {
+                    Ok(query) => {
+                        // Kept as typed so it can be saved as a smart folder
+                        app.search_query = app.input_buffer.clone();
+                        app.enhanced_search_notes(query);
+                    }
+                    Err(e) => {
+                        // Keep the dialog open so the query can be fixed
+                        app.search_error = Some(e);
+                        return;
+                    }
                 }
-                
-                // TODO: Add folder filtering support
-                // if app.input_buffer.starts_with("folder:") { ... }
-                
-                app.enhanced_search_notes(query);
             }
             app.mode = AppMode::Normal;
             app.input_buffer.clear();
+            app.search_error = None;
         }
         
         KeyCode::Char(c) => {
             app.input_buffer.push(c);
+            app.search_error = None;
         }
         
         KeyCode::Backspace => {
             app.input_buffer.pop();
+            app.search_error = None;
         }
         
         // Up/Down to navigate search history
@@ -561,6 +674,7 @@ fn handle_advanced_search_mode(app: &mut App, key: KeyEvent) {
             if !history.is_empty() {
                 app.input_buffer = history[0].clone();
             }
+            app.search_error = None;
         }
         
         _ => {}
@@ -591,6 +705,8 @@ fn handle_replace_mode(app: &mut App, key: KeyEvent) {
                                 app.editor_content = note.content.clone();
                                 // Update the note in the notebook
                                 app.notebook.notes.insert(note.id, note.clone());
+                                app.link_index.update_note(note);
+                                app.enhanced_search.index_note(note);
                                 app.set_message(format!("Replaced {} occurrences", count));
                             } else {
                                 app.set_message("No matches found to replace".to_string());
@@ -670,6 +786,253 @@ fn handle_help_mode(app: &mut App, key: KeyEvent) {
     }
 }
 
+fn handle_tasks_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => {
+            app.mode = AppMode::Normal;
+        }
+        KeyCode::Char('j') | KeyCode::Down => app.next_task(),
+        KeyCode::Char('k') | KeyCode::Up => app.previous_task(),
+        KeyCode::Enter => app.open_selected_task(),
+        KeyCode::Char('x') | KeyCode::Char(' ') => app.toggle_selected_task(),
+        KeyCode::Tab => app.toggle_task_grouping(),
+        KeyCode::Char('d') => app.toggle_done_tasks(),
+        _ => {}
+    }
+}
+
+fn handle_rename_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Esc => {
+            app.cancel_rename();
+            app.set_message("Rename cancelled".to_string());
+        }
+        
+        KeyCode::Enter => {
+            let name = app.input_buffer.clone();
+            // Invalid names keep the dialog open so they can be corrected
+            if let Err(e) = app.submit_rename(&name) {
+                app.set_message(e);
+            }
+        }
+        
+        KeyCode::Char(c) => {
+            app.input_buffer.push(c);
+        }
+        
+        KeyCode::Backspace => {
+            app.input_buffer.pop();
+        }
+        
+        _ => {}
+    }
+}
+
+fn handle_tag_edit_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Esc => {
+            app.cancel_tag_edit();
+        }
+        
+        KeyCode::Enter => {
+            app.finish_tag_edit();
+        }
+        
+        // Complete the tag being typed from existing tags
+        KeyCode::Tab => {
+            app.accept_tag_suggestion();
+        }
+        
+        KeyCode::Down => {
+            app.next_tag_suggestion();
+        }
+        
+        KeyCode::Up => {
+            app.previous_tag_suggestion();
+        }
+        
+        KeyCode::Char(c) => {
+            app.input_buffer.push(c);
+            app.update_tag_suggestions();
+        }
+        
+        KeyCode::Backspace => {
+            app.input_buffer.pop();
+            app.update_tag_suggestions();
+        }
+        
+        _ => {}
+    }
+}
+
+fn handle_tag_browser_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => app.mode = AppMode::Normal,
+        KeyCode::Char('j') | KeyCode::Down => app.next_tag_in_browser(),
+        KeyCode::Char('k') | KeyCode::Up => app.previous_tag_in_browser(),
+        KeyCode::Char('h') | KeyCode::Left => app.collapse_tag_in_browser(),
+        KeyCode::Char('l') | KeyCode::Right => app.expand_tag_in_browser(),
+        KeyCode::Enter => app.filter_by_selected_tag(),
+        KeyCode::Char('r') => app.start_tag_rename(),
+        KeyCode::Char('c') => {
+            app.mode = AppMode::Normal;
+            app.clear_tag_filter();
+        }
+        _ => {}
+    }
+}
+
+fn handle_quick_open_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Esc => app.close_quick_open(),
+        KeyCode::Enter => {
+            if let Err(e) = app.open_selected_quick_open() {
+                app.set_message(e);
+            }
+        }
+        KeyCode::Down | KeyCode::Tab => app.next_quick_open_result(),
+        KeyCode::Up | KeyCode::BackTab => app.previous_quick_open_result(),
+        KeyCode::Char('n') if key.modifiers.contains(KeyModifiers::CONTROL) => app.next_quick_open_result(),
+        KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => app.previous_quick_open_result(),
+        KeyCode::Char(c) => {
+            app.input_buffer.push(c);
+            app.update_quick_open();
+        }
+        KeyCode::Backspace => {
+            app.input_buffer.pop();
+            app.update_quick_open();
+        }
+        _ => {}
+    }
+}
+
+fn handle_recent_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('R') => app.mode = AppMode::Normal,
+        KeyCode::Char('j') | KeyCode::Down => app.next_recent_note(),
+        KeyCode::Char('k') | KeyCode::Up => app.previous_recent_note(),
+        KeyCode::Enter => {
+            if let Err(e) = app.open_selected_recent_note() {
+                app.set_message(e);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn handle_saved_search_edit_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Esc => app.cancel_saved_search_edit(),
+        KeyCode::Enter => {
+            // An invalid query keeps the dialog open so it can be corrected
+            if let Err(e) = app.submit_saved_search_edit() {
+                app.set_message(e);
+            }
+        }
+        KeyCode::Char(c) => app.input_buffer.push(c),
+        KeyCode::Backspace => {
+            app.input_buffer.pop();
+        }
+        _ => {}
+    }
+}
+
+fn handle_template_picker_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Esc => app.cancel_template(),
+        KeyCode::Char('j') | KeyCode::Down | KeyCode::Tab => app.next_template(),
+        KeyCode::Char('k') | KeyCode::Up | KeyCode::BackTab => app.previous_template(),
+        KeyCode::Enter => {
+            if let Err(e) = app.choose_template() {
+                app.cancel_template();
+                app.set_message(e);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn handle_template_prompt_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Esc => app.cancel_template(),
+        KeyCode::Enter => {
+            if let Err(e) = app.submit_template_field() {
+                app.cancel_template();
+                app.set_message(e);
+            }
+        }
+        KeyCode::Char(c) => app.input_buffer.push(c),
+        KeyCode::Backspace => {
+            app.input_buffer.pop();
+        }
+        _ => {}
+    }
+}
+
+fn handle_command_palette_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Esc => app.close_command_palette(),
+        KeyCode::Enter => app.run_selected_palette_command(),
+        KeyCode::Down | KeyCode::Tab => app.next_palette_match(),
+        KeyCode::Up | KeyCode::BackTab => app.previous_palette_match(),
+        KeyCode::Char('n') if key.modifiers.contains(KeyModifiers::CONTROL) => app.next_palette_match(),
+        KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => app.previous_palette_match(),
+        KeyCode::Char(c) => {
+            app.input_buffer.push(c);
+            app.update_command_palette();
+        }
+        KeyCode::Backspace => {
+            app.input_buffer.pop();
+            app.update_command_palette();
+        }
+        _ => {}
+    }
+}
+
+fn handle_tag_rename_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Esc => {
+            app.cancel_tag_rename();
+        }
+        
+        KeyCode::Enter => {
+            let name = app.input_buffer.clone();
+            // Invalid tags keep the prompt open so they can be corrected
+            if let Err(e) = app.submit_tag_rename(&name) {
+                app.set_message(e);
+            }
+        }
+        
+        KeyCode::Char(c) => {
+            app.input_buffer.push(c);
+        }
+        
+        KeyCode::Backspace => {
+            app.input_buffer.pop();
+        }
+        
+        _ => {}
+    }
+}
+
+fn handle_rename_confirm_mode(app: &mut App, key: KeyEvent) {
+    let result = match key.code {
+        // Rename and rewrite the links pointing at the note
+        KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => app.confirm_rename(true),
+        // Rename but leave existing links as they are
+        KeyCode::Char('n') | KeyCode::Char('N') => app.confirm_rename(false),
+        KeyCode::Esc => {
+            app.cancel_rename();
+            app.set_message("Rename cancelled".to_string());
+            Ok(())
+        }
+        _ => Ok(()),
+    };
+    if let Err(e) = result {
+        app.set_message(e);
+    }
+}
+
 fn handle_delete_confirm_mode(app: &mut App, key: KeyEvent) {
     match key.code {
         // Confirm deletion with 'y' or Enter
@@ -690,3 +1053,107 @@ fn handle_delete_confirm_mode(app: &mut App, key: KeyEvent) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::COMMANDS;
+    use crate::models::{Note, NotebookData};
+
+    /// The key a registry binding such as `Ctrl+S` or `/ (editor)` names, and its context
+    fn parse_binding(binding: &str) -> (KeyEvent, &str) {
+        let (keys, context) = match binding.split_once(" (") {
+            Some((keys, context)) => (keys, context.trim_end_matches(')')),
+            None => (binding, ""),
+        };
+        let (modifiers, key) = match keys.strip_prefix("Ctrl+") {
+            Some(key) => (KeyModifiers::CONTROL, key.to_lowercase()),
+            None => (KeyModifiers::NONE, keys.to_string()),
+        };
+        let mut chars = key.chars();
+        let (Some(c), None) = (chars.next(), chars.next()) else {
+            panic!("unsupported keybinding '{}'", binding);
+        };
+        (KeyEvent::new(KeyCode::Char(c), modifiers), context)
+    }
+
+    /// An app with a note open in the editor, set up for a binding's context
+    fn app_in_context(context: &str) -> App {
+        let mut notebook = NotebookData::new();
+        let mut note = Note::new("Plans".to_string(), None);
+        note.content = "- [ ] plan the week #work\nplan more".to_string();
+        let note_id = note.id;
+        notebook.add_note(note);
+
+        let mut app = App::new();
+        app.load_notebook(notebook);
+        app.external_editor = None;
+        app.select_note(note_id);
+        app.focused_pane = FocusedPane::Editor;
+        match context {
+            "" | "editor" => {}
+            "outside editor" => app.focused_pane = FocusedPane::Folders,
+            "after /" => app.find_query = "plan".to_string(),
+            "results" => app.search_notes("plan".to_string()),
+            other => panic!("unknown keybinding context '{}'", other),
+        }
+        app
+    }
+
+    /// What a key or command visibly changed
+    fn observable(app: &App) -> String {
+        format!(
+            "{:?} {:?} quit={} note={:?} preview={} backlinks={} message={:?} input={:?} command={:?} content={:?} cursor={:?}",
+            app.mode,
+            app.focused_pane,
+            app.should_quit,
+            app.current_note.as_ref().map(|n| &n.title),
+            app.preview_enabled,
+            app.backlinks_enabled,
+            app.status_message,
+            app.input_buffer,
+            app.command_buffer,
+            app.editor_content,
+            app.editor_cursor,
+        )
+    }
+
+    #[test]
+    fn registry_keybindings_match_the_key_handlers() {
+        for command in COMMANDS {
+            let Some(binding) = command.keybinding else {
+                continue;
+            };
+            let (key, context) = parse_binding(binding);
+
+            let mut pressed = app_in_context(context);
+            handle_normal_mode(&mut pressed, key);
+            let mut run = app_in_context(context);
+            (command.run)(&mut run, "");
+
+            assert_eq!(observable(&pressed), observable(&run), ":{} is listed as {}", command.name, binding);
+        }
+    }
+
+    #[test]
+    fn ctrl_i_jumps_forward_like_ctrl_n() {
+        let mut notebook = NotebookData::new();
+        let first = Note::new("First".to_string(), None);
+        let second = Note::new("Second".to_string(), None);
+        let ids = (first.id, second.id);
+        notebook.add_note(first);
+        notebook.add_note(second);
+        let mut app = App::new();
+        app.load_notebook(notebook);
+        app.select_note(ids.0);
+        app.select_note(ids.1);
+
+        let ctrl = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+        for forward in ['i', 'n'] {
+            handle_normal_mode(&mut app, ctrl('o'));
+            assert_eq!(app.current_note.as_ref().map(|n| n.id), Some(ids.0));
+            handle_normal_mode(&mut app, ctrl(forward));
+            assert_eq!(app.current_note.as_ref().map(|n| n.id), Some(ids.1));
+        }
+    }
+}
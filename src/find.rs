@@ -0,0 +1,89 @@
+/// An occurrence of the find query in the open note, as byte offsets within its line
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FindMatch {
+    pub line: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Occurrences of the query in one line, left to right and not overlapping.
+/// Case is ignored unless the query has an uppercase letter.
+pub fn find_in_line(line: &str, query: &str) -> Vec<(usize, usize)> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let ignore_case = !query.chars().any(char::is_uppercase);
+    let same = |a: char, b: char| {
+        if ignore_case {
+            a.to_lowercase().eq(b.to_lowercase())
+        } else {
+            a == b
+        }
+    };
+
+    let mut matches = Vec::new();
+    let mut from = 0;
+    while from < line.len() {
+        // Compare char by char so the offsets stay those of the original text
+        let mut end = from;
+        let mut rest = line[from..].chars();
+        let matched = query.chars().all(|q| {
+            rest.next().is_some_and(|c| {
+                end += c.len_utf8();
+                same(c, q)
+            })
+        });
+        if matched {
+            matches.push((from, end));
+            from = end;
+        } else {
+            from += line[from..].chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    matches
+}
+
+/// Every occurrence of the query in the text, in order
+pub fn find_all(text: &str, query: &str) -> Vec<FindMatch> {
+    text.split('\n')
+        .enumerate()
+        .flat_map(|(line, text)| {
+            find_in_line(text, query)
+                .into_iter()
+                .map(move |(start, end)| FindMatch { line, start, end })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lowercase_queries_ignore_case() {
+        assert_eq!(find_in_line("Rust rust RUST", "rust"), vec![(0, 4), (5, 9), (10, 14)]);
+        assert_eq!(find_in_line("Rust rust RUST", "Rust"), vec![(0, 4)]);
+    }
+
+    #[test]
+    fn matches_do_not_overlap() {
+        assert_eq!(find_in_line("aaaa", "aa"), vec![(0, 2), (2, 4)]);
+        assert!(find_in_line("text", "").is_empty());
+        assert!(find_in_line("ab", "abc").is_empty());
+    }
+
+    #[test]
+    fn offsets_are_bytes_of_the_original_line() {
+        assert_eq!(find_in_line("naïve NAÏVE", "ï"), vec![(2, 4), (9, 11)]);
+        assert_eq!(find_in_line("éa", "a"), vec![(2, 3)]);
+    }
+
+    #[test]
+    fn matches_are_found_on_every_line_in_order() {
+        let matches = find_all("one two\n\ntwo", "two");
+        assert_eq!(matches, vec![
+            FindMatch { line: 0, start: 4, end: 7 },
+            FindMatch { line: 2, start: 0, end: 3 },
+        ]);
+    }
+}
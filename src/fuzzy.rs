@@ -0,0 +1,135 @@
+/// A successful fuzzy match of a query against a candidate string
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzyMatch {
+    pub score: i64,
+    /// Char indices of the candidate that matched the query
+    pub positions: Vec<usize>,
+}
+
+const MATCH_SCORE: i64 = 16;
+const CONSECUTIVE_BONUS: i64 = 24;
+const WORD_START_BONUS: i64 = 20;
+const PREFIX_BONUS: i64 = 32;
+const GAP_PENALTY: i64 = 2;
+
+/// Match the query as a case-insensitive subsequence of the candidate.
+/// Consecutive runs, word starts and a matching prefix score higher;
+/// gaps and unmatched trailing text score lower.
+pub fn fuzzy_match(query: &str, candidate: &str) -> Option<FuzzyMatch> {
+    let query: Vec<char> = query.chars().filter(|c| !c.is_whitespace()).flat_map(char::to_lowercase).collect();
+    if query.is_empty() {
+        return Some(FuzzyMatch { score: 0, positions: Vec::new() });
+    }
+
+    let chars: Vec<char> = candidate.chars().collect();
+    let mut positions = Vec::with_capacity(query.len());
+    let mut score = 0;
+    let mut next = 0;
+
+    for (i, &c) in chars.iter().enumerate() {
+        if next == query.len() {
+            break;
+        }
+        if !c.to_lowercase().eq(std::iter::once(query[next])) {
+            continue;
+        }
+
+        score += MATCH_SCORE;
+        match positions.last() {
+            Some(&last) if last + 1 == i => score += CONSECUTIVE_BONUS,
+            Some(&last) => score -= GAP_PENALTY * (i - last - 1) as i64,
+            None if i == 0 => score += PREFIX_BONUS,
+            None => score -= GAP_PENALTY * i as i64,
+        }
+        let word_start = i == 0 || !chars[i - 1].is_alphanumeric() || (chars[i - 1].is_lowercase() && c.is_uppercase());
+        if word_start {
+            score += WORD_START_BONUS;
+        }
+
+        positions.push(i);
+        next += 1;
+    }
+
+    if next < query.len() {
+        return None;
+    }
+
+    // Prefer shorter candidates when everything else is equal
+    score -= (chars.len() - positions.len()) as i64;
+    Some(FuzzyMatch { score, positions })
+}
+
+/// Edits needed to turn one string into the other, counting insertions, deletions,
+/// substitutions and swaps of neighbouring characters. None when more than `max`.
+pub fn edit_distance(a: &str, b: &str, max: usize) -> Option<usize> {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+
+    let mut before_previous: Vec<usize> = Vec::new();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        let mut row = vec![i; b.len() + 1];
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            row[j] = (previous[j] + 1).min(row[j - 1] + 1).min(previous[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                row[j] = row[j].min(before_previous[j - 2] + 1);
+            }
+        }
+        // Every later row is at least the smallest entry of this one
+        if row.iter().min().is_some_and(|&least| least > max) {
+            return None;
+        }
+        before_previous = std::mem::replace(&mut previous, row);
+    }
+    Some(previous[b.len()]).filter(|&distance| distance <= max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(query: &str, candidate: &str) -> i64 {
+        fuzzy_match(query, candidate).unwrap().score
+    }
+
+    #[test]
+    fn queries_match_as_case_insensitive_subsequences() {
+        assert_eq!(fuzzy_match("MtN", "meeting notes").unwrap().positions, vec![0, 3, 5]);
+        assert_eq!(fuzzy_match("m g", "Meeting Notes").unwrap().positions, vec![0, 6]);
+        assert_eq!(fuzzy_match("xyz", "meeting notes"), None);
+        assert_eq!(fuzzy_match("", "anything").unwrap().score, 0);
+    }
+
+    #[test]
+    fn prefixes_runs_and_word_starts_score_higher() {
+        assert!(score("mee", "meeting") > score("mee", "a meeting"));
+        assert!(score("note", "notes") > score("note", "n o t e"));
+        assert!(score("mn", "Meeting Notes") > score("mn", "common"));
+        assert!(score("pa", "ProjectAlpha") > score("pa", "Preparation"));
+    }
+
+    #[test]
+    fn shorter_candidates_win_ties() {
+        assert!(score("plan", "plan") > score("plan", "plans"));
+    }
+
+    #[test]
+    fn edit_distance_counts_edits_and_swaps() {
+        assert_eq!(edit_distance("meeting", "meeting", 2), Some(0));
+        assert_eq!(edit_distance("meeting", "meetnig", 2), Some(1));
+        assert_eq!(edit_distance("meeting", "meting", 2), Some(1));
+        assert_eq!(edit_distance("kitten", "sitting", 3), Some(3));
+        assert_eq!(edit_distance("café", "cafe", 1), Some(1));
+    }
+
+    #[test]
+    fn edit_distance_gives_up_past_the_limit() {
+        assert_eq!(edit_distance("kitten", "sitting", 2), None);
+        assert_eq!(edit_distance("a", "abcd", 2), None);
+        assert_eq!(edit_distance("", "ab", 2), Some(2));
+    }
+}
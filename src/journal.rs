@@ -0,0 +1,108 @@
+use crate::snippets::SnippetBody;
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+/// Where daily notes live and what a new one looks like, read from the journal file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct JournalConfig {
+    /// Folder path from the root, with nested folders separated by `/`
+    pub folder: String,
+    /// chrono format string for the title of an entry
+    pub title_format: String,
+    /// Snippet body for a new entry; `$DATE` is the entry's date
+    pub template: SnippetBody,
+}
+
+impl Default for JournalConfig {
+    fn default() -> Self {
+        Self {
+            folder: "Daily Notes".to_string(),
+            title_format: "%Y-%m-%d".to_string(),
+            template: SnippetBody::Text("# $NOTE_TITLE\n\n$0".to_string()),
+        }
+    }
+}
+
+impl JournalConfig {
+    /// Names of the journal folder and its ancestors, from the root down
+    pub fn folder_path(&self) -> Vec<&str> {
+        self.folder.split('/').map(str::trim).filter(|s| !s.is_empty()).collect()
+    }
+
+    pub fn entry_title(&self, date: NaiveDate) -> String {
+        date.format(&self.title_format).to_string()
+    }
+
+    /// The date of an entry, if the title follows the title format
+    pub fn entry_date(&self, title: &str) -> Option<NaiveDate> {
+        NaiveDate::parse_from_str(title, &self.title_format).ok()
+    }
+}
+
+/// Read the journal settings, falling back to the defaults for missing fields
+pub fn load_config(path: &Path) -> Result<JournalConfig, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let config: JournalConfig = serde_json::from_str(&contents)
+        .map_err(|e| format!("Invalid journal file {}: {}", path.display(), e))?;
+
+    // Unknown specifiers and time fields fail to format a date, which `to_string` would panic on
+    let sample = NaiveDate::from_ymd_opt(2024, 1, 31).unwrap_or_default();
+    let mut title = String::new();
+    if write!(title, "{}", sample.format(&config.title_format)).is_err() {
+        return Err(format!("Journal title format '{}' is not a valid date format", config.title_format));
+    }
+    // A format without a full date cannot be read back, so entries could not be found again
+    if config.entry_date(&config.entry_title(sample)) != Some(sample) {
+        return Err(format!("Journal title format '{}' must include the year, month and day", config.title_format));
+    }
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(json: &str) -> Result<JournalConfig, String> {
+        let path = std::env::temp_dir().join(format!("scribble-journal-{}.json", uuid::Uuid::new_v4()));
+        fs::write(&path, json).unwrap();
+        let config = load_config(&path);
+        let _ = fs::remove_file(&path);
+        config
+    }
+
+    #[test]
+    fn missing_fields_fall_back_to_defaults() {
+        let config = load(r#"{"folder": "Journal/Daily"}"#).unwrap();
+        assert_eq!(config.folder_path(), vec!["Journal", "Daily"]);
+        assert_eq!(config.title_format, "%Y-%m-%d");
+    }
+
+    #[test]
+    fn titles_round_trip_through_the_format() {
+        let config = load(r#"{"title_format": "%A, %d %B %Y"}"#).unwrap();
+        let date = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+        let title = config.entry_title(date);
+        assert_eq!(title, "Sunday, 18 October 2026");
+        assert_eq!(config.entry_date(&title), Some(date));
+        assert_eq!(config.entry_date("Shopping list"), None);
+    }
+
+    #[test]
+    fn formats_that_cannot_format_a_date_are_rejected() {
+        for format in ["%Q", "%Y-%m-%d %H", "%Y-%m-%d %"] {
+            let error = load(&format!(r#"{{"title_format": "{}"}}"#, format)).unwrap_err();
+            assert!(error.contains("is not a valid date format"), "{}: {}", format, error);
+        }
+    }
+
+    #[test]
+    fn formats_without_a_full_date_are_rejected() {
+        let error = load(r#"{"title_format": "%Y-%m"}"#).unwrap_err();
+        assert!(error.contains("must include the year, month and day"), "{}", error);
+    }
+}
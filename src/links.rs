@@ -0,0 +1,402 @@
+use crate::models::{Note, NotebookData};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// A `[[Target#heading|alias]]` link between notes
+#[derive(Debug, Clone, PartialEq)]
+pub struct WikiLink {
+    pub target: String,
+    pub heading: Option<String>,
+    pub alias: Option<String>,
+    /// Byte range of the whole link, brackets included
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Find the wiki links in a piece of text. Links do not span lines.
+pub fn parse_links(text: &str) -> Vec<WikiLink> {
+    let mut links = Vec::new();
+    let mut from = 0;
+
+    while let Some(open) = text[from..].find("[[").map(|i| from + i) {
+        let inner_start = open + 2;
+        let Some(close) = text[inner_start..].find("]]").map(|i| inner_start + i) else {
+            break;
+        };
+        let inner = &text[inner_start..close];
+
+        // A newline or a later `[[` means this one is not a link; retry from there
+        if let Some(newline) = inner.rfind('\n') {
+            from = inner_start + newline + 1;
+            continue;
+        }
+        if let Some(reopen) = inner.rfind("[[") {
+            from = inner_start + reopen;
+            continue;
+        }
+
+        if let Some(link) = parse_inner(inner, open, close + 2) {
+            links.push(link);
+        }
+        from = close + 2;
+    }
+    links
+}
+
+fn parse_inner(inner: &str, start: usize, end: usize) -> Option<WikiLink> {
+    let (reference, alias) = match inner.split_once('|') {
+        Some((reference, alias)) => (reference, Some(alias.trim().to_string()).filter(|a| !a.is_empty())),
+        None => (inner, None),
+    };
+    let (target, heading) = match reference.split_once('#') {
+        Some((target, heading)) => (target, Some(heading.trim().to_string()).filter(|h| !h.is_empty())),
+        None => (reference, None),
+    };
+
+    let target = target.trim();
+    if target.is_empty() {
+        return None;
+    }
+    Some(WikiLink {
+        target: target.to_string(),
+        heading,
+        alias,
+        start,
+        end,
+    })
+}
+
+/// The link containing byte column `col` of a line
+pub fn link_at(line: &str, col: usize) -> Option<WikiLink> {
+    parse_links(line)
+        .into_iter()
+        .find(|link| link.start <= col && col < link.end)
+}
+
+/// Whether a title can be written as a link target: `#`, `|`, `[[` and `]]`
+/// would split `[[title]]` into a different target, heading or alias
+pub fn is_linkable_title(title: &str) -> bool {
+    !title.contains(['#', '|']) && !title.contains("[[") && !title.contains("]]") && !title.ends_with(']')
+}
+
+/// The note a link target refers to: an exact title match, else a case-insensitive one
+pub fn resolve(notebook: &NotebookData, target: &str) -> Option<Uuid> {
+    let target = target.trim();
+    notebook
+        .notes
+        .values()
+        .find(|note| note.title == target)
+        .or_else(|| {
+            let target = target.to_lowercase();
+            notebook.notes.values().find(|note| note.title.to_lowercase() == target)
+        })
+        .map(|note| note.id)
+}
+
+/// Point every link to `old_title` at `new_title`, keeping headings and aliases.
+/// Returns None if nothing changed. Links in fenced code blocks are left alone.
+pub fn rewrite_links(content: &str, old_title: &str, new_title: &str) -> Option<String> {
+    let old_key = old_title.to_lowercase();
+    let mut changed = false;
+    let mut in_code_block = false;
+
+    let lines: Vec<String> = content
+        .split('\n')
+        .map(|line| {
+            if line.trim_start().starts_with("```") {
+                in_code_block = !in_code_block;
+            }
+            if in_code_block {
+                return line.to_string();
+            }
+
+            let mut line = line.to_string();
+            for link in parse_links(&line).into_iter().rev() {
+                if link.target.to_lowercase() != old_key {
+                    continue;
+                }
+                let mut rewritten = format!("[[{}", new_title);
+                if let Some(heading) = &link.heading {
+                    rewritten.push_str(&format!("#{}", heading));
+                }
+                if let Some(alias) = &link.alias {
+                    rewritten.push_str(&format!("|{}", alias));
+                }
+                rewritten.push_str("]]");
+                line.replace_range(link.start..link.end, &rewritten);
+                changed = true;
+            }
+            line
+        })
+        .collect();
+
+    changed.then(|| lines.join("\n"))
+}
+
+/// Line number of the heading with the given text, ignoring case
+pub fn heading_line(content: &str, heading: &str) -> Option<usize> {
+    content.split('\n').position(|line| {
+        let trimmed = line.trim_start();
+        trimmed.starts_with('#') && trimmed.trim_start_matches('#').trim().eq_ignore_ascii_case(heading.trim())
+    })
+}
+
+/// If the cursor is inside an unfinished `[[` on this line, the byte column
+/// of the brackets and the text typed after them
+pub fn open_link_query(line_up_to_cursor: &str) -> Option<(usize, &str)> {
+    let open = line_up_to_cursor.rfind("[[")?;
+    let query = &line_up_to_cursor[open + 2..];
+    if query.contains("]]") || query.contains('|') || query.contains('#') || query.contains('[') {
+        return None;
+    }
+    Some((open, query))
+}
+
+/// A link from one line of a note
+#[derive(Debug, Clone)]
+struct LinkOccurrence {
+    /// Lowercased link target
+    target: String,
+    line_number: usize,
+    line: String,
+}
+
+/// A note linking to the current one, with the line the link is on
+#[derive(Debug, Clone)]
+pub struct Backlink {
+    pub source_id: Uuid,
+    pub source_title: String,
+    pub line_number: usize,
+    pub context: String,
+}
+
+/// Links between notes, kept up to date one note at a time as notes change
+#[derive(Debug, Clone, Default)]
+pub struct LinkIndex {
+    by_source: HashMap<Uuid, Vec<LinkOccurrence>>,
+    /// Lowercased target title to the notes linking to it
+    by_target: HashMap<String, HashSet<Uuid>>,
+}
+
+impl LinkIndex {
+    pub fn build(notebook: &NotebookData) -> Self {
+        let mut index = Self::default();
+        for note in notebook.notes.values() {
+            index.update_note(note);
+        }
+        index
+    }
+
+    /// Re-index the links in one note
+    pub fn update_note(&mut self, note: &Note) {
+        self.remove_note(note.id);
+
+        let mut in_code_block = false;
+        let mut occurrences = Vec::new();
+        for (line_number, line) in note.content.split('\n').enumerate() {
+            if line.trim_start().starts_with("```") {
+                in_code_block = !in_code_block;
+                continue;
+            }
+            if in_code_block {
+                continue;
+            }
+            for link in parse_links(line) {
+                occurrences.push(LinkOccurrence {
+                    target: link.target.to_lowercase(),
+                    line_number,
+                    line: line.trim().to_string(),
+                });
+            }
+        }
+
+        for occurrence in &occurrences {
+            self.by_target.entry(occurrence.target.clone()).or_default().insert(note.id);
+        }
+        if !occurrences.is_empty() {
+            self.by_source.insert(note.id, occurrences);
+        }
+    }
+
+    pub fn remove_note(&mut self, note_id: Uuid) {
+        if let Some(occurrences) = self.by_source.remove(&note_id) {
+            for occurrence in occurrences {
+                if let Some(sources) = self.by_target.get_mut(&occurrence.target) {
+                    sources.remove(&note_id);
+                    if sources.is_empty() {
+                        self.by_target.remove(&occurrence.target);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Notes with at least one link to the given title, including self-links
+    pub fn sources_linking_to(&self, title: &str) -> Vec<Uuid> {
+        self.by_target
+            .get(&title.to_lowercase())
+            .map(|sources| sources.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Every line in another note that links to the given note, sorted by note title
+    pub fn backlinks(&self, notebook: &NotebookData, note_id: Uuid) -> Vec<Backlink> {
+        let Some(note) = notebook.notes.get(&note_id) else {
+            return Vec::new();
+        };
+        let target = note.title.to_lowercase();
+        let Some(sources) = self.by_target.get(&target) else {
+            return Vec::new();
+        };
+
+        let mut backlinks: Vec<Backlink> = sources
+            .iter()
+            .filter(|&&source_id| source_id != note_id)
+            .filter_map(|source_id| Some((notebook.notes.get(source_id)?, self.by_source.get(source_id)?)))
+            .flat_map(|(source, occurrences)| {
+                let mut lines_seen = HashSet::new();
+                occurrences
+                    .iter()
+                    .filter(|o| o.target == target && lines_seen.insert(o.line_number))
+                    .map(|o| Backlink {
+                        source_id: source.id,
+                        source_title: source.title.clone(),
+                        line_number: o.line_number,
+                        context: o.line.clone(),
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        backlinks.sort_by(|a, b| {
+            a.source_title.to_lowercase().cmp(&b.source_title.to_lowercase()).then(a.line_number.cmp(&b.line_number))
+        });
+        backlinks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notebook(notes: &[(&str, &str)]) -> NotebookData {
+        let mut notebook = NotebookData::new();
+        for (title, content) in notes {
+            let mut note = Note::new(title.to_string(), None);
+            note.content = content.to_string();
+            notebook.add_note(note);
+        }
+        notebook
+    }
+
+    fn id_of(notebook: &NotebookData, title: &str) -> Uuid {
+        notebook.notes.values().find(|note| note.title == title).unwrap().id
+    }
+
+    #[test]
+    fn links_have_targets_headings_and_aliases() {
+        let links = parse_links("see [[Plan#Goals|the goals]] and [[ Ideas ]]");
+        assert_eq!(links.len(), 2);
+        assert_eq!(links[0].target, "Plan");
+        assert_eq!(links[0].heading.as_deref(), Some("Goals"));
+        assert_eq!(links[0].alias.as_deref(), Some("the goals"));
+        assert_eq!((links[0].start, links[0].end), (4, 28));
+        assert_eq!(links[1].target, "Ideas");
+        assert_eq!(links[1].heading, None);
+    }
+
+    #[test]
+    fn unfinished_and_empty_links_are_skipped() {
+        assert!(parse_links("[[]] [[#heading]] [[open").is_empty());
+        assert_eq!(parse_links("[[broken\n[[Fine]]")[0].target, "Fine");
+        assert_eq!(parse_links("[[a [[b]]")[0].target, "b");
+    }
+
+    #[test]
+    fn link_at_finds_the_link_under_the_cursor() {
+        let line = "x [[A]] y";
+        assert_eq!(link_at(line, 2).map(|l| l.target), Some("A".to_string()));
+        assert_eq!(link_at(line, 6).map(|l| l.target), Some("A".to_string()));
+        assert_eq!(link_at(line, 7), None);
+    }
+
+    #[test]
+    fn targets_resolve_exactly_then_ignoring_case() {
+        let notebook = notebook(&[("Plan", ""), ("plan", ""), ("Ideas", "")]);
+        assert_eq!(resolve(&notebook, "plan"), Some(id_of(&notebook, "plan")));
+        assert_eq!(resolve(&notebook, " IDEAS "), Some(id_of(&notebook, "Ideas")));
+        assert_eq!(resolve(&notebook, "Missing"), None);
+    }
+
+    #[test]
+    fn headings_are_found_ignoring_case() {
+        assert_eq!(heading_line("intro\n## Next Steps\ntext", "next steps"), Some(1));
+        assert_eq!(heading_line("Next Steps", "next steps"), None);
+    }
+
+    #[test]
+    fn link_queries_stop_at_closed_links() {
+        assert_eq!(open_link_query("see [[Pla"), Some((4, "Pla")));
+        assert_eq!(open_link_query("see [[Plan]] x"), None);
+        assert_eq!(open_link_query("see [[Plan#go"), None);
+    }
+
+    #[test]
+    fn backlinks_list_linking_lines_from_other_notes() {
+        let notebook = notebook(&[
+            ("Target", "self link [[Target]]"),
+            ("b source", "[[target]] twice [[Target|again]]\nplain\n  see [[Target#Top]]"),
+            ("A source", "```\n[[Target]]\n```\n[[Target]]"),
+        ]);
+        let index = LinkIndex::build(&notebook);
+        let backlinks = index.backlinks(&notebook, id_of(&notebook, "Target"));
+        let found: Vec<(&str, usize, &str)> = backlinks
+            .iter()
+            .map(|b| (b.source_title.as_str(), b.line_number, b.context.as_str()))
+            .collect();
+        assert_eq!(found, vec![
+            ("A source", 3, "[[Target]]"),
+            ("b source", 0, "[[target]] twice [[Target|again]]"),
+            ("b source", 2, "see [[Target#Top]]"),
+        ]);
+    }
+
+    #[test]
+    fn updating_a_note_replaces_its_links() {
+        let mut notebook = notebook(&[("Target", ""), ("Source", "[[Target]]")]);
+        let mut index = LinkIndex::build(&notebook);
+        let source_id = id_of(&notebook, "Source");
+        let target_id = id_of(&notebook, "Target");
+
+        let source = notebook.notes.get_mut(&source_id).unwrap();
+        source.content = "no links".to_string();
+        index.update_note(source);
+        assert!(index.backlinks(&notebook, target_id).is_empty());
+        assert!(index.by_target.is_empty());
+
+        index.update_note(&Note { content: "[[Target]]".to_string(), ..notebook.notes[&source_id].clone() });
+        assert_eq!(index.backlinks(&notebook, target_id).len(), 1);
+        index.remove_note(source_id);
+        assert!(index.by_source.is_empty() && index.by_target.is_empty());
+    }
+
+    #[test]
+    fn renaming_rewrites_links_keeping_headings_and_aliases() {
+        let content = "[[old note#Top|alias]] and [[Other]]\n```\n[[Old Note]]\n```\n[[Old Note]]";
+        assert_eq!(
+            rewrite_links(content, "Old Note", "New").as_deref(),
+            Some("[[New#Top|alias]] and [[Other]]\n```\n[[Old Note]]\n```\n[[New]]")
+        );
+        assert_eq!(rewrite_links("[[Other]]", "Old Note", "New"), None);
+    }
+
+    #[test]
+    fn sources_include_self_links() {
+        let notebook = notebook(&[("Loop", "[[loop]]"), ("Other", "[[Loop]]")]);
+        let index = LinkIndex::build(&notebook);
+        let mut sources = index.sources_linking_to("LOOP");
+        sources.sort();
+        let mut expected = vec![id_of(&notebook, "Loop"), id_of(&notebook, "Other")];
+        expected.sort();
+        assert_eq!(sources, expected);
+    }
+}
@@ -0,0 +1,308 @@
+/// A list marker at the start of a markdown line
+#[derive(Debug, Clone, PartialEq)]
+pub enum ListMarker {
+    Bullet(char),
+    Ordered { number: u64, delimiter: char },
+}
+
+impl ListMarker {
+    /// The marker for the item that follows this one
+    pub fn next(&self) -> ListMarker {
+        match self {
+            ListMarker::Bullet(c) => ListMarker::Bullet(*c),
+            ListMarker::Ordered { number, delimiter } => ListMarker::Ordered {
+                number: number + 1,
+                delimiter: *delimiter,
+            },
+        }
+    }
+
+    /// The marker as written, including the trailing space
+    pub fn render(&self) -> String {
+        match self {
+            ListMarker::Bullet(c) => format!("{} ", c),
+            ListMarker::Ordered { number, delimiter } => format!("{}{} ", number, delimiter),
+        }
+    }
+}
+
+/// The leading structure of a markdown line: block quotes, indentation,
+/// list marker and task checkbox
+#[derive(Debug, Clone)]
+pub struct LinePrefix<'a> {
+    pub quote: &'a str,
+    pub indent: &'a str,
+    pub marker: Option<ListMarker>,
+    pub checkbox: Option<bool>,
+    /// Byte length of the whole prefix; the item text starts here
+    pub len: usize,
+}
+
+impl<'a> LinePrefix<'a> {
+    pub fn parse(line: &'a str) -> Self {
+        // Block quote markers, each optionally followed by one space
+        let mut quote_end = 0;
+        loop {
+            let rest = line[quote_end..].trim_start_matches([' ', '\t']);
+            match rest.strip_prefix('>') {
+                Some(after) => {
+                    let after = after.strip_prefix(' ').unwrap_or(after);
+                    quote_end = line.len() - after.len();
+                }
+                None => break,
+            }
+        }
+
+        let rest = &line[quote_end..];
+        let indent_len = rest.len() - rest.trim_start_matches([' ', '\t']).len();
+        let indent = &rest[..indent_len];
+
+        let marker = parse_marker(&rest[indent_len..]);
+        let mut len = quote_end + indent_len + marker.as_ref().map_or(0, |(_, l)| *l);
+
+        let mut checkbox = None;
+        if marker.is_some() {
+            let after = &line[len..];
+            for (pattern, checked) in [("[ ]", false), ("[x]", true), ("[X]", true)] {
+                if after == pattern || after.starts_with(&format!("{} ", pattern)) {
+                    checkbox = Some(checked);
+                    len = (len + pattern.len() + 1).min(line.len());
+                    break;
+                }
+            }
+        }
+
+        Self {
+            quote: &line[..quote_end],
+            indent,
+            marker: marker.map(|(m, _)| m),
+            checkbox,
+            len,
+        }
+    }
+
+    /// Width of the list marker including its trailing space
+    pub fn marker_width(&self) -> usize {
+        self.marker.as_ref().map_or(0, |m| m.render().len())
+    }
+
+    /// Prefix for a new line continuing this one
+    pub fn continuation(&self) -> String {
+        let mut prefix = format!("{}{}", self.quote, self.indent);
+        if let Some(ref marker) = self.marker {
+            prefix.push_str(&marker.next().render());
+            if self.checkbox.is_some() {
+                prefix.push_str("[ ] ");
+            }
+        }
+        prefix
+    }
+}
+
+fn parse_marker(body: &str) -> Option<(ListMarker, usize)> {
+    let first = body.chars().next()?;
+    if matches!(first, '-' | '*' | '+') {
+        return body[1..].starts_with(' ').then_some((ListMarker::Bullet(first), 2));
+    }
+
+    let digits = body.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits == 0 || digits > 9 {
+        return None;
+    }
+    let delimiter = body[digits..].chars().next()?;
+    if !matches!(delimiter, '.' | ')') || !body[digits + 1..].starts_with(' ') {
+        return None;
+    }
+    let number = body[..digits].parse().ok()?;
+    Some((ListMarker::Ordered { number, delimiter }, digits + 2))
+}
+
+/// What pressing Enter should do to the current line
+#[derive(Debug, Clone, PartialEq)]
+pub enum EnterAction {
+    /// Insert this text at the cursor
+    Insert(String),
+    /// Replace the current line without adding a new one
+    ReplaceLine(String),
+}
+
+/// Decide how Enter continues the markdown structure of `lines[row]`
+/// with the cursor at byte column `col`
+pub fn on_enter(lines: &[&str], row: usize, col: usize) -> EnterAction {
+    let line = lines.get(row).copied().unwrap_or("");
+    let prefix = LinePrefix::parse(line);
+
+    // Cursor inside the prefix: don't duplicate it
+    if col < prefix.len {
+        return EnterAction::Insert("\n".to_string());
+    }
+
+    let item_is_empty = line[prefix.len..].trim().is_empty();
+
+    if prefix.marker.is_some() {
+        if !item_is_empty {
+            return EnterAction::Insert(format!("\n{}", prefix.continuation()));
+        }
+        // Enter on an empty item ends the list, one nesting level at a time
+        if !prefix.indent.is_empty() {
+            let indent = outdent_target(lines, row);
+            let body = &line[prefix.quote.len() + prefix.indent.len()..];
+            return EnterAction::ReplaceLine(format!("{}{}{}", prefix.quote, indent, body));
+        }
+        return EnterAction::ReplaceLine(prefix.quote.to_string());
+    }
+
+    if !prefix.quote.is_empty() && item_is_empty {
+        // An empty quote line ends the block quote
+        return EnterAction::ReplaceLine(String::new());
+    }
+
+    EnterAction::Insert(format!("\n{}{}", prefix.quote, prefix.indent))
+}
+
+/// Indentation that nests the list item at `row` one level deeper,
+/// under the closest previous sibling
+pub fn indent_target(lines: &[&str], row: usize) -> Option<String> {
+    let current = LinePrefix::parse(lines.get(row)?);
+    current.marker.as_ref()?;
+
+    let width = lines[..row]
+        .iter()
+        .rev()
+        .map(|line| LinePrefix::parse(line))
+        .filter(|p| p.marker.is_some() && p.quote == current.quote)
+        .find(|p| p.indent.len() <= current.indent.len())
+        .filter(|p| p.indent.len() == current.indent.len())
+        .map_or(current.marker_width(), |sibling| sibling.marker_width());
+
+    Some(format!("{}{}", current.indent, " ".repeat(width)))
+}
+
+/// Indentation of the parent list item of `row`, or none at the top level
+pub fn outdent_target(lines: &[&str], row: usize) -> String {
+    let current = LinePrefix::parse(lines.get(row).copied().unwrap_or(""));
+    lines[..row.min(lines.len())]
+        .iter()
+        .rev()
+        .map(|line| LinePrefix::parse(line))
+        .find(|p| p.marker.is_some() && p.quote == current.quote && p.indent.len() < current.indent.len())
+        .map_or(String::new(), |parent| parent.indent.to_string())
+}
+
+/// Marker placed before the completion date of a done task
+pub const COMPLETION_MARKER: &str = "✅";
+
+/// Toggle the task checkbox on a list item line, stamping the completion
+/// date when checking it off. A plain list item becomes an open task.
+/// Returns None if the line is not a list item.
+pub fn toggle_checkbox(line: &str, today: chrono::NaiveDate) -> Option<String> {
+    let prefix = LinePrefix::parse(line);
+    prefix.marker.as_ref()?;
+    let box_start = prefix.quote.len() + prefix.indent.len() + prefix.marker_width();
+
+    let toggled = match prefix.checkbox {
+        Some(false) => {
+            let text = line[box_start + 3..].trim_end();
+            format!("{}[x]{} {} {}", &line[..box_start], text, COMPLETION_MARKER, today.format("%Y-%m-%d"))
+        }
+        Some(true) => {
+            let text = strip_completion_date(&line[box_start + 3..]);
+            format!("{}[ ]{}", &line[..box_start], text)
+        }
+        None => format!("{}[ ] {}", &line[..box_start], &line[box_start..]),
+    };
+    Some(toggled)
+}
+
+/// Remove a trailing ` ✅ YYYY-MM-DD` completion stamp
+pub fn strip_completion_date(text: &str) -> &str {
+    let marker = format!(" {} ", COMPLETION_MARKER);
+    match text.rfind(&marker) {
+        Some(pos) if chrono::NaiveDate::parse_from_str(text[pos + marker.len()..].trim(), "%Y-%m-%d").is_ok() => {
+            &text[..pos]
+        }
+        _ => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enter(lines: &[&str], row: usize) -> EnterAction {
+        on_enter(lines, row, lines[row].len())
+    }
+
+    #[test]
+    fn prefixes_are_parsed_through_quotes_and_indentation() {
+        let prefix = LinePrefix::parse("> \t  12) item");
+        assert_eq!(prefix.quote, "> ");
+        assert_eq!(prefix.indent, "\t  ");
+        assert_eq!(prefix.marker, Some(ListMarker::Ordered { number: 12, delimiter: ')' }));
+        assert_eq!(&"> \t  12) item"[prefix.len..], "item");
+        assert_eq!(LinePrefix::parse("-not a list").marker, None);
+        assert_eq!(LinePrefix::parse("1234567890. too long").marker, None);
+    }
+
+    #[test]
+    fn enter_continues_lists_and_quotes() {
+        assert_eq!(enter(&["- one"], 0), EnterAction::Insert("\n- ".to_string()));
+        assert_eq!(enter(&["9. nine"], 0), EnterAction::Insert("\n10. ".to_string()));
+        assert_eq!(enter(&["> quoted"], 0), EnterAction::Insert("\n> ".to_string()));
+        assert_eq!(enter(&["    code"], 0), EnterAction::Insert("\n    ".to_string()));
+    }
+
+    #[test]
+    fn enter_inside_the_prefix_only_breaks_the_line() {
+        assert_eq!(on_enter(&["- item"], 0, 1), EnterAction::Insert("\n".to_string()));
+    }
+
+    #[test]
+    fn enter_on_an_empty_item_ends_the_list_one_level_at_a_time() {
+        let lines = ["- parent", "  - child", "    - "];
+        assert_eq!(enter(&lines, 2), EnterAction::ReplaceLine("  - ".to_string()));
+        assert_eq!(enter(&["- "], 0), EnterAction::ReplaceLine(String::new()));
+        assert_eq!(enter(&["> - "], 0), EnterAction::ReplaceLine("> ".to_string()));
+        assert_eq!(enter(&["> "], 0), EnterAction::ReplaceLine(String::new()));
+    }
+
+    #[test]
+    fn indent_nests_under_the_previous_sibling() {
+        assert_eq!(indent_target(&["1. one", "2. two"], 1), Some("   ".to_string()));
+        assert_eq!(indent_target(&["- one", "- two"], 1), Some("  ".to_string()));
+        assert_eq!(indent_target(&["text"], 0), None);
+        assert_eq!(outdent_target(&["- one", "  - two", "    - three"], 2), "  ");
+        assert_eq!(outdent_target(&["- one", "  - two"], 1), "");
+    }
+
+    fn day() -> chrono::NaiveDate {
+        chrono::NaiveDate::from_ymd_opt(2026, 10, 18).unwrap()
+    }
+
+    #[test]
+    fn checking_a_task_stamps_the_date_and_unchecking_removes_it() {
+        let done = toggle_checkbox("  - [ ] call Sam", day()).unwrap();
+        assert_eq!(done, "  - [x] call Sam ✅ 2026-10-18");
+        assert_eq!(toggle_checkbox(&done, day()).unwrap(), "  - [ ] call Sam");
+        assert_eq!(toggle_checkbox("- [X] old", day()).unwrap(), "- [ ] old");
+    }
+
+    #[test]
+    fn list_items_become_tasks_and_other_lines_are_left_alone() {
+        assert_eq!(toggle_checkbox("1. buy milk", day()).unwrap(), "1. [ ] buy milk");
+        assert_eq!(toggle_checkbox("plain text", day()), None);
+    }
+
+    #[test]
+    fn tasks_continue_as_open_tasks() {
+        assert_eq!(on_enter(&["- [x] done"], 0, 10), EnterAction::Insert("\n- [ ] ".to_string()));
+        assert_eq!(LinePrefix::parse("- [ ]").checkbox, Some(false));
+        assert_eq!(LinePrefix::parse("- [x]text").checkbox, None);
+    }
+
+    #[test]
+    fn only_a_trailing_date_stamp_is_stripped() {
+        assert_eq!(strip_completion_date(" ship ✅ 2026-01-02"), " ship");
+        assert_eq!(strip_completion_date(" ship ✅ soon"), " ship ✅ soon");
+    }
+}
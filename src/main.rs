@@ -1,19 +1,37 @@
 mod app;
 mod autocomplete;
+mod commands;
+mod config;
 mod events;
+mod find;
+mod fuzzy;
+mod journal;
+mod links;
+mod lists;
 mod models;
 mod preview;
+mod query;
+mod quick_open;
 mod search;
+mod search_index;
+mod snippets;
 mod storage;
 mod syntax;
+mod table;
+mod tags;
+mod tasks;
+mod templates;
 mod theme;
 mod ui;
 
 use app::App;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event},
+    event::{
+        self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture, Event,
+        KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+    },
     execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    terminal::{disable_raw_mode, enable_raw_mode, supports_keyboard_enhancement, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
 use std::{
@@ -25,19 +43,24 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture, EnableBracketedPaste)?;
+    // Where the terminal can tell Ctrl+I from Tab, Ctrl+I jumps forward as in vim
+    let keyboard_enhanced = supports_keyboard_enhancement().unwrap_or(false);
+    if keyboard_enhanced {
+        execute!(stdout, PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES))?;
+    }
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
     // Create app and load data
     let mut app = App::new();
+    app.keyboard_enhanced = keyboard_enhanced;
     let storage = storage::Storage::new()?;
     
     // Load existing notebook data
     match storage.load_notebook() {
         Ok(notebook) => {
-            app.notebook = notebook;
-            app.refresh_tree_view();
+            app.load_notebook(notebook);
             app.set_message(format!("Loaded {} notes across {} folders", 
                 app.notebook.notes.len(), app.notebook.folders.len()));
         }
@@ -45,6 +68,19 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             app.set_message(format!("Failed to load notebook: {}. Starting fresh.", e));
         }
     }
+    
+    // Session state is a convenience; a missing or broken file just starts empty
+    if let Ok(session) = storage.load_session() {
+        app.restore_session(session);
+    }
+    
+    // User snippets are optional; only a broken file is worth reporting
+    if let Err(e) = app.load_snippets() {
+        app.set_message(e);
+    }
+    if let Err(e) = app.load_journal_config() {
+        app.set_message(e);
+    }
 
     // Main loop
     let tick_rate = Duration::from_millis(250);
@@ -62,18 +98,24 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         terminal.draw(|f| ui::draw(f, &mut app))?;
 
         // Handle events
-        let timeout = tick_rate
+        let mut timeout = tick_rate
             .checked_sub(last_tick.elapsed())
             .unwrap_or_else(|| Duration::from_secs(0));
+        // Wake up for a debounced search even between ticks
+        if let Some(wait) = app.pending_search_wait() {
+            timeout = timeout.min(wait);
+        }
 
         if crossterm::event::poll(timeout)? {
-            if let Event::Key(key) = event::read()? {
-                if let Err(e) = events::handle_event(&mut app, Event::Key(key)) {
-                    break Err(e.into());
+            if let event @ (Event::Key(_) | Event::Paste(_)) = event::read()? {
+                if let Err(e) = events::handle_event(&mut app, event) {
+                    break Err(e);
                 }
             }
         }
 
+        app.run_pending_search();
+
         if last_tick.elapsed() >= tick_rate {
             app.update_visual_feedback();
             last_tick = Instant::now();
@@ -88,13 +130,20 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     if let Err(e) = storage.save_notebook(&app.notebook) {
         eprintln!("Failed to save notebook data: {}", e);
     }
+    if let Err(e) = storage.save_session(&app.session_state()) {
+        eprintln!("Failed to save session state: {}", e);
+    }
 
     // Restore terminal
+    if app.keyboard_enhanced {
+        execute!(terminal.backend_mut(), PopKeyboardEnhancementFlags)?;
+    }
     disable_raw_mode()?;
     execute!(
         terminal.backend_mut(),
         LeaveAlternateScreen,
-        DisableMouseCapture
+        DisableMouseCapture,
+        DisableBracketedPaste
     )?;
     terminal.show_cursor()?;
 
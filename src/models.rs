@@ -14,6 +14,12 @@ pub struct Note {
     pub modified_at: DateTime<Utc>,
     pub tags: Vec<String>,
     pub file_path: Option<PathBuf>,
+    /// When the note was pinned; pinned notes are listed in this order
+    #[serde(default)]
+    pub pinned_at: Option<DateTime<Utc>>,
+    /// Tags that are only on the note because of an inline #tag in its content
+    #[serde(default)]
+    pub inline_tags: Vec<String>,
 }
 
 impl Note {
@@ -28,6 +34,8 @@ impl Note {
             modified_at: now,
             tags: Vec::new(),
             file_path: None,
+            pinned_at: None,
+            inline_tags: Vec::new(),
         }
     }
 
@@ -36,7 +44,11 @@ impl Note {
         self.modified_at = Utc::now();
     }
 
-    #[allow(dead_code)]
+    pub fn rename(&mut self, new_title: String) {
+        self.title = new_title;
+        self.modified_at = Utc::now();
+    }
+
     pub fn add_tag(&mut self, tag: String) {
         if !self.tags.contains(&tag) {
             self.tags.push(tag);
@@ -44,11 +56,31 @@ impl Note {
         }
     }
 
-    #[allow(dead_code)]
+    pub fn set_tags(&mut self, tags: Vec<String>) {
+        self.tags = tags;
+        self.modified_at = Utc::now();
+    }
+
     pub fn remove_tag(&mut self, tag: &str) {
         self.tags.retain(|t| t != tag);
+        self.inline_tags.retain(|t| t != tag);
         self.modified_at = Utc::now();
     }
+
+    /// Add the inline #tags found in the content to the note's tags, and drop
+    /// tags that came from #tags no longer in the text. Tags added by hand stay.
+    pub fn sync_inline_tags(&mut self, hashtags: Vec<String>) {
+        let stale: Vec<String> = self.inline_tags.iter().filter(|t| !hashtags.contains(t)).cloned().collect();
+        for tag in &stale {
+            self.remove_tag(tag);
+        }
+        for tag in hashtags {
+            if !self.tags.contains(&tag) {
+                self.inline_tags.push(tag.clone());
+                self.add_tag(tag);
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -71,7 +103,6 @@ impl Folder {
         }
     }
 
-    #[allow(dead_code)]
     pub fn rename(&mut self, new_name: String) {
         self.name = new_name;
     }
@@ -96,11 +127,35 @@ impl FolderTreeNode {
     }
 }
 
+/// A search query kept in the tree as a smart folder listing its current results
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub id: Uuid,
+    pub name: String,
+    /// The query as typed in advanced search
+    pub query: String,
+    #[serde(default)]
+    pub expanded: bool,
+}
+
+impl SavedSearch {
+    pub fn new(name: String, query: String) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            name,
+            query,
+            expanded: false,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NotebookData {
     pub folders: HashMap<Uuid, Folder>,
     pub notes: HashMap<Uuid, Note>,
     pub root_folder_ids: Vec<Uuid>,
+    #[serde(default)]
+    pub saved_searches: Vec<SavedSearch>,
 }
 
 impl NotebookData {
@@ -109,6 +164,7 @@ impl NotebookData {
             folders: HashMap::new(),
             notes: HashMap::new(),
             root_folder_ids: Vec::new(),
+            saved_searches: Vec::new(),
         }
     }
 
@@ -159,6 +215,36 @@ impl NotebookData {
             .collect()
     }
 
+    /// Pinned notes in the order they were pinned
+    pub fn pinned_notes(&self) -> Vec<&Note> {
+        let mut pinned: Vec<&Note> = self.notes.values().filter(|note| note.pinned_at.is_some()).collect();
+        pinned.sort_by_key(|note| (note.pinned_at, note.id));
+        pinned
+    }
+
+    /// Names of the folders from the root down to `folder_id`
+    pub fn folder_path(&self, folder_id: Uuid) -> Vec<String> {
+        let mut path = Vec::new();
+        let mut current = Some(folder_id);
+        
+        // Bounded walk in case of a corrupted parent cycle
+        while let Some(id) = current {
+            if path.len() > self.folders.len() {
+                break;
+            }
+            match self.folders.get(&id) {
+                Some(folder) => {
+                    path.push(folder.name.clone());
+                    current = folder.parent_id;
+                }
+                None => break,
+            }
+        }
+        
+        path.reverse();
+        path
+    }
+
     pub fn build_folder_tree(&self) -> Vec<FolderTreeNode> {
         let mut tree = Vec::new();
         
@@ -195,6 +281,7 @@ impl NotebookData {
         node
     }
 
+    #[allow(dead_code)]
     pub fn search_notes(&self, query: &str) -> Vec<&Note> {
         let query_lower = query.to_lowercase();
         self.notes.values()
@@ -207,6 +294,40 @@ impl NotebookData {
     }
 }
 
+/// A visit to a note, with where the cursor was when it was left
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NoteVisit {
+    pub note_id: Uuid,
+    pub cursor: (u16, u16),
+    pub scroll: u16,
+}
+
+impl NoteVisit {
+    pub fn new(note_id: Uuid) -> Self {
+        Self { note_id, cursor: (0, 0), scroll: 0 }
+    }
+}
+
+/// UI state kept between sessions, separate from the notebook itself
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionState {
+    /// `:` command lines, oldest first
+    #[serde(default)]
+    pub command_history: Vec<String>,
+    /// Opened notes, most recent first
+    #[serde(default)]
+    pub recent_notes: Vec<Uuid>,
+    /// Note visits, oldest first, and the position in them
+    #[serde(default)]
+    pub jump_list: Vec<NoteVisit>,
+    #[serde(default)]
+    pub jump_index: usize,
+    #[serde(default)]
+    pub pinned_collapsed: bool,
+    #[serde(default)]
+    pub saved_searches_collapsed: bool,
+}
+
 impl Default for NotebookData {
     fn default() -> Self {
         Self::new()
@@ -16,7 +16,7 @@ pub fn render_markdown_preview(content: &str) -> Text<'static> {
     let mut in_list = false;
     let mut in_emphasis = false;
     let mut in_strong = false;
-    let mut in_code = false;
+    let in_code = false;
     
     for event in parser {
         match event {
@@ -116,10 +116,8 @@ pub fn render_markdown_preview(content: &str) -> Text<'static> {
                     base_style
                 };
                 
-                if in_list {
-                    if current_line.is_empty() {
-                        current_line.push(Span::styled("• ", TokyoNightTheme::markdown_list()));
-                    }
+                if in_list && current_line.is_empty() {
+                    current_line.push(Span::styled("• ", TokyoNightTheme::markdown_list()));
                 }
                 
                 if in_heading && current_line.is_empty() {
@@ -132,7 +130,12 @@ pub fn render_markdown_preview(content: &str) -> Text<'static> {
                     current_line.push(Span::styled(prefix, style));
                 }
                 
-                current_line.push(Span::styled(text.to_string(), style));
+                let span = Span::styled(text.to_string(), style);
+                if in_code_block || in_heading {
+                    current_line.push(span);
+                } else {
+                    current_line.extend(crate::syntax::highlight_hashtags(Line::from(span)).spans);
+                }
             }
             Event::Code(text) => {
                 current_line.push(Span::styled(
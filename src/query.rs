@@ -0,0 +1,498 @@
+use crate::models::{Note, NotebookData};
+use crate::tags;
+use chrono::{DateTime, Duration, Local, NaiveDate, TimeDelta, TimeZone, Utc};
+
+/// A parsed search query: terms combined with AND, OR and NOT
+#[derive(Debug, Clone)]
+pub enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Term(Term),
+}
+
+/// A single condition on a note
+#[derive(Debug, Clone)]
+pub enum Term {
+    /// A word or quoted phrase anywhere in the title, content or tags
+    Text(String),
+    Title(String),
+    /// The tag or one nested below it
+    Tag(String),
+    /// A folder the note is in, directly or further down
+    Folder(String),
+    Date(DateField, DateRange),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DateField {
+    Created,
+    Modified,
+}
+
+/// Times from `start` up to but not including `end`; a missing bound is open
+#[derive(Debug, Clone, Copy)]
+pub struct DateRange {
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+}
+
+impl DateRange {
+    fn contains(&self, time: DateTime<Utc>) -> bool {
+        self.start.is_none_or(|start| time >= start) && self.end.is_none_or(|end| time < end)
+    }
+}
+
+impl Expr {
+    pub fn matches(&self, note: &Note, notebook: &NotebookData, case_sensitive: bool) -> bool {
+        match self {
+            Expr::And(a, b) => a.matches(note, notebook, case_sensitive) && b.matches(note, notebook, case_sensitive),
+            Expr::Or(a, b) => a.matches(note, notebook, case_sensitive) || b.matches(note, notebook, case_sensitive),
+            Expr::Not(e) => !e.matches(note, notebook, case_sensitive),
+            Expr::Term(term) => term.matches(note, notebook, case_sensitive),
+        }
+    }
+
+    /// Free-text terms a matching note contains, for highlighting; terms under NOT are left out
+    pub fn text_terms(&self) -> Vec<&str> {
+        match self {
+            Expr::And(a, b) | Expr::Or(a, b) => {
+                let mut terms = a.text_terms();
+                terms.extend(b.text_terms());
+                terms
+            }
+            Expr::Not(_) => Vec::new(),
+            Expr::Term(Term::Text(text)) => vec![text.as_str()],
+            Expr::Term(_) => Vec::new(),
+        }
+    }
+}
+
+impl Term {
+    fn matches(&self, note: &Note, notebook: &NotebookData, case_sensitive: bool) -> bool {
+        let contains = |haystack: &str, needle: &str| {
+            if case_sensitive {
+                haystack.contains(needle)
+            } else {
+                haystack.to_lowercase().contains(&needle.to_lowercase())
+            }
+        };
+        match self {
+            Term::Text(text) => {
+                contains(&note.title, text)
+                    || contains(&note.content, text)
+                    || note.tags.iter().any(|tag| contains(tag, text))
+            }
+            Term::Title(text) => contains(&note.title, text),
+            Term::Tag(tag) => tags::note_has_tag(note, tag),
+            Term::Folder(name) => note.folder_id.is_some_and(|id| folder_matches(notebook, id, name)),
+            Term::Date(DateField::Created, range) => range.contains(note.created_at),
+            Term::Date(DateField::Modified, range) => range.contains(note.modified_at),
+        }
+    }
+}
+
+/// Whether a folder or one of its ancestors has the name; with slashes,
+/// the name is a path that must end at one of them, e.g. `projects/alpha`
+fn folder_matches(notebook: &NotebookData, folder_id: uuid::Uuid, name: &str) -> bool {
+    let wanted: Vec<String> = name.split('/').map(|s| s.trim().to_lowercase()).filter(|s| !s.is_empty()).collect();
+    let path: Vec<String> = notebook.folder_path(folder_id).iter().map(|s| s.to_lowercase()).collect();
+    !wanted.is_empty() && (wanted.len()..=path.len()).any(|end| path[..end].ends_with(&wanted))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Phrase(String),
+    Field(String, String),
+    Open,
+    Close,
+    And,
+    Or,
+    Not,
+}
+
+const FIELDS: [&str; 5] = ["tag", "folder", "title", "created", "modified"];
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    // A quoted string starting at `start`, returning it and the index after the closing quote
+    let quoted = |start: usize| -> Result<(String, usize), String> {
+        let len = chars[start + 1..]
+            .iter()
+            .position(|&c| c == '"')
+            .ok_or_else(|| "Missing closing quote \"".to_string())?;
+        Ok((chars[start + 1..start + 1 + len].iter().collect(), start + len + 2))
+    };
+
+    while i < chars.len() {
+        match chars[i] {
+            c if c.is_whitespace() => i += 1,
+            '(' => {
+                tokens.push(Token::Open);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::Close);
+                i += 1;
+            }
+            '"' => {
+                let (phrase, next) = quoted(i)?;
+                tokens.push(Token::Phrase(phrase));
+                i = next;
+            }
+            _ => {
+                let start = i;
+                while i < chars.len() && !chars[i].is_whitespace() && !matches!(chars[i], '(' | ')' | '"') {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+
+                let field = word
+                    .split_once(':')
+                    .filter(|(name, _)| FIELDS.contains(&name.to_lowercase().as_str()));
+                if let Some((name, value)) = field {
+                    let name = name.to_lowercase();
+                    let value = if value.is_empty() && chars.get(i) == Some(&'"') {
+                        let (value, next) = quoted(i)?;
+                        i = next;
+                        value
+                    } else {
+                        value.to_string()
+                    };
+                    if value.trim().is_empty() {
+                        return Err(format!("'{}:' needs a value, e.g. {}", name, field_example(&name)));
+                    }
+                    tokens.push(Token::Field(name, value));
+                    continue;
+                }
+
+                tokens.push(match word.as_str() {
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    "NOT" => Token::Not,
+                    _ => Token::Word(word),
+                });
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+fn field_example(field: &str) -> &'static str {
+    match field {
+        "tag" => "tag:todo",
+        "folder" => "folder:projects",
+        "title" => "title:meeting",
+        "created" => "created:>2026-01-01",
+        _ => "modified:<7d",
+    }
+}
+
+/// Parse a query such as `tag:todo AND (meeting OR "design review") NOT folder:archive`.
+/// Adjacent terms are joined with AND; AND binds tighter than OR.
+pub fn parse(input: &str) -> Result<Expr, String> {
+    let tokens = tokenize(input)?;
+    if tokens.is_empty() {
+        return Err("Empty query".to_string());
+    }
+    let mut parser = Parser { tokens, pos: 0, now: Local::now() };
+    let expr = parser.or_expr()?;
+    match parser.tokens.get(parser.pos) {
+        None => Ok(expr),
+        Some(Token::Close) => Err("Unexpected ')' without a matching '('".to_string()),
+        Some(token) => Err(format!("Unexpected {}", describe(token))),
+    }
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Word(word) => format!("'{}'", word),
+        Token::Phrase(phrase) => format!("\"{}\"", phrase),
+        Token::Field(name, value) => format!("'{}:{}'", name, value),
+        Token::Open => "'('".to_string(),
+        Token::Close => "')'".to_string(),
+        Token::And => "AND".to_string(),
+        Token::Or => "OR".to_string(),
+        Token::Not => "NOT".to_string(),
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    now: DateTime<Local>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn or_expr(&mut self) -> Result<Expr, String> {
+        let mut expr = self.and_expr("")?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            let right = self.and_expr("OR")?;
+            expr = Expr::Or(Box::new(expr), Box::new(right));
+        }
+        Ok(expr)
+    }
+
+    /// Terms joined by AND, written or implied; `after` names what came before, for errors
+    fn and_expr(&mut self, after: &str) -> Result<Expr, String> {
+        let mut expr = self.unary(after)?;
+        loop {
+            match self.peek() {
+                Some(Token::And) => {
+                    self.pos += 1;
+                    let right = self.unary("AND")?;
+                    expr = Expr::And(Box::new(expr), Box::new(right));
+                }
+                Some(Token::Or | Token::Close) | None => return Ok(expr),
+                Some(_) => {
+                    let right = self.unary("")?;
+                    expr = Expr::And(Box::new(expr), Box::new(right));
+                }
+            }
+        }
+    }
+
+    fn unary(&mut self, after: &str) -> Result<Expr, String> {
+        let Some(token) = self.tokens.get(self.pos).cloned() else {
+            return Err(if after.is_empty() {
+                "Expected a search term at the end of the query".to_string()
+            } else {
+                format!("Expected a search term after {}", after)
+            });
+        };
+        self.pos += 1;
+        match token {
+            Token::Not => Ok(Expr::Not(Box::new(self.unary("NOT")?))),
+            Token::Open => {
+                if self.peek() == Some(&Token::Close) {
+                    return Err("Empty parentheses".to_string());
+                }
+                let expr = self.or_expr()?;
+                if self.peek() != Some(&Token::Close) {
+                    return Err("Missing ')' to close '('".to_string());
+                }
+                self.pos += 1;
+                Ok(expr)
+            }
+            Token::Word(word) | Token::Phrase(word) => Ok(Expr::Term(Term::Text(word))),
+            Token::Field(name, value) => Ok(Expr::Term(self.field(&name, &value)?)),
+            Token::Close if after.is_empty() => Err("Unexpected ')' without a matching '('".to_string()),
+            token => {
+                let context = if after.is_empty() { String::new() } else { format!(" after {}", after) };
+                Err(format!("Expected a search term{} but found {}", context, describe(&token)))
+            }
+        }
+    }
+
+    fn field(&self, name: &str, value: &str) -> Result<Term, String> {
+        match name {
+            "tag" => tags::normalize_tag(value)
+                .map(Term::Tag)
+                .ok_or_else(|| format!("'{}' is not a valid tag", value)),
+            "folder" => Ok(Term::Folder(value.to_string())),
+            "title" => Ok(Term::Title(value.to_string())),
+            "created" => Ok(Term::Date(DateField::Created, self.date_range(name, value)?)),
+            _ => Ok(Term::Date(DateField::Modified, self.date_range(name, value)?)),
+        }
+    }
+
+    /// Read `>2026-01-01`, `<=yesterday`, `<7d` and the like. Dates compare by day;
+    /// ages such as `7d` or `2w` count back from now, so `<7d` means less than 7 days old.
+    fn date_range(&self, name: &str, value: &str) -> Result<DateRange, String> {
+        let (op, when) = ["<=", ">=", "<", ">", "="]
+            .iter()
+            .find_map(|op| value.strip_prefix(op).map(|rest| (*op, rest)))
+            .unwrap_or(("=", value));
+        let invalid = || {
+            format!(
+                "Invalid date '{}' in {}: use YYYY-MM-DD, today, yesterday or an age like 7d, 2w, 3m",
+                when, name
+            )
+        };
+
+        if looks_like_age(when) {
+            // Ages too large for a date are rejected rather than wrapping around
+            let cutoff = parse_age(when)
+                .and_then(|age| self.now.checked_sub_signed(age))
+                .ok_or_else(invalid)?;
+            return Ok(match op {
+                "<" | "<=" => DateRange { start: Some(cutoff.with_timezone(&Utc)), end: None },
+                ">" | ">=" => DateRange { start: None, end: Some(cutoff.with_timezone(&Utc)) },
+                _ => self.day_range(op, cutoff.date_naive()),
+            });
+        }
+
+        let today = self.now.date_naive();
+        let date = match when.to_lowercase().as_str() {
+            "today" => today,
+            "yesterday" => today - Duration::days(1),
+            _ => NaiveDate::parse_from_str(when, "%Y-%m-%d").map_err(|_| invalid())?,
+        };
+        Ok(self.day_range(op, date))
+    }
+
+    fn day_range(&self, op: &str, date: NaiveDate) -> DateRange {
+        let start = local_midnight(date);
+        let end = date.succ_opt().and_then(local_midnight);
+        match op {
+            ">" => DateRange { start: end, end: None },
+            ">=" => DateRange { start, end: None },
+            "<" => DateRange { start: None, end: start },
+            "<=" => DateRange { start: None, end },
+            _ => DateRange { start, end },
+        }
+    }
+}
+
+/// Whether the text has the shape of an age: digits followed by a unit letter
+fn looks_like_age(text: &str) -> bool {
+    let Some(unit) = text.chars().last() else {
+        return false;
+    };
+    let count = &text[..text.len() - unit.len_utf8()];
+    matches!(unit.to_ascii_lowercase(), 'h' | 'd' | 'w' | 'm')
+        && !count.is_empty()
+        && count.chars().all(|c| c.is_ascii_digit())
+}
+
+/// An age such as `7d`, `12h`, `2w` or `3m` (months of 30 days); None when it is too large
+fn parse_age(text: &str) -> Option<TimeDelta> {
+    let unit = text.chars().last()?;
+    let count: i64 = text[..text.len() - unit.len_utf8()].parse().ok()?;
+    match unit.to_ascii_lowercase() {
+        'h' => TimeDelta::try_hours(count),
+        'd' => TimeDelta::try_days(count),
+        'w' => TimeDelta::try_weeks(count),
+        'm' => TimeDelta::try_days(count.checked_mul(30)?),
+        _ => None,
+    }
+}
+
+fn local_midnight(date: NaiveDate) -> Option<DateTime<Utc>> {
+    let midnight = date.and_hms_opt(0, 0, 0)?;
+    Local.from_local_datetime(&midnight).earliest().map(|time| time.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Folder;
+
+    fn note(title: &str, content: &str, tags: &[&str]) -> Note {
+        let mut note = Note::new(title.to_string(), None);
+        note.content = content.to_string();
+        note.tags = tags.iter().map(|tag| tag.to_string()).collect();
+        note
+    }
+
+    fn matches(query: &str, note: &Note) -> bool {
+        parse(query).unwrap().matches(note, &NotebookData::new(), false)
+    }
+
+    #[test]
+    fn adjacent_terms_are_joined_with_and() {
+        let note = note("Weekly meeting", "Agenda for the design review", &[]);
+        assert!(matches("meeting agenda", &note));
+        assert!(!matches("meeting budget", &note));
+        assert!(matches("meeting budget OR meeting agenda", &note));
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let note = note("Groceries", "milk", &[]);
+        assert!(matches("budget AND plan OR milk", &note));
+        assert!(!matches("budget AND (plan OR milk)", &note));
+    }
+
+    #[test]
+    fn phrases_and_not() {
+        let note = note("Review", "notes from the design review", &[]);
+        assert!(matches("\"design review\"", &note));
+        assert!(!matches("\"review design\"", &note));
+        assert!(matches("review NOT draft", &note));
+        assert!(!matches("NOT review", &note));
+    }
+
+    #[test]
+    fn text_matches_substrings() {
+        let note = note("Standup", "the meeting ran long", &[]);
+        assert!(matches("eeting", &note));
+        assert!(!parse("Meeting").unwrap().matches(&note, &NotebookData::new(), true));
+    }
+
+    #[test]
+    fn tags_match_nested_tags() {
+        let note = note("Plan", "", &["project/alpha"]);
+        assert!(matches("tag:project", &note));
+        assert!(matches("tag:project/alpha", &note));
+        assert!(!matches("tag:alpha", &note));
+    }
+
+    #[test]
+    fn folders_match_ancestors_and_paths() {
+        let mut notebook = NotebookData::new();
+        let projects = Folder::new("Projects".to_string(), None);
+        let alpha = Folder::new("Alpha".to_string(), Some(projects.id));
+        let alpha_id = alpha.id;
+        notebook.add_folder(projects);
+        notebook.add_folder(alpha);
+        let note = Note::new("Plan".to_string(), Some(alpha_id));
+        let check = |query: &str| parse(query).unwrap().matches(&note, &notebook, false);
+        assert!(check("folder:projects"));
+        assert!(check("folder:projects/alpha"));
+        assert!(!check("folder:alpha/projects"));
+    }
+
+    #[test]
+    fn ages_count_back_from_now() {
+        let mut note = note("Old", "", &[]);
+        note.modified_at = Utc::now() - Duration::days(10);
+        assert!(matches("modified:<2w", &note));
+        assert!(!matches("modified:<7d", &note));
+        assert!(matches("modified:>7d", &note));
+    }
+
+    #[test]
+    fn dates_compare_by_day() {
+        let mut note = note("Dated", "", &[]);
+        note.created_at = local_midnight(NaiveDate::from_ymd_opt(2026, 3, 15).unwrap()).unwrap() + Duration::hours(13);
+        assert!(matches("created:2026-03-15", &note));
+        assert!(matches("created:>=2026-03-15", &note));
+        assert!(!matches("created:>2026-03-15", &note));
+        assert!(matches("created:<=2026-03-15", &note));
+        assert!(!matches("created:<2026-03-15", &note));
+    }
+
+    #[test]
+    fn ages_too_large_for_a_date_are_rejected() {
+        for query in ["modified:<1000000000d", "created:>99999999999m", "modified:<99999999999999999999h"] {
+            let error = parse(query).unwrap_err();
+            assert!(error.starts_with("Invalid date"), "{}: {}", query, error);
+            assert!(!error.contains(": -"), "{}", error);
+        }
+    }
+
+    #[test]
+    fn malformed_queries_are_errors() {
+        assert_eq!(parse("").unwrap_err(), "Empty query");
+        assert_eq!(parse("(a OR b").unwrap_err(), "Missing ')' to close '('");
+        assert_eq!(parse("a)").unwrap_err(), "Unexpected ')' without a matching '('");
+        assert_eq!(parse("a AND").unwrap_err(), "Expected a search term after AND");
+        assert!(parse("created:soon").unwrap_err().starts_with("Invalid date 'soon' in created"));
+    }
+
+    #[test]
+    fn text_terms_leave_out_negated_terms() {
+        let expr = parse("alpha (beta OR gamma) NOT delta tag:x").unwrap();
+        assert_eq!(expr.text_terms(), vec!["alpha", "beta", "gamma"]);
+    }
+}
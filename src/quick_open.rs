@@ -0,0 +1,155 @@
+use crate::fuzzy;
+use crate::models::NotebookData;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// Matches on the folder path count for less than matches on the title
+const PATH_MATCH_PENALTY: i64 = 24;
+
+/// Bonus for the most recently opened note, shrinking with each older one
+const RECENT_BONUS: i64 = 40;
+const RECENT_BONUS_STEP: i64 = 4;
+
+/// A note offered by the quick-open finder
+#[derive(Debug, Clone)]
+pub struct QuickOpenResult {
+    pub note_id: Uuid,
+    pub title: String,
+    /// Folder names from the root down, joined with " / "; empty for root notes
+    pub breadcrumb: String,
+    /// Char indices of the title and of the breadcrumb that matched the query
+    pub title_positions: Vec<usize>,
+    pub breadcrumb_positions: Vec<usize>,
+    pub score: i64,
+    pub modified_at: DateTime<Utc>,
+}
+
+/// Notes whose title or folder path fuzzy matches the query, best first.
+/// `recent` lists recently opened notes, newest first; they rank higher, and
+/// the most recently modified note wins among equal scores.
+pub fn find_notes(notebook: &NotebookData, query: &str, recent: &[Uuid], limit: usize) -> Vec<QuickOpenResult> {
+    let mut results: Vec<QuickOpenResult> = notebook
+        .notes
+        .values()
+        .filter_map(|note| {
+            let breadcrumb = note
+                .folder_id
+                .map(|id| notebook.folder_path(id).join(" / "))
+                .unwrap_or_default();
+
+            let title_match = fuzzy::fuzzy_match(query, &note.title);
+            // The title joins the path so a query can run from folder into title
+            let path_match = if breadcrumb.is_empty() {
+                None
+            } else {
+                let full_path = format!("{} / {}", breadcrumb, note.title);
+                fuzzy::fuzzy_match(query, &full_path)
+            };
+
+            let breadcrumb_len = breadcrumb.chars().count();
+            let (score, title_positions, breadcrumb_positions) = match (title_match, path_match) {
+                (Some(t), Some(p)) if p.score - PATH_MATCH_PENALTY > t.score => split_path_match(p, breadcrumb_len),
+                (Some(t), _) => (t.score, t.positions, Vec::new()),
+                (None, Some(p)) => split_path_match(p, breadcrumb_len),
+                (None, None) => return None,
+            };
+            let recency = recent
+                .iter()
+                .position(|&id| id == note.id)
+                .map_or(0, |i| (RECENT_BONUS - RECENT_BONUS_STEP * i as i64).max(0));
+
+            Some(QuickOpenResult {
+                note_id: note.id,
+                title: note.title.clone(),
+                breadcrumb,
+                title_positions,
+                breadcrumb_positions,
+                score: score + recency,
+                modified_at: note.modified_at,
+            })
+        })
+        .collect();
+
+    results.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| b.modified_at.cmp(&a.modified_at))
+            .then_with(|| a.title.to_lowercase().cmp(&b.title.to_lowercase()))
+    });
+    results.truncate(limit);
+    results
+}
+
+/// Split a match on "breadcrumb / title" into breadcrumb and title positions
+fn split_path_match(path_match: fuzzy::FuzzyMatch, breadcrumb_len: usize) -> (i64, Vec<usize>, Vec<usize>) {
+    let title_start = breadcrumb_len + " / ".len();
+    let (in_title, in_breadcrumb): (Vec<usize>, Vec<usize>) =
+        path_match.positions.into_iter().partition(|&p| p >= title_start);
+    (
+        path_match.score - PATH_MATCH_PENALTY,
+        in_title.into_iter().map(|p| p - title_start).collect(),
+        in_breadcrumb.into_iter().filter(|&p| p < breadcrumb_len).collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Folder, Note};
+
+    fn notebook(notes: &[(&str, Option<&str>)]) -> (NotebookData, Vec<Uuid>) {
+        let mut notebook = NotebookData::new();
+        let mut ids = Vec::new();
+        for (title, folder) in notes {
+            let folder_id = folder.map(|name| {
+                let existing = notebook.folders.values().find(|f| f.name == name).map(|f| f.id);
+                existing.unwrap_or_else(|| {
+                    let folder = Folder::new(name.to_string(), None);
+                    let id = folder.id;
+                    notebook.add_folder(folder);
+                    id
+                })
+            });
+            let note = Note::new(title.to_string(), folder_id);
+            ids.push(note.id);
+            notebook.add_note(note);
+        }
+        (notebook, ids)
+    }
+
+    fn titles(results: &[QuickOpenResult]) -> Vec<&str> {
+        results.iter().map(|r| r.title.as_str()).collect()
+    }
+
+    #[test]
+    fn better_title_matches_rank_first() {
+        let (notebook, _) = notebook(&[("Meeting notes", None), ("Monthly metrics", None), ("Recipes", None)]);
+        let results = find_notes(&notebook, "meet", &[], 10);
+        assert_eq!(titles(&results), vec!["Meeting notes"]);
+        assert_eq!(results[0].title_positions, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn folder_paths_match_with_a_breadcrumb() {
+        let (notebook, _) = notebook(&[("Roadmap", Some("Work")), ("Workout log", None)]);
+        let results = find_notes(&notebook, "work road", &[], 10);
+        assert_eq!(titles(&results), vec!["Roadmap"]);
+        assert_eq!(results[0].breadcrumb, "Work");
+        assert_eq!(results[0].breadcrumb_positions, vec![0, 1, 2, 3]);
+        assert_eq!(results[0].title_positions, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn recently_opened_notes_rank_higher() {
+        let (notebook, ids) = notebook(&[("Plan A", None), ("Plan B", None), ("Plan C", None)]);
+        assert_eq!(titles(&find_notes(&notebook, "plan", &[ids[2], ids[1]], 10)), vec!["Plan C", "Plan B", "Plan A"]);
+        assert_eq!(titles(&find_notes(&notebook, "", &[ids[1]], 1)), vec!["Plan B"]);
+    }
+
+    #[test]
+    fn recency_does_not_outweigh_a_much_better_match() {
+        let (notebook, ids) = notebook(&[("Journal", None), ("Project journey notes", None)]);
+        let results = find_notes(&notebook, "journal", &[ids[1]], 10);
+        assert_eq!(titles(&results), vec!["Journal"]);
+    }
+}
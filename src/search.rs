@@ -1,6 +1,8 @@
 use crate::models::{Note, NotebookData};
+use crate::query::{self, Expr};
+use crate::search_index::{self, SearchIndex};
 use regex::Regex;
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
 use uuid::Uuid;
 
 #[derive(Debug, Clone)]
@@ -9,6 +11,10 @@ pub struct SearchQuery {
     pub is_regex: bool,
     pub folder_id: Option<Uuid>, // None = search all, Some = search in specific folder
     pub case_sensitive: bool,
+    /// Structured query; when set, notes must satisfy it and `text` is only kept for history
+    pub expr: Option<Expr>,
+    /// Also match words a few typos away from the query words
+    pub fuzzy: bool,
 }
 
 impl SearchQuery {
@@ -18,14 +24,53 @@ impl SearchQuery {
             is_regex: false,
             folder_id: None,
             case_sensitive: false,
+            expr: None,
+            fuzzy: false,
         }
     }
     
+    /// Read a query as typed in the quick search: words, allowing typos after `fuzzy:`
+    pub fn words(input: &str) -> Self {
+        match input.strip_prefix("fuzzy:") {
+            Some(words) => SearchQuery::new(words.trim().to_string()).fuzzy(),
+            None => SearchQuery::new(input.to_string()),
+        }
+    }
+    
+    /// Read a query as typed in advanced search. `regex:` searches for the rest as a
+    /// regex and `fuzzy:` for its words allowing typos; anything else is parsed with the
+    /// query language, case-sensitively after `case:`.
+    pub fn parse(input: &str) -> Result<Self, String> {
+        if let Some(pattern) = input.strip_prefix("regex:") {
+            let pattern = pattern.trim();
+            Regex::new(pattern).map_err(|e| format!("Invalid regex: {}", e))?;
+            return Ok(SearchQuery::new(pattern.to_string()).with_regex());
+        }
+        if let Some(words) = input.strip_prefix("fuzzy:") {
+            if words.trim().is_empty() {
+                return Err("Enter the words to search for after fuzzy:".to_string());
+            }
+            return Ok(SearchQuery::new(words.trim().to_string()).fuzzy());
+        }
+        
+        let mut query = SearchQuery::new(input.trim().to_string());
+        let query_text = match input.strip_prefix("case:") {
+            Some(rest) => {
+                query = query.case_sensitive();
+                rest
+            }
+            None => input,
+        };
+        query.expr = Some(query::parse(query_text)?);
+        Ok(query)
+    }
+    
     pub fn with_regex(mut self) -> Self {
         self.is_regex = true;
         self
     }
     
+    #[allow(dead_code)]
     pub fn in_folder(mut self, folder_id: Option<Uuid>) -> Self {
         self.folder_id = folder_id;
         self
@@ -35,6 +80,11 @@ impl SearchQuery {
         self.case_sensitive = true;
         self
     }
+    
+    pub fn fuzzy(mut self) -> Self {
+        self.fuzzy = true;
+        self
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -91,6 +141,7 @@ impl SearchHistory {
         self.queries.iter().collect()
     }
     
+    #[allow(dead_code)]
     pub fn clear(&mut self) {
         self.queries.clear();
     }
@@ -98,20 +149,70 @@ impl SearchHistory {
 
 pub struct EnhancedSearch {
     history: SearchHistory,
+    index: SearchIndex,
 }
 
 impl EnhancedSearch {
     pub fn new() -> Self {
         Self {
             history: SearchHistory::new(50),
+            index: SearchIndex::default(),
         }
     }
     
+    /// Index every note of a newly loaded notebook
+    pub fn rebuild_index(&mut self, notebook: &NotebookData) {
+        self.index = SearchIndex::build(notebook);
+    }
+    
+    /// Re-index a note after its title, tags, text or folder changed
+    pub fn index_note(&mut self, note: &Note) {
+        self.index.update_note(note);
+    }
+    
+    pub fn unindex_note(&mut self, note_id: Uuid) {
+        self.index.remove_note(note_id);
+    }
+    
     pub fn search(&mut self, notebook: &NotebookData, query: SearchQuery) -> Result<Vec<SearchResult>, String> {
         // Add to history if not empty
         if !query.text.trim().is_empty() {
             self.history.add(query.text.clone());
         }
+        self.find(notebook, &query)
+    }
+    
+    /// Search without recording the query in the history, as for live saved searches
+    pub fn find(&self, notebook: &NotebookData, query: &SearchQuery) -> Result<Vec<SearchResult>, String> {
+        // An empty text would match everywhere at once
+        if query.expr.is_none() && !query.is_regex && query.text.is_empty() {
+            return Ok(Vec::new());
+        }
+        
+        // Plain words are looked up in the index; regexes and structured queries scan the notes
+        let substring_query;
+        let query = if query.expr.is_none() && !query.is_regex && !query.case_sensitive {
+            let text = query.text.trim();
+            let quoted = text.len() >= 2 && text.starts_with('"') && text.ends_with('"');
+            let text = if quoted { &text[1..text.len() - 1] } else { text };
+            let terms = search_index::tokenize(text);
+            // Several words, or quoted ones, must appear together as written
+            let phrase = (!query.fuzzy && (quoted || terms.len() > 1)).then_some(text);
+            if !terms.is_empty() {
+                let results = self.find_indexed(notebook, query, &terms, phrase)?;
+                if !results.is_empty() {
+                    return Ok(results);
+                }
+            }
+            if text.is_empty() {
+                return Ok(Vec::new());
+            }
+            // The index only finds words by their start; scan for the text inside words too
+            substring_query = SearchQuery { text: text.to_string(), ..query.clone() };
+            &substring_query
+        } else {
+            query
+        };
         
         let notes_to_search: Vec<&Note> = if let Some(folder_id) = query.folder_id {
             // Search only in specific folder
@@ -126,17 +227,24 @@ impl EnhancedSearch {
         let mut results = Vec::new();
         
         for note in notes_to_search {
-            if let Some(search_result) = self.search_note(note, &query)? {
+            if let Some(search_result) = self.search_note(notebook, note, query)? {
                 results.push(search_result);
             }
         }
         
-        // Sort results by relevance (title matches first, then by number of matches)
+        // Sort by the relevance of the free-text terms, then title matches first, then by number of matches
+        let terms: Vec<String> = query.expr
+            .as_ref()
+            .map(|expr| expr.text_terms().into_iter().flat_map(search_index::tokenize).collect())
+            .unwrap_or_default();
+        let scores = self.index.scores(&terms);
+        let score = |result: &SearchResult| scores.get(&result.note.id).copied().unwrap_or_default();
         results.sort_by(|a, b| {
             let a_title_matches = a.matches.iter().filter(|m| m.match_type == MatchType::Title).count();
             let b_title_matches = b.matches.iter().filter(|m| m.match_type == MatchType::Title).count();
             
-            b_title_matches.cmp(&a_title_matches)
+            score(b).total_cmp(&score(a))
+                .then(b_title_matches.cmp(&a_title_matches))
                 .then(b.matches.len().cmp(&a.matches.len()))
                 .then(a.note.title.cmp(&b.note.title))
         });
@@ -144,7 +252,57 @@ impl EnhancedSearch {
         Ok(results)
     }
     
-    fn search_note(&self, note: &Note, query: &SearchQuery) -> Result<Option<SearchResult>, String> {
+    /// Notes with words starting with every term, ranked by BM25 with the title counting most.
+    /// Notes that only match fuzzy queries with typos follow all the exact matches.
+    /// With a phrase, only the notes containing it are kept, and its occurrences are the matches.
+    fn find_indexed(&self, notebook: &NotebookData, query: &SearchQuery, terms: &[String], phrase: Option<&str>) -> Result<Vec<SearchResult>, String> {
+        let mut found = self.index.search(terms, false);
+        if query.fuzzy {
+            let exact: HashSet<Uuid> = found.iter().map(|(id, _)| *id).collect();
+            found.extend(self.index.search(terms, true).into_iter().filter(|(id, _)| !exact.contains(id)));
+        }
+        
+        let phrase_query = phrase.map(|phrase| SearchQuery::new(phrase.to_string()));
+        let mut results = Vec::new();
+        for (id, _) in found {
+            let Some(note) = notebook.notes.get(&id) else {
+                continue;
+            };
+            if query.folder_id.is_some() && note.folder_id != query.folder_id {
+                continue;
+            }
+            let matches = match phrase_query {
+                Some(ref phrase_query) => self.note_matches(note, phrase_query)?,
+                None => word_matches(note, terms, query.fuzzy),
+            };
+            if !matches.is_empty() {
+                results.push(SearchResult { note: note.clone(), matches });
+            }
+        }
+        Ok(results)
+    }
+    
+    fn search_note(&self, notebook: &NotebookData, note: &Note, query: &SearchQuery) -> Result<Option<SearchResult>, String> {
+        let Some(ref expr) = query.expr else {
+            let matches = self.note_matches(note, query)?;
+            return Ok((!matches.is_empty()).then(|| SearchResult { note: note.clone(), matches }));
+        };
+        
+        // Structured queries decide which notes match; their free-text terms are highlighted
+        if !expr.matches(note, notebook, query.case_sensitive) {
+            return Ok(None);
+        }
+        let mut matches = Vec::new();
+        for term in expr.text_terms() {
+            let mut term_query = SearchQuery::new(term.to_string());
+            term_query.case_sensitive = query.case_sensitive;
+            matches.extend(self.note_matches(note, &term_query)?);
+        }
+        Ok(Some(SearchResult { note: note.clone(), matches }))
+    }
+    
+    /// Occurrences of a plain or regex query in the note's title, content and tags
+    fn note_matches(&self, note: &Note, query: &SearchQuery) -> Result<Vec<SearchMatch>, String> {
         let mut matches = Vec::new();
         
         // Search in title
@@ -166,14 +324,7 @@ impl EnhancedSearch {
             }
         }
         
-        if matches.is_empty() {
-            Ok(None)
-        } else {
-            Ok(Some(SearchResult {
-                note: note.clone(),
-                matches,
-            }))
-        }
+        Ok(matches)
     }
     
     fn find_matches(&self, text: &str, query: &SearchQuery, match_type: MatchType) -> Result<Option<Vec<SearchMatch>>, String> {
@@ -219,11 +370,7 @@ impl EnhancedSearch {
     }
     
     fn find_text_matches(&self, text: &str, query: &SearchQuery, match_type: MatchType, line_num: usize) -> Result<Option<Vec<SearchMatch>>, String> {
-        let (search_text, search_query) = if query.case_sensitive {
-            (text, query.text.as_str())
-        } else {
-            (text, query.text.as_str())
-        };
+        let (search_text, search_query) = (text, query.text.as_str());
         
         let mut matches = Vec::new();
         let mut start_pos = 0;
@@ -261,6 +408,7 @@ impl EnhancedSearch {
         self.history.get_history()
     }
     
+    #[allow(dead_code)]
     pub fn clear_history(&mut self) {
         self.history.clear();
     }
@@ -330,3 +478,102 @@ impl Default for EnhancedSearch {
         Self::new()
     }
 }
+
+/// Words in the note's title, text and tags that start with one of the terms, or are
+/// within a few typos of one in a fuzzy search
+fn word_matches(note: &Note, terms: &[String], fuzzy: bool) -> Vec<SearchMatch> {
+    let find = |text: &str, match_type: MatchType, line_number: usize| {
+        search_index::word_spans(text)
+            .into_iter()
+            .filter(|&(start, end)| {
+                let word = text[start..end].to_lowercase();
+                terms.iter().any(|term| search_index::word_matches_term(&word, term, fuzzy))
+            })
+            .map(|(start, end)| SearchMatch {
+                line_number,
+                line_text: text.to_string(),
+                start_offset: start,
+                end_offset: end,
+                match_type: match_type.clone(),
+            })
+            .collect::<Vec<_>>()
+    };
+    
+    let mut matches = find(&note.title, MatchType::Title, 0);
+    for (line_number, line) in note.content.lines().enumerate() {
+        matches.extend(find(line, MatchType::Content, line_number));
+    }
+    for tag in &note.tags {
+        matches.extend(find(tag, MatchType::Tag, 0));
+    }
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notebook(notes: &[(&str, &str)]) -> (NotebookData, EnhancedSearch) {
+        let mut notebook = NotebookData::new();
+        for (title, content) in notes {
+            let mut note = Note::new(title.to_string(), None);
+            note.content = content.to_string();
+            notebook.add_note(note);
+        }
+        let mut search = EnhancedSearch::new();
+        search.rebuild_index(&notebook);
+        (notebook, search)
+    }
+
+    fn titles(search: &EnhancedSearch, notebook: &NotebookData, query: SearchQuery) -> Vec<String> {
+        search.find(notebook, &query).unwrap().into_iter().map(|r| r.note.title).collect()
+    }
+
+    #[test]
+    fn words_are_found_by_prefix_and_ranked_by_title() {
+        let (notebook, search) = notebook(&[("Groceries", "buy milk for the meeting"), ("Meeting notes", "agenda")]);
+        assert_eq!(titles(&search, &notebook, SearchQuery::new("meet".to_string())), vec!["Meeting notes", "Groceries"]);
+    }
+
+    #[test]
+    fn text_inside_words_falls_back_to_a_scan() {
+        let (notebook, search) = notebook(&[("Standup", "the meeting ran long"), ("Lunch", "sandwich")]);
+        let results = search.find(&notebook, &SearchQuery::new("ting".to_string())).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].note.title, "Standup");
+        let found = &results[0].matches[0];
+        assert_eq!(&found.line_text[found.start_offset..found.end_offset], "ting");
+    }
+
+    #[test]
+    fn several_words_match_as_a_phrase() {
+        let (notebook, search) = notebook(&[("Apart", "foo then bar"), ("Together", "say foo bar twice: foo bar")]);
+        let results = search.find(&notebook, &SearchQuery::new("foo bar".to_string())).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].note.title, "Together");
+        assert_eq!(results[0].matches.len(), 2);
+        assert_eq!(titles(&search, &notebook, SearchQuery::new("\"bar twice\"".to_string())), vec!["Together"]);
+    }
+
+    #[test]
+    fn empty_queries_find_nothing() {
+        let (notebook, search) = notebook(&[("Note", "text")]);
+        assert!(titles(&search, &notebook, SearchQuery::new(String::new())).is_empty());
+        assert!(titles(&search, &notebook, SearchQuery::new("\"\"".to_string())).is_empty());
+    }
+
+    #[test]
+    fn structured_queries_scan_the_notes() {
+        let (notebook, search) = notebook(&[("Plan", "alpha beta"), ("Other", "alpha")]);
+        let query = SearchQuery::parse("alpha NOT beta").unwrap();
+        assert_eq!(titles(&search, &notebook, query), vec!["Other"]);
+    }
+
+    #[test]
+    fn fuzzy_queries_tolerate_typos() {
+        let (notebook, search) = notebook(&[("Meeting notes", "agenda")]);
+        assert!(titles(&search, &notebook, SearchQuery::new("meetnig".to_string())).is_empty());
+        assert_eq!(titles(&search, &notebook, SearchQuery::parse("fuzzy:meetnig").unwrap()), vec!["Meeting notes"]);
+        assert!(SearchQuery::parse("fuzzy:  ").is_err());
+    }
+}
@@ -0,0 +1,325 @@
+use crate::fuzzy;
+use crate::models::{Note, NotebookData};
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
+
+/// BM25 term frequency saturation
+const K1: f64 = 1.2;
+/// BM25 length normalization
+const B: f64 = 0.75;
+/// How much a term counts in each field relative to the note text
+const TITLE_BOOST: f64 = 5.0;
+const TAGS_BOOST: f64 = 3.0;
+const CONTENT_BOOST: f64 = 1.0;
+/// Score of a word that only starts with the query term, relative to an exact match
+const PREFIX_WEIGHT: f64 = 0.7;
+/// Score of a word one typo away from the query term, halved for each further typo
+const TYPO_WEIGHT: f64 = 0.4;
+
+/// A count for each indexed field of a note
+#[derive(Debug, Clone, Copy, Default)]
+struct FieldCounts {
+    title: usize,
+    tags: usize,
+    content: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+struct IndexedNote {
+    lengths: FieldCounts,
+    /// Distinct terms of the note, to take it out of their postings again
+    terms: Vec<String>,
+}
+
+/// Inverted index over note titles, tags and text, kept up to date one note at a time
+#[derive(Debug, Clone, Default)]
+pub struct SearchIndex {
+    /// Term to the notes containing it, with how often it occurs in each field
+    postings: BTreeMap<String, HashMap<Uuid, FieldCounts>>,
+    notes: HashMap<Uuid, IndexedNote>,
+    /// Field lengths summed over all notes, for the average lengths
+    total_lengths: FieldCounts,
+}
+
+/// Byte ranges of the words in a text: runs of letters and digits
+pub fn word_spans(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        match (c.is_alphanumeric(), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                spans.push((s, i));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        spans.push((s, text.len()));
+    }
+    spans
+}
+
+/// Lowercased words of a text, as they are indexed
+pub fn tokenize(text: &str) -> Vec<String> {
+    word_spans(text)
+        .into_iter()
+        .map(|(start, end)| text[start..end].to_lowercase())
+        .collect()
+}
+
+/// Typos tolerated in a fuzzy search term: none in short words, where almost anything is one edit away
+fn max_typos(term: &str) -> usize {
+    match term.chars().count() {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+/// How well an indexed word matches a query term, if at all
+fn word_weight(word: &str, term: &str, fuzzy: bool) -> Option<f64> {
+    if word == term {
+        Some(1.0)
+    } else if word.starts_with(term) {
+        Some(PREFIX_WEIGHT)
+    } else if fuzzy {
+        fuzzy::edit_distance(word, term, max_typos(term)).map(|typos| TYPO_WEIGHT / 2f64.powi(typos as i32 - 1))
+    } else {
+        None
+    }
+}
+
+/// Whether a lowercased word matches a query term, by prefix or, when fuzzy, within the typo allowance
+pub fn word_matches_term(word: &str, term: &str, fuzzy: bool) -> bool {
+    word_weight(word, term, fuzzy).is_some()
+}
+
+impl SearchIndex {
+    pub fn build(notebook: &NotebookData) -> Self {
+        let mut index = Self::default();
+        for note in notebook.notes.values() {
+            index.update_note(note);
+        }
+        index
+    }
+
+    /// Re-index the title, tags and text of one note
+    pub fn update_note(&mut self, note: &Note) {
+        self.remove_note(note.id);
+
+        let mut counts: HashMap<String, FieldCounts> = HashMap::new();
+        let title = tokenize(&note.title);
+        let tags: Vec<String> = note.tags.iter().flat_map(|tag| tokenize(tag)).collect();
+        let content = tokenize(&note.content);
+        for term in &title {
+            counts.entry(term.clone()).or_default().title += 1;
+        }
+        for term in &tags {
+            counts.entry(term.clone()).or_default().tags += 1;
+        }
+        for term in &content {
+            counts.entry(term.clone()).or_default().content += 1;
+        }
+
+        let lengths = FieldCounts {
+            title: title.len(),
+            tags: tags.len(),
+            content: content.len(),
+        };
+        self.total_lengths.title += lengths.title;
+        self.total_lengths.tags += lengths.tags;
+        self.total_lengths.content += lengths.content;
+
+        let terms: Vec<String> = counts.keys().cloned().collect();
+        for (term, field_counts) in counts {
+            self.postings.entry(term).or_default().insert(note.id, field_counts);
+        }
+        self.notes.insert(note.id, IndexedNote { lengths, terms });
+    }
+
+    pub fn remove_note(&mut self, note_id: Uuid) {
+        let Some(indexed) = self.notes.remove(&note_id) else {
+            return;
+        };
+        self.total_lengths.title -= indexed.lengths.title;
+        self.total_lengths.tags -= indexed.lengths.tags;
+        self.total_lengths.content -= indexed.lengths.content;
+        for term in indexed.terms {
+            if let Some(notes) = self.postings.get_mut(&term) {
+                notes.remove(&note_id);
+                if notes.is_empty() {
+                    self.postings.remove(&term);
+                }
+            }
+        }
+    }
+
+    /// Notes containing a word equal to or starting with every term, best match first.
+    /// Fuzzy searches also accept words a few typos away from a term.
+    pub fn search(&self, terms: &[String], fuzzy: bool) -> Vec<(Uuid, f64)> {
+        let mut scores: Option<HashMap<Uuid, f64>> = None;
+        for term in terms {
+            let term_scores = self.term_scores(term, fuzzy);
+            scores = Some(match scores {
+                None => term_scores,
+                Some(previous) => previous
+                    .into_iter()
+                    .filter_map(|(id, score)| term_scores.get(&id).map(|s| (id, score + s)))
+                    .collect(),
+            });
+        }
+
+        let mut results: Vec<(Uuid, f64)> = scores.unwrap_or_default().into_iter().collect();
+        results.sort_by(|a, b| b.1.total_cmp(&a.1));
+        results
+    }
+
+    /// Relevance to the terms of every note containing any of them
+    pub fn scores(&self, terms: &[String]) -> HashMap<Uuid, f64> {
+        let mut scores: HashMap<Uuid, f64> = HashMap::new();
+        for term in terms {
+            for (id, score) in self.term_scores(term, false) {
+                *scores.entry(id).or_default() += score;
+            }
+        }
+        scores
+    }
+
+    /// BM25F score of each note with a word matching the term, taking the best matching word
+    fn term_scores(&self, term: &str, fuzzy: bool) -> HashMap<Uuid, f64> {
+        let mut scores: HashMap<Uuid, f64> = HashMap::new();
+        let note_count = self.notes.len() as f64;
+        // Words starting with the term sort together; typos can be anywhere, so fuzzy terms check every word
+        let words: Box<dyn Iterator<Item = (&String, &HashMap<Uuid, FieldCounts>)>> = if fuzzy {
+            Box::new(self.postings.iter())
+        } else {
+            Box::new(
+                self.postings
+                    .range::<str, _>((std::ops::Bound::Included(term), std::ops::Bound::Unbounded))
+                    .take_while(|(word, _)| word.starts_with(term)),
+            )
+        };
+        for (word, notes) in words {
+            let Some(weight) = word_weight(word, term, fuzzy) else {
+                continue;
+            };
+            let found_in = notes.len() as f64;
+            let idf = (1.0 + (note_count - found_in + 0.5) / (found_in + 0.5)).ln();
+            for (id, counts) in notes {
+                let Some(indexed) = self.notes.get(id) else {
+                    continue;
+                };
+                let frequency = TITLE_BOOST * self.normalized(counts.title, indexed.lengths.title, self.total_lengths.title)
+                    + TAGS_BOOST * self.normalized(counts.tags, indexed.lengths.tags, self.total_lengths.tags)
+                    + CONTENT_BOOST * self.normalized(counts.content, indexed.lengths.content, self.total_lengths.content);
+                let score = weight * idf * frequency * (K1 + 1.0) / (K1 + frequency);
+                let best = scores.entry(*id).or_default();
+                *best = best.max(score);
+            }
+        }
+        scores
+    }
+
+    /// Term frequency in a field, scaled down for fields longer than average
+    fn normalized(&self, count: usize, length: usize, total_length: usize) -> f64 {
+        if count == 0 {
+            return 0.0;
+        }
+        let average = (total_length as f64 / self.notes.len().max(1) as f64).max(1.0);
+        count as f64 / (1.0 - B + B * length as f64 / average)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(title: &str, content: &str, tags: &[&str]) -> Note {
+        let mut note = Note::new(title.to_string(), None);
+        note.content = content.to_string();
+        note.tags = tags.iter().map(|tag| tag.to_string()).collect();
+        note
+    }
+
+    fn terms(text: &str) -> Vec<String> {
+        tokenize(text)
+    }
+
+    #[test]
+    fn words_are_runs_of_letters_and_digits() {
+        assert_eq!(word_spans("Hi, v2 café!"), vec![(0, 2), (4, 6), (7, 12)]);
+        assert_eq!(tokenize("Design-Review 2026"), vec!["design", "review", "2026"]);
+    }
+
+    #[test]
+    fn every_term_must_match_by_prefix() {
+        let mut index = SearchIndex::default();
+        let both = note("Weekly meeting", "agenda", &[]);
+        let one = note("Meeting", "", &[]);
+        index.update_note(&both);
+        index.update_note(&one);
+        let ids: Vec<Uuid> = index.search(&terms("meet agen"), false).into_iter().map(|(id, _)| id).collect();
+        assert_eq!(ids, vec![both.id]);
+        assert!(index.search(&terms("eeting"), false).is_empty());
+    }
+
+    #[test]
+    fn titles_outrank_tags_and_tags_outrank_text() {
+        let mut index = SearchIndex::default();
+        let in_text = note("One", "rust", &[]);
+        let in_tag = note("Two", "", &["rust"]);
+        let in_title = note("Rust", "", &[]);
+        for note in [&in_text, &in_tag, &in_title] {
+            index.update_note(note);
+        }
+        let ids: Vec<Uuid> = index.search(&terms("rust"), false).into_iter().map(|(id, _)| id).collect();
+        assert_eq!(ids, vec![in_title.id, in_tag.id, in_text.id]);
+    }
+
+    #[test]
+    fn exact_words_outrank_prefixes() {
+        let mut index = SearchIndex::default();
+        let prefix = note("Testing", "", &[]);
+        let exact = note("Test", "", &[]);
+        index.update_note(&prefix);
+        index.update_note(&exact);
+        let ids: Vec<Uuid> = index.search(&terms("test"), false).into_iter().map(|(id, _)| id).collect();
+        assert_eq!(ids, vec![exact.id, prefix.id]);
+    }
+
+    #[test]
+    fn updating_and_removing_notes_changes_their_postings() {
+        let mut index = SearchIndex::default();
+        let mut note = note("Draft", "apples", &[]);
+        index.update_note(&note);
+        note.content = "oranges".to_string();
+        index.update_note(&note);
+        assert!(index.search(&terms("apples"), false).is_empty());
+        assert_eq!(index.search(&terms("oranges"), false).len(), 1);
+        index.remove_note(note.id);
+        assert!(index.search(&terms("oranges"), false).is_empty());
+        assert!(index.postings.is_empty());
+    }
+
+    #[test]
+    fn fuzzy_terms_allow_typos_by_length() {
+        assert!(word_matches_term("meeting", "meetnig", true));
+        assert!(!word_matches_term("meeting", "meetnig", false));
+        assert!(word_matches_term("architecture", "archtecure", true));
+        // Short terms get no typos
+        assert!(!word_matches_term("cat", "cut", true));
+    }
+
+    #[test]
+    fn typos_score_below_exact_matches() {
+        let mut index = SearchIndex::default();
+        let typo = note("Meating", "", &[]);
+        let exact = note("Meeting", "", &[]);
+        index.update_note(&typo);
+        index.update_note(&exact);
+        let ids: Vec<Uuid> = index.search(&terms("meeting"), true).into_iter().map(|(id, _)| id).collect();
+        assert_eq!(ids, vec![exact.id, typo.id]);
+    }
+}
@@ -0,0 +1,336 @@
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+/// A user-defined snippet from the snippets file
+#[derive(Debug, Clone, Deserialize)]
+pub struct Snippet {
+    pub trigger: String,
+    #[serde(default)]
+    pub description: String,
+    pub body: SnippetBody,
+}
+
+/// A snippet body, written either as one string or as a list of lines
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum SnippetBody {
+    Text(String),
+    Lines(Vec<String>),
+}
+
+impl SnippetBody {
+    pub fn text(&self) -> String {
+        match self {
+            SnippetBody::Text(text) => text.clone(),
+            SnippetBody::Lines(lines) => lines.join("\n"),
+        }
+    }
+}
+
+/// Read snippets from a JSON file, skipping entries whose trigger is empty or contains spaces
+pub fn load_snippets(path: &Path) -> Result<Vec<Snippet>, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let snippets: Vec<Snippet> = serde_json::from_str(&contents)
+        .map_err(|e| format!("Invalid snippets file {}: {}", path.display(), e))?;
+
+    Ok(snippets
+        .into_iter()
+        .filter(|s| !s.trigger.is_empty() && !s.trigger.contains(char::is_whitespace))
+        .collect())
+}
+
+/// Values for the variables a snippet body can use
+#[derive(Debug, Clone, Default)]
+pub struct SnippetContext {
+    pub note_title: String,
+    pub folder: String,
+    /// Indentation of the line the snippet expands on, repeated on every following line
+    pub indent: String,
+    /// Date for `$DATE` when it is not today, as for a journal entry
+    pub date: Option<chrono::NaiveDate>,
+}
+
+impl SnippetContext {
+    fn variable(&self, name: &str) -> Option<String> {
+        let now = chrono::Local::now();
+        match name {
+            "DATE" => Some(self.date.unwrap_or(now.date_naive()).format("%Y-%m-%d").to_string()),
+            "TIME" => Some(now.format("%H:%M").to_string()),
+            "NOTE_TITLE" => Some(self.note_title.clone()),
+            "FOLDER" => Some(self.folder.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// A tab stop as a byte range, relative to the expanded text or to the editor content
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TabStop {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// An expanded snippet body with its tab stops in visiting order, `$0` last
+#[derive(Debug, Clone)]
+pub struct Expansion {
+    pub text: String,
+    pub stops: Vec<TabStop>,
+}
+
+/// Escape text so `expand` gives it back unchanged instead of reading stops or variables in it
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '$' | '}') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+enum Token {
+    Stop(u32, Option<String>),
+    Variable(String),
+}
+
+/// Expand `$1`, `${1:placeholder}`, `$0` and variables such as `$DATE` in a snippet body.
+/// Unknown variables are left as written; `\$` inserts a literal dollar sign.
+pub fn expand(body: &str, ctx: &SnippetContext) -> Expansion {
+    let chars: Vec<char> = body.chars().collect();
+    let mut text = String::new();
+    let mut numbered: Vec<(u32, TabStop)> = Vec::new();
+    let mut placeholders: Vec<(u32, String)> = Vec::new();
+
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' if matches!(chars.get(i + 1), Some('$' | '\\' | '}')) => {
+                text.push(chars[i + 1]);
+                i += 2;
+            }
+            '\n' => {
+                text.push('\n');
+                text.push_str(&ctx.indent);
+                i += 1;
+            }
+            '$' => match parse_token(&chars[i + 1..]) {
+                Some((Token::Stop(number, placeholder), consumed)) => {
+                    let start = text.len();
+                    if let Some((_, existing)) = placeholders.iter().find(|(n, _)| *n == number) {
+                        // Later occurrences repeat the first placeholder but are not stops
+                        text.push_str(existing);
+                    } else {
+                        let placeholder = placeholder.map(|p| expand(&p, ctx).text).unwrap_or_default();
+                        text.push_str(&placeholder);
+                        numbered.push((number, TabStop { start, end: text.len() }));
+                        placeholders.push((number, placeholder));
+                    }
+                    i += 1 + consumed;
+                }
+                Some((Token::Variable(name), consumed)) => {
+                    match ctx.variable(&name) {
+                        Some(value) => text.push_str(&value),
+                        None => text.extend(&chars[i..i + 1 + consumed]),
+                    }
+                    i += 1 + consumed;
+                }
+                None => {
+                    text.push('$');
+                    i += 1;
+                }
+            },
+            c => {
+                text.push(c);
+                i += 1;
+            }
+        }
+    }
+
+    // $0 is the final stop; without one the snippet ends after its text
+    numbered.sort_by_key(|(number, _)| if *number == 0 { u32::MAX } else { *number });
+    let mut stops: Vec<TabStop> = numbered.into_iter().map(|(_, stop)| stop).collect();
+    if !placeholders.iter().any(|(n, _)| *n == 0) {
+        stops.push(TabStop { start: text.len(), end: text.len() });
+    }
+
+    Expansion { text, stops }
+}
+
+/// Parse what follows a `$`, returning the token and the number of chars it used
+fn parse_token(rest: &[char]) -> Option<(Token, usize)> {
+    let digits = rest.iter().take_while(|c| c.is_ascii_digit()).count();
+    if digits > 0 {
+        let number = rest[..digits].iter().collect::<String>().parse().ok()?;
+        return Some((Token::Stop(number, None), digits));
+    }
+
+    let name_len = rest.iter().take_while(|c| c.is_ascii_uppercase() || **c == '_').count();
+    if name_len > 0 {
+        return Some((Token::Variable(rest[..name_len].iter().collect()), name_len));
+    }
+
+    if rest.first() != Some(&'{') {
+        return None;
+    }
+
+    // Find the matching brace, allowing nested ${...} in placeholders
+    let mut depth = 0;
+    let mut close = None;
+    let mut escaped = false;
+    for (i, &c) in rest.iter().enumerate() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    close = Some(i);
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+    let close = close?;
+    let inner: String = rest[1..close].iter().collect();
+
+    let (head, placeholder) = match inner.split_once(':') {
+        Some((head, placeholder)) => (head, Some(placeholder.to_string())),
+        None => (inner.as_str(), None),
+    };
+    if let Ok(number) = head.parse() {
+        return Some((Token::Stop(number, placeholder), close + 1));
+    }
+    if placeholder.is_none() && !head.is_empty() && head.chars().all(|c| c.is_ascii_uppercase() || c == '_') {
+        return Some((Token::Variable(head.to_string()), close + 1));
+    }
+    None
+}
+
+/// Tab stops of an expanded snippet while the user fills them in
+#[derive(Debug, Clone)]
+pub struct SnippetSession {
+    /// Absolute byte ranges in the editor content
+    pub stops: Vec<TabStop>,
+    pub current: usize,
+    /// Content length when the cursor entered the current stop
+    pub content_len: usize,
+}
+
+impl SnippetSession {
+    /// Account for text typed into the current stop since it was entered,
+    /// shifting the stops that come after it
+    pub fn sync(&mut self, content_len: usize) {
+        let delta = content_len as isize - self.content_len as isize;
+        let current = self.stops[self.current];
+        for (i, stop) in self.stops.iter_mut().enumerate() {
+            if i == self.current {
+                stop.end = stop.end.saturating_add_signed(delta).max(stop.start);
+            } else if stop.start > current.start || (stop.start == current.start && i > self.current) {
+                stop.start = stop.start.saturating_add_signed(delta);
+                stop.end = stop.end.saturating_add_signed(delta);
+            }
+        }
+        self.content_len = content_len;
+    }
+
+    /// Whether the placeholder of the current stop is untouched and should be
+    /// replaced by the next typed character
+    pub fn placeholder_pending(&self, cursor: usize, content_len: usize) -> bool {
+        let stop = self.stops[self.current];
+        stop.end > stop.start && cursor == stop.start && content_len == self.content_len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stop_texts(expansion: &Expansion) -> Vec<&str> {
+        expansion.stops.iter().map(|stop| &expansion.text[stop.start..stop.end]).collect()
+    }
+
+    #[test]
+    fn stops_are_visited_in_order_with_zero_last() {
+        let expansion = expand("${2:b} $0 ${1:a}", &SnippetContext::default());
+        assert_eq!(expansion.text, "b  a");
+        assert_eq!(stop_texts(&expansion), vec!["a", "b", ""]);
+        assert_eq!(expansion.stops[2], TabStop { start: 2, end: 2 });
+    }
+
+    #[test]
+    fn without_zero_the_snippet_ends_after_its_text() {
+        let expansion = expand("[$1]", &SnippetContext::default());
+        assert_eq!(expansion.text, "[]");
+        assert_eq!(expansion.stops, vec![TabStop { start: 1, end: 1 }, TabStop { start: 2, end: 2 }]);
+    }
+
+    #[test]
+    fn repeated_stops_copy_the_first_placeholder() {
+        let expansion = expand("${1:name} = $1", &SnippetContext::default());
+        assert_eq!(expansion.text, "name = name");
+        assert_eq!(stop_texts(&expansion), vec!["name", ""]);
+    }
+
+    #[test]
+    fn placeholders_can_nest_variables() {
+        let ctx = SnippetContext { note_title: "Plan".to_string(), ..Default::default() };
+        let expansion = expand("${1:# ${NOTE_TITLE}} $FOLDER_X", &ctx);
+        assert_eq!(expansion.text, "# Plan $FOLDER_X");
+        assert_eq!(stop_texts(&expansion)[0], "# Plan");
+    }
+
+    #[test]
+    fn escapes_and_stray_dollars_are_literal() {
+        let expansion = expand(r"costs \$5 or $ 6 \${1}", &SnippetContext::default());
+        assert_eq!(expansion.text, "costs $5 or $ 6 ${1}");
+    }
+
+    #[test]
+    fn following_lines_keep_the_indentation() {
+        let ctx = SnippetContext { indent: "  ".to_string(), ..Default::default() };
+        assert_eq!(expand("- a\n- $1", &ctx).text, "- a\n  - ");
+    }
+
+    #[test]
+    fn typing_in_a_stop_shifts_the_later_ones() {
+        let mut session = SnippetSession {
+            stops: vec![TabStop { start: 0, end: 0 }, TabStop { start: 5, end: 8 }],
+            current: 0,
+            content_len: 10,
+        };
+        session.sync(13);
+        assert_eq!(session.stops, vec![TabStop { start: 0, end: 3 }, TabStop { start: 8, end: 11 }]);
+        session.current = 1;
+        assert!(session.placeholder_pending(8, 13));
+        assert!(!session.placeholder_pending(9, 13));
+    }
+
+    #[test]
+    fn dates_default_to_today() {
+        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+        assert_eq!(expand("# $DATE", &SnippetContext::default()).text, format!("# {}", today));
+    }
+
+    #[test]
+    fn dates_can_be_set_for_journal_entries() {
+        let ctx = SnippetContext { date: chrono::NaiveDate::from_ymd_opt(2026, 2, 3), ..Default::default() };
+        assert_eq!(expand("# $DATE", &ctx).text, "# 2026-02-03");
+    }
+
+    #[test]
+    fn bodies_can_be_written_as_lines() {
+        let snippet: Snippet = serde_json::from_str(r##"{"trigger": "day", "body": ["# $DATE", "", "$0"]}"##).unwrap();
+        assert_eq!(snippet.body.text(), "# $DATE\n\n$0");
+    }
+
+    #[test]
+    fn escaped_text_expands_to_itself() {
+        let text = r"$1 ${2:a} $DATE \ } \$";
+        assert_eq!(expand(&escape(text), &SnippetContext::default()).text, text);
+    }
+}
@@ -1,12 +1,11 @@
-use crate::models::NotebookData;
-use dirs;
-use serde_json;
+use crate::models::{NotebookData, SessionState};
 use std::fs;
 use std::path::PathBuf;
 
 pub struct Storage {
     data_dir: PathBuf,
     notebook_file: PathBuf,
+    session_file: PathBuf,
 }
 
 impl Storage {
@@ -15,10 +14,12 @@ impl Storage {
         fs::create_dir_all(&data_dir)?;
         
         let notebook_file = data_dir.join("notebook.json");
+        let session_file = data_dir.join("session.json");
         
         Ok(Self {
             data_dir,
             notebook_file,
+            session_file,
         })
     }
 
@@ -53,6 +54,21 @@ impl Storage {
         Ok(())
     }
 
+    pub fn load_session(&self) -> Result<SessionState, Box<dyn std::error::Error>> {
+        if self.session_file.exists() {
+            let contents = fs::read_to_string(&self.session_file)?;
+            Ok(serde_json::from_str(&contents)?)
+        } else {
+            Ok(SessionState::default())
+        }
+    }
+
+    pub fn save_session(&self, session: &SessionState) -> Result<(), Box<dyn std::error::Error>> {
+        let json = serde_json::to_string_pretty(session)?;
+        fs::write(&self.session_file, json)?;
+        Ok(())
+    }
+
     #[allow(dead_code)]
     pub fn get_notes_dir(&self) -> PathBuf {
         self.data_dir.join("notes")
@@ -79,6 +79,67 @@ impl Default for SyntaxHighlighter {
     }
 }
 
+/// Patch the style of byte ranges of a line's text, splitting spans where needed.
+/// Ranges must be sorted and must not overlap.
+pub fn overlay_styles<'a>(line: Line<'a>, ranges: &[(usize, usize, Style)]) -> Line<'a> {
+    if ranges.is_empty() {
+        return line;
+    }
+
+    let mut spans = Vec::with_capacity(line.spans.len() + ranges.len() * 2);
+    let mut offset = 0;
+    for span in line.spans {
+        let text = span.content.as_ref();
+        let span_end = offset + text.len();
+        let mut pos = offset;
+
+        for &(start, end, style) in ranges.iter().filter(|(s, e, _)| *s < span_end && *e > offset) {
+            let start = start.max(pos);
+            let end = end.min(span_end);
+            if start > pos {
+                spans.push(Span::styled(text[pos - offset..start - offset].to_string(), span.style));
+            }
+            spans.push(Span::styled(text[start - offset..end - offset].to_string(), span.style.patch(style)));
+            pos = end;
+        }
+        if pos == offset {
+            spans.push(span);
+        } else if pos < span_end {
+            spans.push(Span::styled(text[pos - offset..].to_string(), span.style));
+        }
+        offset = span_end;
+    }
+
+    Line { spans, ..line }
+}
+
+/// Style the `[[wiki links]]` in a rendered line, marking those whose note is missing
+pub fn highlight_wiki_links<'a>(line: Line<'a>, exists: &dyn Fn(&str) -> bool) -> Line<'a> {
+    let text: String = line.spans.iter().map(|span| span.content.as_ref()).collect();
+    let ranges: Vec<(usize, usize, Style)> = crate::links::parse_links(&text)
+        .into_iter()
+        .map(|link| {
+            let style = if exists(&link.target) {
+                TokyoNightTheme::markdown_link()
+            } else {
+                TokyoNightTheme::broken_link()
+            };
+            (link.start, link.end, style)
+        })
+        .collect();
+    overlay_styles(line, &ranges)
+}
+
+/// Style the inline `#tags` in a rendered line
+pub fn highlight_hashtags(line: Line<'_>) -> Line<'_> {
+    let text: String = line.spans.iter().map(|span| span.content.as_ref()).collect();
+    let ranges: Vec<(usize, usize, Style)> = crate::tags::hashtag_spans(&text)
+        .into_iter()
+        .map(|(start, end)| (start, end, TokyoNightTheme::hashtag()))
+        .collect();
+    overlay_styles(line, &ranges)
+}
+
 // Fallback simple markdown highlighting for cases where syntect fails
 pub fn simple_markdown_highlight(content: &str) -> Text<'_> {
     let mut lines = Vec::new();
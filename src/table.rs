@@ -0,0 +1,364 @@
+use unicode_width::UnicodeWidthStr;
+
+/// Column alignment from a GFM table separator row
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColumnAlignment {
+    None,
+    Left,
+    Center,
+    Right,
+}
+
+impl ColumnAlignment {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "none" | "default" => Some(ColumnAlignment::None),
+            "left" | "l" => Some(ColumnAlignment::Left),
+            "center" | "centre" | "c" => Some(ColumnAlignment::Center),
+            "right" | "r" => Some(ColumnAlignment::Right),
+            _ => None,
+        }
+    }
+
+    fn from_separator(cell: &str) -> Self {
+        match (cell.starts_with(':'), cell.ends_with(':') && cell.len() > 1) {
+            (true, true) => ColumnAlignment::Center,
+            (true, false) => ColumnAlignment::Left,
+            (false, true) => ColumnAlignment::Right,
+            (false, false) => ColumnAlignment::None,
+        }
+    }
+
+    fn separator(self, width: usize) -> String {
+        let dashes = |n: usize| "-".repeat(n);
+        match self {
+            ColumnAlignment::None => dashes(width),
+            ColumnAlignment::Left => format!(":{}", dashes(width - 1)),
+            ColumnAlignment::Center => format!(":{}:", dashes(width - 2)),
+            ColumnAlignment::Right => format!("{}:", dashes(width - 1)),
+        }
+    }
+
+    fn pad(self, text: &str, width: usize) -> String {
+        let padding = width.saturating_sub(text.width());
+        match self {
+            ColumnAlignment::Right => format!("{}{}", " ".repeat(padding), text),
+            ColumnAlignment::Center => {
+                let left = padding / 2;
+                format!("{}{}{}", " ".repeat(left), text, " ".repeat(padding - left))
+            }
+            _ => format!("{}{}", text, " ".repeat(padding)),
+        }
+    }
+}
+
+/// A GFM table. Row 0 is the header; the separator row is implied by `alignments`.
+#[derive(Debug, Clone)]
+pub struct Table {
+    pub rows: Vec<Vec<String>>,
+    pub alignments: Vec<ColumnAlignment>,
+}
+
+/// Minimum column width, so the separator can hold `:-:`
+const MIN_COLUMN_WIDTH: usize = 3;
+
+impl Table {
+    /// Parse table lines: header, separator and body rows
+    pub fn parse(lines: &[&str]) -> Option<Table> {
+        if lines.len() < 2 || !is_separator_row(lines[1]) {
+            return None;
+        }
+
+        let alignments = split_cells(lines[1])
+            .iter()
+            .map(|cell| ColumnAlignment::from_separator(cell))
+            .collect();
+        let rows = lines
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != 1)
+            .map(|(_, line)| split_cells(line))
+            .collect();
+
+        let mut table = Table { rows, alignments };
+        table.normalize();
+        Some(table)
+    }
+
+    pub fn column_count(&self) -> usize {
+        self.alignments.len()
+    }
+
+    /// Pad every row to the same number of columns
+    fn normalize(&mut self) {
+        let columns = self.rows
+            .iter()
+            .map(Vec::len)
+            .chain(std::iter::once(self.alignments.len()))
+            .max()
+            .unwrap_or(1)
+            .max(1);
+        for row in &mut self.rows {
+            row.resize(columns, String::new());
+        }
+        self.alignments.resize(columns, ColumnAlignment::None);
+    }
+
+    /// Render the table with every column padded to its widest cell, by display width
+    pub fn render(&self) -> Vec<String> {
+        let widths: Vec<usize> = (0..self.column_count())
+            .map(|col| {
+                self.rows
+                    .iter()
+                    .map(|row| row[col].width())
+                    .max()
+                    .unwrap_or(0)
+                    .max(MIN_COLUMN_WIDTH)
+            })
+            .collect();
+
+        let render_row = |cells: Vec<String>| format!("| {} |", cells.join(" | "));
+
+        let mut lines = Vec::with_capacity(self.rows.len() + 1);
+        for (i, row) in self.rows.iter().enumerate() {
+            let cells = row
+                .iter()
+                .zip(&widths)
+                .zip(&self.alignments)
+                .map(|((cell, &width), &alignment)| alignment.pad(cell, width))
+                .collect();
+            lines.push(render_row(cells));
+
+            if i == 0 {
+                let separators = self.alignments
+                    .iter()
+                    .zip(&widths)
+                    .map(|(alignment, &width)| alignment.separator(width))
+                    .collect();
+                lines.push(render_row(separators));
+            }
+        }
+        lines
+    }
+
+    /// Insert an empty body row after the given row (0 is the header)
+    pub fn insert_row(&mut self, after: usize) {
+        let at = (after + 1).clamp(1, self.rows.len());
+        self.rows.insert(at, vec![String::new(); self.column_count()]);
+    }
+
+    /// Delete a body row. The header cannot be deleted.
+    pub fn delete_row(&mut self, row: usize) -> Result<(), String> {
+        if row == 0 || row >= self.rows.len() {
+            return Err("Cannot delete the header row".to_string());
+        }
+        self.rows.remove(row);
+        Ok(())
+    }
+
+    /// Insert an empty column after the given column
+    pub fn insert_column(&mut self, after: usize) {
+        let at = (after + 1).min(self.column_count());
+        for row in &mut self.rows {
+            row.insert(at, String::new());
+        }
+        self.alignments.insert(at, ColumnAlignment::None);
+    }
+
+    /// Delete a column, keeping at least one
+    pub fn delete_column(&mut self, col: usize) -> Result<(), String> {
+        if self.column_count() <= 1 {
+            return Err("Cannot delete the last column".to_string());
+        }
+        let col = col.min(self.column_count() - 1);
+        for row in &mut self.rows {
+            row.remove(col);
+        }
+        self.alignments.remove(col);
+        Ok(())
+    }
+
+    pub fn set_alignment(&mut self, col: usize, alignment: ColumnAlignment) {
+        if let Some(a) = self.alignments.get_mut(col) {
+            *a = alignment;
+        }
+    }
+}
+
+/// Whether a line looks like a table row
+pub fn is_table_row(line: &str) -> bool {
+    let trimmed = line.trim();
+    !trimmed.is_empty() && trimmed.contains('|')
+}
+
+/// Whether a line is a GFM separator row such as `|---|:-:|`; without a pipe it is a rule
+pub fn is_separator_row(line: &str) -> bool {
+    let cells = split_cells(line);
+    line.contains('|')
+        && line.contains('-')
+        && !cells.is_empty()
+        && cells.iter().all(|cell| {
+            let inner = cell.trim_start_matches(':').trim_end_matches(':');
+            !inner.is_empty() && inner.chars().all(|c| c == '-')
+        })
+}
+
+/// Find the table containing `row`, returning the range of its lines. A table starts at
+/// a header directly above a separator row, so prose with pipes around it is left out.
+pub fn find_table(lines: &[&str], row: usize) -> Option<(usize, usize)> {
+    if !lines.get(row).is_some_and(|l| is_table_row(l)) {
+        return None;
+    }
+
+    // The nearest header at or above the row, without leaving the run of pipe lines
+    let mut start = row;
+    loop {
+        if lines.get(start + 1).is_some_and(|l| is_separator_row(l)) {
+            break;
+        }
+        if start == 0 || !is_table_row(lines[start - 1]) {
+            return None;
+        }
+        start -= 1;
+    }
+    let mut end = start + 1;
+    while end + 1 < lines.len() && is_table_row(lines[end + 1]) {
+        end += 1;
+    }
+    (row <= end).then_some((start, end))
+}
+
+/// Split a table row into trimmed cells, honouring `\|` escapes
+pub fn split_cells(line: &str) -> Vec<String> {
+    let trimmed = line.trim();
+    let trimmed = trimmed.strip_prefix('|').unwrap_or(trimmed);
+    let trimmed = match trimmed.strip_suffix('|') {
+        Some(rest) if !rest.ends_with('\\') => rest,
+        _ => trimmed,
+    };
+
+    let mut cells = Vec::new();
+    let mut current = String::new();
+    let mut escaped = false;
+    for c in trimmed.chars() {
+        if c == '|' && !escaped {
+            cells.push(current.trim().to_string());
+            current.clear();
+        } else {
+            current.push(c);
+        }
+        escaped = c == '\\' && !escaped;
+    }
+    cells.push(current.trim().to_string());
+    cells
+}
+
+/// Index of the cell containing byte column `col` of a table line
+pub fn cell_at(line: &str, col: usize) -> usize {
+    let leading_pipe = line.trim_start().starts_with('|');
+    let mut escaped = false;
+    let mut pipes: usize = 0;
+    for (i, c) in line.char_indices() {
+        if i >= col {
+            break;
+        }
+        if c == '|' && !escaped {
+            pipes += 1;
+        }
+        escaped = c == '\\' && !escaped;
+    }
+    if leading_pipe {
+        pipes.saturating_sub(1)
+    } else {
+        pipes
+    }
+}
+
+/// Byte column where the text of a cell starts in a rendered table line
+pub fn cell_start(rendered: &str, cell: usize) -> usize {
+    let mut escaped = false;
+    let pipes: Vec<usize> = rendered
+        .char_indices()
+        .filter(|&(_, c)| {
+            let is_pipe = c == '|' && !escaped;
+            escaped = c == '\\' && !escaped;
+            is_pipe
+        })
+        .map(|(i, _)| i)
+        .collect();
+
+    let Some(&open) = pipes.get(cell) else {
+        return rendered.len();
+    };
+    let close = pipes.get(cell + 1).copied().unwrap_or(rendered.len());
+    let segment = &rendered[open + 1..close];
+    match segment.find(|c: char| c != ' ') {
+        Some(offset) => open + 1 + offset,
+        None => (open + 2).min(close),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tables_need_a_separator_below_the_header() {
+        let lines = ["| a | b |", "|---|---|", "| 1 | 2 |", "", "a | b", "no separator | here"];
+        assert_eq!(find_table(&lines, 0), Some((0, 2)));
+        assert_eq!(find_table(&lines, 1), Some((0, 2)));
+        assert_eq!(find_table(&lines, 2), Some((0, 2)));
+        assert_eq!(find_table(&lines, 3), None);
+        assert_eq!(find_table(&lines, 4), None);
+        assert_eq!(find_table(&lines, 5), None);
+    }
+
+    #[test]
+    fn prose_with_pipes_above_a_table_is_not_part_of_it() {
+        let lines = ["Use a | b to pipe", "| a | b |", "| --- | :-: |", "| 1 | 2 |"];
+        assert_eq!(find_table(&lines, 0), None);
+        assert_eq!(find_table(&lines, 3), Some((1, 3)));
+    }
+
+    #[test]
+    fn rules_are_not_separator_rows() {
+        assert!(!is_separator_row("---"));
+        assert!(is_separator_row("|---|"));
+        assert!(is_separator_row(":--|--:"));
+        assert!(!is_separator_row("| - a |"));
+    }
+
+    #[test]
+    fn render_pads_columns_and_keeps_alignments() {
+        let table = Table::parse(&["| Name | Qty |", "|:-|-:|", "| apple | 3 |"]).unwrap();
+        assert_eq!(table.alignments, vec![ColumnAlignment::Left, ColumnAlignment::Right]);
+        assert_eq!(table.render(), vec!["| Name  | Qty |", "| :---- | --: |", "| apple |   3 |"]);
+    }
+
+    #[test]
+    fn wide_characters_take_two_columns() {
+        let table = Table::parse(&["| 名前 | x |", "|---|---|", "| abcde | y |"]).unwrap();
+        assert_eq!(table.render(), vec!["| 名前  | x   |", "| ----- | --- |", "| abcde | y   |"]);
+    }
+
+    #[test]
+    fn rows_are_padded_to_the_same_columns() {
+        let mut table = Table::parse(&["| a |", "|---|", "| 1 | 2 | 3 |"]).unwrap();
+        assert_eq!(table.column_count(), 3);
+        assert_eq!(table.rows[0], vec!["a", "", ""]);
+        table.insert_column(0);
+        assert_eq!(table.rows[1], vec!["1", "", "2", "3"]);
+        assert!(table.delete_row(0).is_err());
+        table.insert_row(0);
+        assert_eq!(table.rows.len(), 3);
+        assert!(table.delete_row(1).is_ok());
+    }
+
+    #[test]
+    fn escaped_pipes_stay_in_their_cell() {
+        assert_eq!(split_cells(r"| a \| b | c |"), vec![r"a \| b", "c"]);
+        assert_eq!(cell_at(r"| a \| b | c |", 10), 1);
+        assert_eq!(cell_at("| a | b |", 2), 0);
+        assert_eq!(cell_start("| a   | b   |", 1), 8);
+    }
+}
@@ -0,0 +1,348 @@
+use crate::fuzzy;
+use crate::models::{Note, NotebookData};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use uuid::Uuid;
+
+/// Clean up a tag as typed: drop a leading `#` and outer slashes, lowercase it,
+/// and reject empty tags, empty nesting levels or tags containing whitespace or commas
+pub fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.trim().trim_start_matches('#').trim_matches('/');
+    if tag.is_empty() || tag.contains("//") || tag.contains(|c: char| c.is_whitespace() || c == ',') {
+        return None;
+    }
+    Some(tag.to_lowercase())
+}
+
+/// Parse a comma or space separated tag list, dropping duplicates
+pub fn parse_tag_list(input: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in input.split(|c: char| c == ',' || c.is_whitespace()).filter_map(normalize_tag) {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// Whether a tag is `ancestor` itself or nested below it (`project/alpha` is within `project`)
+pub fn is_within(tag: &str, ancestor: &str) -> bool {
+    let tag = tag.to_lowercase();
+    let ancestor = ancestor.to_lowercase();
+    tag == ancestor || tag.strip_prefix(&ancestor).is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Whether a note carries the tag or one nested below it
+pub fn note_has_tag(note: &Note, tag: &str) -> bool {
+    note.tags.iter().any(|t| is_within(t, tag))
+}
+
+/// A tag with its place in the tag hierarchy
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagTreeItem {
+    /// Full tag, e.g. `project/alpha`
+    pub path: String,
+    /// Last segment of the path, e.g. `alpha`
+    pub name: String,
+    pub depth: usize,
+    /// Notes carrying this tag or any tag nested below it
+    pub count: usize,
+    pub has_children: bool,
+}
+
+/// Every tag and every parent of a nested tag as a flattened tree, parents
+/// before children. Children of the `collapsed` paths are left out.
+pub fn tag_tree(notebook: &NotebookData, collapsed: &HashSet<String>) -> Vec<TagTreeItem> {
+    let mut notes_by_path: HashMap<String, HashSet<Uuid>> = HashMap::new();
+    for note in notebook.notes.values() {
+        for tag in &note.tags {
+            let tag = tag.to_lowercase();
+            for (i, _) in tag.match_indices('/').chain(std::iter::once((tag.len(), ""))) {
+                if i > 0 {
+                    notes_by_path.entry(tag[..i].to_string()).or_default().insert(note.id);
+                }
+            }
+        }
+    }
+
+    let mut paths: Vec<&String> = notes_by_path.keys().collect();
+    paths.sort_by(|a, b| a.split('/').cmp(b.split('/')));
+
+    paths
+        .iter()
+        .enumerate()
+        .filter(|(_, path)| !collapsed.iter().any(|c| path.len() > c.len() && is_within(path, c)))
+        .map(|(i, path)| TagTreeItem {
+            path: path.to_string(),
+            name: path.rsplit('/').next().unwrap_or(path).to_string(),
+            depth: path.matches('/').count(),
+            count: notes_by_path[*path].len(),
+            has_children: paths.get(i + 1).is_some_and(|next| is_within(next, path)),
+        })
+        .collect()
+}
+
+/// The tag after renaming `old` to `new`, carrying nested tags along
+/// (`project/alpha` becomes `work/alpha`). None if the tag is not within `old`.
+/// Both tags are expected to be normalized.
+pub fn retag(tag: &str, old: &str, new: &str) -> Option<String> {
+    is_within(tag, old).then(|| format!("{}{}", new, &tag[old.len()..]))
+}
+
+/// Rename inline `#tags` within `old` in a note's text, keeping the case of
+/// nested segments. Returns None if nothing changed. Fenced code blocks are left alone.
+pub fn rewrite_hashtags(content: &str, old: &str, new: &str) -> Option<String> {
+    let mut changed = false;
+    let mut in_code_block = false;
+
+    let lines: Vec<String> = content
+        .split('\n')
+        .map(|line| {
+            if line.trim_start().starts_with("```") {
+                in_code_block = !in_code_block;
+            }
+            if in_code_block {
+                return line.to_string();
+            }
+
+            let mut line = line.to_string();
+            for (start, end) in hashtag_spans(&line).into_iter().rev() {
+                let tag = &line[start + 1..end];
+                let lower = tag.to_lowercase();
+                let Some(renamed) = retag(&lower, old, new) else {
+                    continue;
+                };
+                // Lowercasing can change byte lengths outside ASCII; use the lowercased tag then
+                let renamed = if lower.len() == tag.len() {
+                    format!("{}{}", new, &tag[old.len()..])
+                } else {
+                    renamed
+                };
+                line.replace_range(start + 1..end, &renamed);
+                changed = true;
+            }
+            line
+        })
+        .collect();
+
+    changed.then(|| lines.join("\n"))
+}
+
+/// Every tag in the notebook with the number of notes carrying it, by name
+pub fn tag_counts(notebook: &NotebookData) -> Vec<(String, usize)> {
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for note in notebook.notes.values() {
+        for tag in &note.tags {
+            *counts.entry(tag.to_lowercase()).or_default() += 1;
+        }
+    }
+    counts.into_iter().collect()
+}
+
+/// Existing tags matching a partly typed one, best match first
+pub fn complete_tag(notebook: &NotebookData, partial: &str, exclude: &[String]) -> Vec<String> {
+    let partial = partial.trim_start_matches('#');
+    let mut matches: Vec<(i64, usize, String)> = tag_counts(notebook)
+        .into_iter()
+        .filter(|(tag, _)| !exclude.contains(tag) && tag != partial)
+        .filter_map(|(tag, count)| fuzzy::fuzzy_match(partial, &tag).map(|m| (m.score, count, tag)))
+        .collect();
+    matches.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.cmp(&a.1)).then(a.2.cmp(&b.2)));
+    matches.into_iter().map(|(_, _, tag)| tag).collect()
+}
+
+/// Whether a char can continue an inline `#tag`
+fn is_tag_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '-' || c == '_' || c == '/'
+}
+
+/// Whether a `#` at this point of a line can start a tag: at the start,
+/// after whitespace or after an opening parenthesis that is not a link target like `[see](#setup)`
+fn can_start_tag(before: &str) -> bool {
+    let mut chars = before.chars().rev();
+    match chars.next() {
+        None => true,
+        Some('(') => chars.next() != Some(']'),
+        Some(c) => c.is_whitespace(),
+    }
+}
+
+/// Whether a line is an ATX heading, whose `#`s are never tags
+fn is_heading(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    let hashes = trimmed.chars().take_while(|&c| c == '#').count();
+    (1..=6).contains(&hashes) && trimmed[hashes..].chars().next().is_none_or(char::is_whitespace)
+}
+
+/// Byte ranges of the inline `#tags` in one line, `#` included.
+/// Headings and `code spans` have no tags.
+pub fn hashtag_spans(line: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    if is_heading(line) {
+        return spans;
+    }
+
+    let bytes = line.as_bytes();
+    let mut i = 0;
+    while i < line.len() {
+        match bytes[i] {
+            b'`' => {
+                // Skip to the closing run of the same number of backticks, if there is one
+                let run = line[i..].bytes().take_while(|&b| b == b'`').count();
+                let fence = &line[i..i + run];
+                let mut search = i + run;
+                i += run;
+                while let Some(close) = line[search..].find(fence).map(|p| search + p) {
+                    let close_run = line[close..].bytes().take_while(|&b| b == b'`').count();
+                    if close_run == run {
+                        i = close + run;
+                        break;
+                    }
+                    search = close + close_run;
+                }
+            }
+            b'#' if can_start_tag(&line[..i]) => {
+                let rest = &line[i + 1..];
+                let len: usize = rest.chars().take_while(|&c| is_tag_char(c)).map(char::len_utf8).sum();
+                let tag = rest[..len].trim_end_matches('/');
+                if tag.chars().next().is_some_and(char::is_alphabetic) {
+                    spans.push((i, i + 1 + tag.len()));
+                }
+                i += 1 + len;
+            }
+            _ => i += line[i..].chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    spans
+}
+
+/// Every inline `#tag` in a note, normalized, in order of first use.
+/// Fenced code blocks are skipped.
+pub fn extract_hashtags(content: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    let mut in_code_block = false;
+    for line in content.split('\n') {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+        for (start, end) in hashtag_spans(line) {
+            if let Some(tag) = normalize_tag(&line[start..end]) {
+                if !tags.contains(&tag) {
+                    tags.push(tag);
+                }
+            }
+        }
+    }
+    tags
+}
+
+/// If the cursor ends a `#tag` being typed on this line, the byte column of
+/// the `#` and the text typed after it
+pub fn open_hashtag_query(line_up_to_cursor: &str) -> Option<(usize, &str)> {
+    if is_heading(line_up_to_cursor) || line_up_to_cursor.matches('`').count() % 2 == 1 {
+        return None;
+    }
+    let hash = line_up_to_cursor.rfind('#')?;
+    let query = &line_up_to_cursor[hash + 1..];
+    let valid = can_start_tag(&line_up_to_cursor[..hash])
+        && query.chars().next().is_some_and(char::is_alphabetic)
+        && query.chars().all(is_tag_char);
+    valid.then_some((hash, query))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags_in(line: &str) -> Vec<&str> {
+        hashtag_spans(line).into_iter().map(|(start, end)| &line[start..end]).collect()
+    }
+
+    #[test]
+    fn tags_start_after_whitespace_or_an_opening_parenthesis() {
+        assert_eq!(tags_in("#todo call (#work) back"), vec!["#todo", "#work"]);
+        assert_eq!(tags_in("issue#12 a#b #1st"), Vec::<&str>::new());
+        assert_eq!(tags_in("#project/alpha/ done"), vec!["#project/alpha"]);
+    }
+
+    #[test]
+    fn link_anchors_are_not_tags() {
+        assert_eq!(tags_in("[see](#setup) and #real"), vec!["#real"]);
+        assert_eq!(open_hashtag_query("[see](#set"), None);
+        assert_eq!(open_hashtag_query("(#wor"), Some((1, "wor")));
+    }
+
+    #[test]
+    fn headings_and_code_spans_have_no_tags() {
+        assert!(tags_in("## Heading #not").is_empty());
+        assert_eq!(tags_in("#tag is not a heading"), vec!["#tag"]);
+        assert_eq!(tags_in("`#code` ``a ` #still`` #after"), vec!["#after"]);
+    }
+
+    #[test]
+    fn extracted_tags_are_normalized_and_skip_code_blocks() {
+        let content = "#Todo and #todo\n```\n#ignored\n```\n#work/Alpha";
+        assert_eq!(extract_hashtags(content), vec!["todo", "work/alpha"]);
+    }
+
+    #[test]
+    fn tags_are_cleaned_up_as_typed() {
+        assert_eq!(normalize_tag(" #Work/ "), Some("work".to_string()));
+        assert_eq!(normalize_tag("a//b"), None);
+        assert_eq!(parse_tag_list("one, two one #three"), vec!["one", "two", "three"]);
+    }
+
+    #[test]
+    fn nesting_follows_slash_boundaries() {
+        assert!(is_within("a/b", "a"));
+        assert!(is_within("A/B", "a"));
+        assert!(is_within("a", "a"));
+        assert!(!is_within("ab", "a"));
+        assert!(!is_within("a", "a/b"));
+    }
+
+    #[test]
+    fn retagging_a_parent_carries_its_children() {
+        assert_eq!(retag("project/alpha", "project", "work"), Some("work/alpha".to_string()));
+        assert_eq!(retag("project", "project", "work/old"), Some("work/old".to_string()));
+        assert_eq!(retag("projects", "project", "work"), None);
+    }
+
+    #[test]
+    fn hashtags_are_rewritten_outside_code() {
+        let content = "#project and #project/Alpha, not #projects\n`#project` stays\n```\n#project\n```";
+        assert_eq!(
+            rewrite_hashtags(content, "project", "work").as_deref(),
+            Some("#work and #work/Alpha, not #projects\n`#project` stays\n```\n#project\n```"),
+        );
+        assert_eq!(rewrite_hashtags("#other", "project", "work"), None);
+    }
+
+    #[test]
+    fn the_tag_tree_lists_parents_before_children() {
+        let mut notebook = NotebookData::new();
+        for tags in [vec!["project/alpha"], vec!["project/beta", "ab"], vec!["project"]] {
+            let mut note = Note::new("Note".to_string(), None);
+            note.tags = tags.into_iter().map(str::to_string).collect();
+            notebook.add_note(note);
+        }
+
+        let tree = tag_tree(&notebook, &HashSet::new());
+        let rows: Vec<(&str, usize, usize, bool)> = tree.iter()
+            .map(|t| (t.path.as_str(), t.depth, t.count, t.has_children))
+            .collect();
+        assert_eq!(rows, vec![
+            ("ab", 0, 1, false),
+            ("project", 0, 3, true),
+            ("project/alpha", 1, 1, false),
+            ("project/beta", 1, 1, false),
+        ]);
+
+        let collapsed = HashSet::from(["project".to_string()]);
+        let paths: Vec<String> = tag_tree(&notebook, &collapsed).into_iter().map(|t| t.path).collect();
+        assert_eq!(paths, vec!["ab", "project"]);
+    }
+}
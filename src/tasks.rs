@@ -0,0 +1,181 @@
+use crate::lists::{self, LinePrefix};
+use crate::models::NotebookData;
+use uuid::Uuid;
+
+/// A checkbox list item found in a note
+#[derive(Debug, Clone)]
+pub struct TaskItem {
+    pub note_id: Uuid,
+    pub note_title: String,
+    pub folder_id: Option<Uuid>,
+    pub line_number: usize,
+    pub text: String,
+    pub done: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TaskGrouping {
+    Note,
+    Folder,
+}
+
+impl TaskGrouping {
+    pub fn toggle(self) -> Self {
+        match self {
+            TaskGrouping::Note => TaskGrouping::Folder,
+            TaskGrouping::Folder => TaskGrouping::Note,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TaskGrouping::Note => "note",
+            TaskGrouping::Folder => "folder",
+        }
+    }
+}
+
+/// Find the checkbox items in markdown content, skipping fenced code blocks.
+/// Returns (line number, task text, done).
+pub fn tasks_in_content(content: &str) -> Vec<(usize, String, bool)> {
+    let mut tasks = Vec::new();
+    let mut in_fence = false;
+
+    for (line_number, line) in content.lines().enumerate() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        let prefix = LinePrefix::parse(line);
+        if let Some(done) = prefix.checkbox {
+            let text = lists::strip_completion_date(&line[prefix.len..]).trim().to_string();
+            tasks.push((line_number, text, done));
+        }
+    }
+
+    tasks
+}
+
+/// Collect every task in the notebook. `current` overrides the stored
+/// content of the note open in the editor so unsaved edits are included.
+pub fn collect_tasks(notebook: &NotebookData, current: Option<(Uuid, &str)>) -> Vec<TaskItem> {
+    let mut items = Vec::new();
+
+    for note in notebook.notes.values() {
+        let content = match current {
+            Some((id, content)) if id == note.id => content,
+            _ => note.content.as_str(),
+        };
+
+        for (line_number, text, done) in tasks_in_content(content) {
+            items.push(TaskItem {
+                note_id: note.id,
+                note_title: note.title.clone(),
+                folder_id: note.folder_id,
+                line_number,
+                text,
+                done,
+            });
+        }
+    }
+
+    items
+}
+
+/// Name of the group a task belongs to
+pub fn group_label(notebook: &NotebookData, task: &TaskItem, grouping: TaskGrouping) -> String {
+    match grouping {
+        TaskGrouping::Note => task.note_title.clone(),
+        TaskGrouping::Folder => match task.folder_id {
+            Some(folder_id) => notebook.folder_path(folder_id).join(" / "),
+            None => "Root".to_string(),
+        },
+    }
+}
+
+/// Sort tasks so that each group is contiguous, keeping source order within a note
+pub fn sort_tasks(notebook: &NotebookData, tasks: &mut [TaskItem], grouping: TaskGrouping) {
+    tasks.sort_by_cached_key(|task| {
+        (
+            group_label(notebook, task, grouping).to_lowercase(),
+            task.note_title.to_lowercase(),
+            task.note_id,
+            task.line_number,
+        )
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Folder, Note};
+
+    fn note(notebook: &mut NotebookData, title: &str, folder_id: Option<Uuid>, content: &str) -> Uuid {
+        let mut note = Note::new(title.to_string(), folder_id);
+        note.content = content.to_string();
+        let id = note.id;
+        notebook.add_note(note);
+        id
+    }
+
+    #[test]
+    fn nested_and_quoted_tasks_are_found_outside_code_fences() {
+        let content = "- [ ] top\n    - [x] nested\n> - [ ] quoted\n```\n- [ ] in code\n```\n- plain item";
+        assert_eq!(tasks_in_content(content), vec![
+            (0, "top".to_string(), false),
+            (1, "nested".to_string(), true),
+            (2, "quoted".to_string(), false),
+        ]);
+    }
+
+    #[test]
+    fn completion_dates_are_left_out_of_the_task_text() {
+        let tasks = tasks_in_content("- [x] ship it ✅ 2024-05-01\n- [x] not a date ✅ soon");
+        assert_eq!(tasks[0], (0, "ship it".to_string(), true));
+        assert_eq!(tasks[1], (1, "not a date ✅ soon".to_string(), true));
+    }
+
+    #[test]
+    fn unsaved_editor_content_replaces_the_stored_note() {
+        let mut notebook = NotebookData::new();
+        let id = note(&mut notebook, "Open", None, "- [ ] stored");
+        let tasks = collect_tasks(&notebook, Some((id, "- [x] edited ✅ 2024-05-01")));
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].text, "edited");
+        assert!(tasks[0].done);
+    }
+
+    #[test]
+    fn done_and_dated_tasks_keep_their_source_order_within_a_note() {
+        let mut notebook = NotebookData::new();
+        note(&mut notebook, "b", None, "- [x] first ✅ 2024-06-01\n- [ ] second\n- [x] third ✅ 2023-01-01");
+        note(&mut notebook, "A", None, "- [ ] only");
+
+        let mut tasks = collect_tasks(&notebook, None);
+        sort_tasks(&notebook, &mut tasks, TaskGrouping::Note);
+        let order: Vec<(&str, bool)> = tasks.iter().map(|t| (t.text.as_str(), t.done)).collect();
+        assert_eq!(order, vec![("only", false), ("first", true), ("second", false), ("third", true)]);
+    }
+
+    #[test]
+    fn folder_grouping_keeps_each_folder_contiguous() {
+        let mut notebook = NotebookData::new();
+        let work = Folder::new("Work".to_string(), None);
+        let work_id = work.id;
+        notebook.add_folder(work);
+        note(&mut notebook, "Zed", Some(work_id), "- [ ] z");
+        note(&mut notebook, "Alpha", None, "- [ ] a");
+        note(&mut notebook, "Beta", Some(work_id), "- [ ] b");
+
+        let mut tasks = collect_tasks(&notebook, None);
+        sort_tasks(&notebook, &mut tasks, TaskGrouping::Folder);
+        let groups: Vec<String> = tasks.iter().map(|t| group_label(&notebook, t, TaskGrouping::Folder)).collect();
+        assert_eq!(groups, vec!["Root", "Work", "Work"]);
+        assert_eq!(tasks[1].text, "b");
+        assert_eq!(tasks[2].text, "z");
+    }
+}
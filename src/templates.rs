@@ -0,0 +1,127 @@
+use crate::models::{Note, NotebookData};
+use crate::snippets;
+
+/// Root folder whose notes are offered as templates for new notes
+pub const TEMPLATES_FOLDER: &str = "Templates";
+
+/// Notes in the templates folder, by title
+pub fn template_notes(notebook: &NotebookData) -> Vec<&Note> {
+    let Some(folder) = notebook
+        .folders
+        .values()
+        .find(|f| f.parent_id.is_none() && f.name.eq_ignore_ascii_case(TEMPLATES_FOLDER))
+    else {
+        return Vec::new();
+    };
+
+    let mut notes = notebook.get_folder_notes(Some(folder.id));
+    notes.sort_by_key(|note| note.title.to_lowercase());
+    notes
+}
+
+/// Byte ranges of the `${?Name}` prompts in a template, with their names
+fn prompts(body: &str) -> Vec<(usize, usize, &str)> {
+    let mut prompts = Vec::new();
+    let mut i = 0;
+    while let Some(offset) = body[i..].find(['\\', '$']) {
+        let start = i + offset;
+        if body[start..].starts_with('\\') {
+            // An escaped character is never the start of a prompt
+            i = start + 1 + body[start + 1..].chars().next().map_or(0, char::len_utf8);
+            continue;
+        }
+        i = start + 1;
+        let Some(rest) = body[start..].strip_prefix("${?") else {
+            continue;
+        };
+        let Some(close) = rest.find('}') else {
+            break;
+        };
+        let name = rest[..close].trim();
+        if !name.is_empty() {
+            let end = start + "${?".len() + close + 1;
+            prompts.push((start, end, name));
+            i = end;
+        }
+    }
+    prompts
+}
+
+/// Names of the fields asked for when a note is created from the template, in order of first use
+pub fn prompt_fields(body: &str) -> Vec<String> {
+    let mut fields: Vec<String> = Vec::new();
+    for (_, _, name) in prompts(body) {
+        if !fields.iter().any(|f| f == name) {
+            fields.push(name.to_string());
+        }
+    }
+    fields
+}
+
+/// Replace each `${?Name}` prompt with the value given for it. Values are escaped
+/// so snippet expansion afterwards leaves them as typed.
+pub fn fill_prompts(body: &str, values: &[(String, String)]) -> String {
+    let mut filled = String::new();
+    let mut last = 0;
+    for (start, end, name) in prompts(body) {
+        filled.push_str(&body[last..start]);
+        if let Some((_, value)) = values.iter().find(|(field, _)| field == name) {
+            filled.push_str(&snippets::escape(value));
+        }
+        last = end;
+    }
+    filled.push_str(&body[last..]);
+    filled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Folder;
+    use crate::snippets::SnippetContext;
+
+    fn values(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn templates_are_the_notes_of_the_root_templates_folder() {
+        let mut notebook = NotebookData::new();
+        let templates = Folder::new("templates".to_string(), None);
+        let nested = Folder::new(TEMPLATES_FOLDER.to_string(), Some(templates.id));
+        for (title, folder_id) in [("Meeting", Some(templates.id)), ("daily", Some(templates.id)), ("Other", Some(nested.id)), ("Loose", None)] {
+            notebook.add_note(Note::new(title.to_string(), folder_id));
+        }
+        notebook.add_folder(templates);
+        notebook.add_folder(nested);
+
+        let titles: Vec<&str> = template_notes(&notebook).iter().map(|n| n.title.as_str()).collect();
+        assert_eq!(titles, vec!["daily", "Meeting"]);
+    }
+
+    #[test]
+    fn repeated_prompts_are_asked_once_and_filled_everywhere() {
+        let body = "# ${?Topic}\nWith ${? Who }, about ${?Topic}";
+        assert_eq!(prompt_fields(body), vec!["Topic", "Who"]);
+        assert_eq!(
+            fill_prompts(body, &values(&[("Topic", "Budget"), ("Who", "Sam")])),
+            "# Budget\nWith Sam, about Budget",
+        );
+    }
+
+    #[test]
+    fn escaped_and_unfinished_prompts_are_not_fields() {
+        let body = r"\${?Literal} ${?} ${?Open";
+        assert!(prompt_fields(body).is_empty());
+        assert_eq!(fill_prompts(body, &[]), body);
+    }
+
+    #[test]
+    fn values_stay_as_typed_after_snippet_expansion() {
+        let body = "Cost: ${?Amount} then $1";
+        let value = r"$5 for ${1:x} \o/ }";
+        let filled = fill_prompts(body, &values(&[("Amount", value)]));
+        let expanded = snippets::expand(&filled, &SnippetContext::default());
+        assert_eq!(expanded.text, format!("Cost: {} then ", value));
+    }
+}
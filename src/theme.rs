@@ -173,13 +173,26 @@ impl TokyoNightTheme {
             .add_modifier(Modifier::ITALIC)
     }
 
-    #[allow(dead_code)]
     pub fn markdown_link() -> Style {
         Style::default()
             .fg(Self::BLUE)
             .add_modifier(Modifier::UNDERLINED)
     }
 
+    /// Inline `#tag` in note text
+    pub fn hashtag() -> Style {
+        Style::default()
+            .fg(Self::CYAN)
+            .add_modifier(Modifier::BOLD)
+    }
+
+    /// Wiki link whose target note does not exist
+    pub fn broken_link() -> Style {
+        Style::default()
+            .fg(Self::RED)
+            .add_modifier(Modifier::UNDERLINED | Modifier::ITALIC)
+    }
+
     /// File tree icons and styling
     pub fn folder_icon() -> Style {
         Style::default().fg(Self::BLUE)
@@ -193,6 +206,14 @@ impl TokyoNightTheme {
         Style::default().fg(Self::GREEN)
     }
 
+    pub fn pinned_icon() -> Style {
+        Style::default().fg(Self::YELLOW)
+    }
+
+    pub fn saved_search_icon() -> Style {
+        Style::default().fg(Self::PURPLE)
+    }
+
     /// Helper text and placeholders
     pub fn placeholder() -> Style {
         Style::default().fg(Self::COMMENT)
@@ -225,6 +246,7 @@ impl TokyoNightTheme {
     }
 
     /// Welcome screen accent
+    #[allow(dead_code)]
     pub fn welcome_accent() -> Style {
         Style::default()
             .fg(Self::CYAN)
@@ -238,13 +260,20 @@ impl TokyoNightTheme {
     }
 
     /// Search results highlight
-    #[allow(dead_code)]
     pub fn search_match() -> Style {
         Style::default()
             .fg(Self::BG)
             .bg(Self::YELLOW)
             .add_modifier(Modifier::BOLD)
     }
+
+    /// The find match under the cursor
+    pub fn current_search_match() -> Style {
+        Style::default()
+            .fg(Self::BG)
+            .bg(Self::ORANGE)
+            .add_modifier(Modifier::BOLD)
+    }
 }
 
 /// Monochrome icon constants for consistent theming
@@ -255,6 +284,8 @@ impl Icons {
     pub const FOLDER_CLOSED: &'static str = "▶";
     pub const FOLDER_OPEN: &'static str = "▼";
     pub const NOTE: &'static str = "●";
+    pub const PINNED: &'static str = "★";
+    #[allow(dead_code)]
     pub const ROOT: &'static str = "~";
     
     // Status indicators
@@ -266,6 +297,7 @@ impl Icons {
     // Mode indicators
     pub const EDITOR: &'static str = "▣";
     pub const PREVIEW: &'static str = "◈";
+    pub const BACKLINKS: &'static str = "⇠";
     pub const SEARCH: &'static str = "◉";
     
     // Navigation
@@ -273,10 +305,16 @@ impl Icons {
     pub const EXPLORER: &'static str = "≡";
     
     // Alternative icon sets (you can switch between these)
+    #[allow(dead_code)]
     pub const FOLDER_CLOSED_ALT: &'static str = "►";
+    #[allow(dead_code)]
     pub const FOLDER_OPEN_ALT: &'static str = "▽";
+    #[allow(dead_code)]
     pub const NOTE_ALT: &'static str = "◦";
+    #[allow(dead_code)]
     pub const FOLDER_CLOSED_SIMPLE: &'static str = "+";
+    #[allow(dead_code)]
     pub const FOLDER_OPEN_SIMPLE: &'static str = "-";
+    #[allow(dead_code)]
     pub const NOTE_SIMPLE: &'static str = "•";
 }
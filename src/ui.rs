@@ -1,6 +1,9 @@
-use crate::app::{App, AppMode, FocusedPane, TreeItemType};
-use crate::syntax::simple_markdown_highlight;
+use crate::app::{App, AppMode, FocusedPane, TreeItemType, TreeSection};
+use crate::syntax::{highlight_hashtags, highlight_wiki_links, overlay_styles, simple_markdown_highlight};
 use crate::theme::{TokyoNightTheme, Icons};
+use crate::find;
+use crate::search::MatchType;
+use crate::templates;
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Modifier, Style},
@@ -45,6 +48,7 @@ pub fn draw(f: &mut Frame, app: &mut App) {
     // Draw modal dialogs if in special modes
     match app.mode {
         AppMode::Search => draw_search_dialog(f, app),
+        AppMode::Find => draw_find_bar(f, app),
         AppMode::SearchAdvanced => draw_advanced_search_dialog(f, app),
         AppMode::SearchReplace => draw_replace_dialog(f, app),
         AppMode::Command => draw_command_dialog(f, app),
@@ -52,6 +56,21 @@ pub fn draw(f: &mut Frame, app: &mut App) {
         AppMode::InputFolder => draw_input_folder_dialog(f, app),
         AppMode::Help => draw_help_dialog(f, app),
         AppMode::DeleteConfirm => draw_delete_confirm_dialog(f, app),
+        AppMode::Rename => draw_rename_dialog(f, app),
+        AppMode::TagEdit => draw_tag_edit_dialog(f, app),
+        AppMode::TagBrowser => draw_tag_browser_dialog(f, app),
+        AppMode::TagRename => {
+            draw_tag_browser_dialog(f, app);
+            draw_tag_rename_dialog(f, app);
+        }
+        AppMode::RenameConfirm => draw_rename_confirm_dialog(f, app),
+        AppMode::QuickOpen => draw_quick_open_dialog(f, app),
+        AppMode::CommandPalette => draw_command_palette_dialog(f, app),
+        AppMode::Recent => draw_recent_dialog(f, app),
+        AppMode::TemplatePicker => draw_template_picker_dialog(f, app),
+        AppMode::TemplatePrompt => draw_template_prompt_dialog(f, app),
+        AppMode::SavedSearchEdit => draw_saved_search_edit_dialog(f, app),
+        AppMode::Tasks => draw_tasks_dialog(f, app),
         _ => {},
     }
 }
@@ -69,7 +88,8 @@ fn draw_breadcrumb(f: &mut Frame, app: &App, area: Rect) {
             format!("{} Root {} ", Icons::FOLDER_CLOSED, Icons::BREADCRUMB_SEPARATOR)
         };
         
-        format!("{}{} {}", folder_path, Icons::NOTE, note.title)
+        let tags: String = note.tags.iter().map(|t| format!("  #{}", t)).collect();
+        format!("{}{} {}{}", folder_path, Icons::NOTE, note.title, tags)
     } else {
         "Scribble • Select a note to start editing".to_string()
     };
@@ -94,7 +114,10 @@ fn draw_folder_tree(f: &mut Frame, app: &mut App, area: Rect) {
     // Count notes and folders for title
     let note_count = app.notebook.notes.len();
     let folder_count = app.notebook.folders.len();
-    let title = format!("{} Explorer ({} notes, {} folders)", Icons::EXPLORER, note_count, folder_count);
+    let title = match app.tag_filter {
+        Some(ref tag) => format!("{} Explorer - #{} (T to change)", Icons::EXPLORER, tag),
+        None => format!("{} Explorer ({} notes, {} folders)", Icons::EXPLORER, note_count, folder_count),
+    };
 
     let block = Block::default()
         .borders(Borders::ALL)
@@ -127,7 +150,22 @@ fn draw_folder_tree(f: &mut Frame, app: &mut App, area: Rect) {
                         (Icons::FOLDER_CLOSED, TokyoNightTheme::folder_icon())
                     }
                 }
+                TreeItemType::Note if item.section == TreeSection::Pinned => (Icons::PINNED, TokyoNightTheme::pinned_icon()),
                 TreeItemType::Note => (Icons::NOTE, TokyoNightTheme::note_icon()),
+                TreeItemType::Pinned => {
+                    if item.expanded {
+                        (Icons::FOLDER_OPEN, TokyoNightTheme::pinned_icon())
+                    } else {
+                        (Icons::FOLDER_CLOSED, TokyoNightTheme::pinned_icon())
+                    }
+                }
+                TreeItemType::SavedSearches | TreeItemType::SavedSearch => {
+                    if item.expanded {
+                        (Icons::FOLDER_OPEN, TokyoNightTheme::saved_search_icon())
+                    } else {
+                        (Icons::FOLDER_CLOSED, TokyoNightTheme::saved_search_icon())
+                    }
+                }
             };
             
             let style = if app.mode == AppMode::Move {
@@ -146,6 +184,9 @@ fn draw_folder_tree(f: &mut Frame, app: &mut App, area: Rect) {
                                 // Show parent folder as destination for notes
                                 Style::default().fg(TokyoNightTheme::CYAN).bg(TokyoNightTheme::BG_HIGHLIGHT)
                             }
+                            TreeItemType::Pinned | TreeItemType::SavedSearches | TreeItemType::SavedSearch => {
+                                Style::default().fg(TokyoNightTheme::COMMENT).bg(TokyoNightTheme::BG_HIGHLIGHT)
+                            }
                         }
                     } else {
                         // Dim other items
@@ -189,6 +230,34 @@ fn draw_folder_tree(f: &mut Frame, app: &mut App, area: Rect) {
 }
 
 fn draw_editor(f: &mut Frame, app: &mut App, area: Rect) {
+    // The search results and backlinks panels sit below the editor (and preview)
+    let area = if app.search_results_enabled {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(5),
+                Constraint::Length((area.height / 3).clamp(5, 12)),
+            ])
+            .split(area);
+        draw_search_results_pane(f, app, chunks[1]);
+        chunks[0]
+    } else {
+        area
+    };
+    let area = if app.backlinks_enabled {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(5),
+                Constraint::Length((area.height / 3).clamp(5, 12)),
+            ])
+            .split(area);
+        draw_backlinks_pane(f, app, chunks[1]);
+        chunks[0]
+    } else {
+        area
+    };
+    
     if app.preview_enabled {
         draw_editor_with_preview(f, app, area);
     } else {
@@ -238,8 +307,17 @@ fn draw_editor_pane(f: &mut Frame, app: &App, area: Rect, is_split_view: bool) {
         
         let preview_indicator = if app.preview_enabled { format!(" {}", Icons::PREVIEW) } else { String::new() };
         
-        format!("{} {} {} {} | {} lines, {} words, {} chars{}", 
-            save_indicator, Icons::EDITOR, note.title, mode_status, line_count, word_count, char_count, preview_indicator)
+        // Show the cell position while the cursor is inside a table
+        let table_indicator = app.table_at_cursor()
+            .filter(|_| is_focused)
+            .map(|(start, _, table)| {
+                let (row, col) = app.table_cell_at_cursor(start, &table);
+                format!(" | table {}x{} @ {},{}", table.rows.len(), table.column_count(), row + 1, col + 1)
+            })
+            .unwrap_or_default();
+        
+        format!("{} {} {} {} | {} lines, {} words, {} chars{}{}", 
+            save_indicator, Icons::EDITOR, note.title, mode_status, line_count, word_count, char_count, table_indicator, preview_indicator)
     } else {
         let preview_indicator = if app.preview_enabled { format!(" {}", Icons::PREVIEW) } else { String::new() };
         format!("{} Editor{}", Icons::EDITOR, preview_indicator)
@@ -250,7 +328,7 @@ fn draw_editor_pane(f: &mut Frame, app: &App, area: Rect, is_split_view: bool) {
         .title(title)
         .border_style(border_style);
 
-    if let Some(_) = app.current_note {
+    if app.current_note.is_some() {
         let content = if app.editor_content.is_empty() {
             "# Start writing your note here...\n\nPress 'i' to enter insert mode\nPress 'Esc' to return to normal mode"
         } else {
@@ -299,8 +377,8 @@ fn draw_editor_pane(f: &mut Frame, app: &App, area: Rect, is_split_view: bool) {
         
         f.render_widget(line_numbers_widget, editor_chunks[0]);
 
-        // Apply enhanced syntax highlighting to content
-        let styled_content = simple_markdown_highlight(content);
+        // Apply enhanced syntax highlighting to content, marking broken [[links]]
+        let styled_content = mark_find_matches(app, mark_search_matches(app, mark_hashtags(mark_wiki_links(app, simple_markdown_highlight(content)))));
         
         let paragraph = Paragraph::new(styled_content)
             .style(TokyoNightTheme::normal())
@@ -309,15 +387,14 @@ fn draw_editor_pane(f: &mut Frame, app: &App, area: Rect, is_split_view: bool) {
 
         f.render_widget(paragraph, editor_chunks[1]);
 
-        // Show cursor if in insert mode (account for line numbers)
-        if app.mode == AppMode::Insert && is_focused {
-            let cursor_area = Rect::new(
-                editor_chunks[1].x + app.editor_cursor.1,
-                editor_chunks[1].y + app.editor_cursor.0 - app.editor_scroll,
-                1,
-                1,
-            );
-            f.set_cursor_position((cursor_area.x, cursor_area.y));
+        // Show cursor while editing or navigating the note (account for line numbers),
+        // unless the view has been scrolled away from the cursor line
+        if (app.mode == AppMode::Insert || app.mode == AppMode::Normal) && is_focused {
+            let visible_row = app.editor_cursor.0.checked_sub(app.editor_scroll)
+                .filter(|&row| row < editor_chunks[1].height);
+            if let Some(row) = visible_row {
+                f.set_cursor_position((editor_chunks[1].x + app.editor_cursor.1, editor_chunks[1].y + row));
+            }
         }
         
         // Draw autocompletion popup if active
@@ -329,6 +406,246 @@ fn draw_editor_pane(f: &mut Frame, app: &App, area: Rect, is_split_view: bool) {
     }
 }
 
+fn draw_backlinks_pane(f: &mut Frame, app: &App, area: Rect) {
+    let is_focused = app.focused_pane == FocusedPane::Backlinks;
+    let border_style = if is_focused {
+        TokyoNightTheme::border_focused()
+    } else {
+        TokyoNightTheme::border_inactive()
+    };
+    
+    let title = match app.current_note {
+        Some(ref note) => format!("{} Backlinks to {} ({})", Icons::BACKLINKS, note.title, app.backlinks.len()),
+        None => format!("{} Backlinks", Icons::BACKLINKS),
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .border_style(border_style);
+    
+    if app.backlinks.is_empty() {
+        let hint = if app.current_note.is_some() {
+            "No notes link here yet. Link to this note with [[its title]]."
+        } else {
+            "Open a note to see what links to it."
+        };
+        let paragraph = Paragraph::new(Span::styled(hint, TokyoNightTheme::placeholder()))
+            .block(block)
+            .wrap(Wrap { trim: true });
+        f.render_widget(paragraph, area);
+        return;
+    }
+    
+    let items: Vec<ListItem> = app.backlinks
+        .iter()
+        .map(|backlink| {
+            let context = highlight_wiki_links(
+                Line::from(Span::styled(backlink.context.as_str(), TokyoNightTheme::help_text())),
+                &|_| true,
+            );
+            let mut spans = vec![
+                Span::styled(format!("{} ", Icons::NOTE), TokyoNightTheme::note_icon()),
+                Span::styled(backlink.source_title.as_str(), Style::default().fg(TokyoNightTheme::FG).add_modifier(Modifier::BOLD)),
+                Span::styled(format!(":{}  ", backlink.line_number + 1), Style::default().fg(TokyoNightTheme::COMMENT)),
+            ];
+            spans.extend(context.spans);
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+    
+    let highlight = if is_focused {
+        TokyoNightTheme::selected()
+    } else {
+        Style::default().bg(TokyoNightTheme::BG_HIGHLIGHT)
+    };
+    let list = List::new(items)
+        .block(block)
+        .style(TokyoNightTheme::normal())
+        .highlight_style(highlight);
+    
+    let mut list_state = ListState::default();
+    list_state.select(Some(app.backlink_selected));
+    f.render_stateful_widget(list, area, &mut list_state);
+}
+
+fn draw_search_results_pane(f: &mut Frame, app: &App, area: Rect) {
+    let is_focused = app.focused_pane == FocusedPane::SearchResults;
+    let border_style = if is_focused {
+        TokyoNightTheme::border_focused()
+    } else {
+        TokyoNightTheme::border_inactive()
+    };
+    
+    let rows = app.search_result_rows();
+    let title = format!("{} Search results ({} in {} notes)", Icons::SEARCH, rows.len(), app.enhanced_search_results.len());
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .border_style(border_style);
+    
+    if rows.is_empty() {
+        let paragraph = Paragraph::new(Span::styled("No matches. Search with / or Ctrl+F.", TokyoNightTheme::placeholder()))
+            .block(block)
+            .wrap(Wrap { trim: true });
+        f.render_widget(paragraph, area);
+        return;
+    }
+    
+    // Each note gets a heading row above its matches; only match rows are selectable
+    let mut items: Vec<ListItem> = Vec::new();
+    let mut selected = 0;
+    for (row, (result_index, match_index)) in rows.into_iter().enumerate() {
+        let result = &app.enhanced_search_results[result_index];
+        if match_index.is_none_or(|m| m == 0) {
+            items.push(ListItem::new(Line::from(vec![
+                Span::styled(format!("{} ", Icons::NOTE), TokyoNightTheme::note_icon()),
+                Span::styled(result.note.title.as_str(), Style::default().fg(TokyoNightTheme::FG).add_modifier(Modifier::BOLD)),
+                Span::styled(format!("  {} matches", result.matches.len()), Style::default().fg(TokyoNightTheme::COMMENT)),
+            ])));
+        }
+        if row == app.search_result_selected {
+            selected = items.len();
+        }
+        
+        let Some(m) = match_index.map(|m| &result.matches[m]) else {
+            items.push(ListItem::new(Span::styled("    matched by the query", TokyoNightTheme::placeholder())));
+            continue;
+        };
+        let location = match m.match_type {
+            MatchType::Content => format!("    {:>4}  ", m.line_number + 1),
+            MatchType::Title => "   title  ".to_string(),
+            MatchType::Tag => "     tag  ".to_string(),
+        };
+        let mut spans = vec![Span::styled(location, Style::default().fg(TokyoNightTheme::COMMENT))];
+        let text = m.line_text.as_str();
+        match (text.get(..m.start_offset), text.get(m.start_offset..m.end_offset), text.get(m.end_offset..)) {
+            (Some(before), Some(matched), Some(after)) => {
+                spans.push(Span::styled(before, TokyoNightTheme::help_text()));
+                spans.push(Span::styled(matched, TokyoNightTheme::search_match()));
+                spans.push(Span::styled(after, TokyoNightTheme::help_text()));
+            }
+            _ => spans.push(Span::styled(text, TokyoNightTheme::help_text())),
+        }
+        items.push(ListItem::new(Line::from(spans)));
+    }
+    
+    let highlight = if is_focused {
+        TokyoNightTheme::selected()
+    } else {
+        Style::default().bg(TokyoNightTheme::BG_HIGHLIGHT)
+    };
+    let list = List::new(items)
+        .block(block)
+        .style(TokyoNightTheme::normal())
+        .highlight_style(highlight);
+    
+    let mut list_state = ListState::default();
+    list_state.select(Some(selected));
+    f.render_stateful_widget(list, area, &mut list_state);
+}
+
+/// Style the [[wiki links]] in rendered note text, marking links to missing notes
+fn mark_wiki_links<'a>(app: &App, mut text: Text<'a>) -> Text<'a> {
+    let note_exists = |title: &str| app.note_titles.contains(&title.trim().to_lowercase());
+    text.lines = text.lines
+        .into_iter()
+        .map(|line| highlight_wiki_links(line, &note_exists))
+        .collect();
+    text
+}
+
+/// Style the inline #tags in editor text, leaving fenced code blocks alone
+fn mark_hashtags(mut text: Text<'_>) -> Text<'_> {
+    let mut in_code_block = false;
+    text.lines = text.lines
+        .into_iter()
+        .map(|line| {
+            let text: String = line.spans.iter().map(|span| span.content.as_ref()).collect();
+            let is_fence = text.trim_start().starts_with("```");
+            if is_fence {
+                in_code_block = !in_code_block;
+            }
+            if is_fence || in_code_block {
+                line
+            } else {
+                highlight_hashtags(line)
+            }
+        })
+        .collect();
+    text
+}
+
+/// Highlight the matches of the last search in the open note while its results are shown
+fn mark_search_matches<'a>(app: &App, mut text: Text<'a>) -> Text<'a> {
+    let result = app.current_note
+        .as_ref()
+        .filter(|_| app.search_results_enabled)
+        .and_then(|note| app.enhanced_search_results.iter().find(|r| r.note.id == note.id));
+    let Some(result) = result else {
+        return text;
+    };
+    
+    text.lines = text.lines
+        .into_iter()
+        .enumerate()
+        .map(|(line_number, line)| {
+            let line_text: String = line.spans.iter().map(|span| span.content.as_ref()).collect();
+            // Lines edited since the search no longer line up with its offsets
+            let mut ranges: Vec<(usize, usize, Style)> = result.matches
+                .iter()
+                .filter(|m| m.match_type == MatchType::Content && m.line_number == line_number && m.line_text == line_text)
+                .map(|m| (m.start_offset, m.end_offset, TokyoNightTheme::search_match()))
+                .collect();
+            ranges.sort_by_key(|&(start, end, _)| (start, end));
+            let mut last_end = 0;
+            ranges.retain(|&(start, end, _)| {
+                let keep = start >= last_end
+                    && start < end
+                    && line_text.is_char_boundary(start)
+                    && line_text.is_char_boundary(end);
+                if keep {
+                    last_end = end;
+                }
+                keep
+            });
+            overlay_styles(line, &ranges)
+        })
+        .collect();
+    text
+}
+
+/// Highlight every occurrence of the find query in the open note, with the one under the cursor stronger
+fn mark_find_matches<'a>(app: &App, mut text: Text<'a>) -> Text<'a> {
+    if app.find_query.is_empty() || app.editor_content.is_empty() {
+        return text;
+    }
+    
+    let source_lines: Vec<&str> = app.editor_content.split('\n').collect();
+    let cursor = (app.editor_cursor.0 as usize, app.editor_cursor.1 as usize);
+    text.lines = text.lines
+        .into_iter()
+        .enumerate()
+        .map(|(line_number, line)| {
+            let line_text: String = line.spans.iter().map(|span| span.content.as_ref()).collect();
+            // Offsets only line up with the cursor when the line is shown as written
+            let as_written = source_lines.get(line_number) == Some(&line_text.as_str());
+            let ranges: Vec<(usize, usize, Style)> = find::find_in_line(&line_text, &app.find_query)
+                .into_iter()
+                .map(|(start, end)| {
+                    let style = if as_written && (line_number, start) == cursor {
+                        TokyoNightTheme::current_search_match()
+                    } else {
+                        TokyoNightTheme::search_match()
+                    };
+                    (start, end, style)
+                })
+                .collect();
+            overlay_styles(line, &ranges)
+        })
+        .collect();
+    text
+}
+
 fn draw_preview_pane(f: &mut Frame, app: &App, area: Rect) {
     let is_focused = app.focused_pane == FocusedPane::Preview;
     
@@ -350,7 +667,7 @@ fn draw_preview_pane(f: &mut Frame, app: &App, area: Rect) {
         let preview_content = if app.editor_content.is_empty() {
             crate::preview::generate_preview_sample()
         } else {
-            crate::preview::render_markdown_preview(&app.editor_content)
+            mark_wiki_links(app, crate::preview::render_markdown_preview(&app.editor_content))
         };
         
         let paragraph = Paragraph::new(preview_content)
@@ -572,23 +889,40 @@ fn draw_status_bar(f: &mut Frame, app: &App, area: Rect) {
         AppMode::Move => "MOVE",
         AppMode::Help => "HELP",
         AppMode::DeleteConfirm => "DELETE?",
+        AppMode::Rename => "RENAME",
+        AppMode::RenameConfirm => "RENAME?",
+        AppMode::TagEdit => "TAGS",
+        AppMode::TagBrowser => "TAG BROWSER",
+        AppMode::TagRename => "RENAME TAG",
+        AppMode::QuickOpen => "OPEN",
+        AppMode::CommandPalette => "PALETTE",
+        AppMode::Recent => "RECENT",
+        AppMode::Tasks => "TASKS",
+        AppMode::TemplatePicker | AppMode::TemplatePrompt => "TEMPLATE",
+        AppMode::SavedSearchEdit => "EDIT SEARCH",
+        AppMode::Find => "FIND",
     };
 
     let pane_text = match app.focused_pane {
         FocusedPane::Folders => format!("{} FOLDERS", Icons::EXPLORER),
         FocusedPane::Editor => format!("{} EDITOR", Icons::EDITOR),
         FocusedPane::Preview => format!("{} PREVIEW", Icons::PREVIEW),
+        FocusedPane::Backlinks => format!("{} BACKLINKS", Icons::BACKLINKS),
+        FocusedPane::SearchResults => format!("{} RESULTS", Icons::SEARCH),
     };
 
     let mode_style = match app.mode {
         AppMode::Normal => TokyoNightTheme::mode_normal(),
         AppMode::Insert => TokyoNightTheme::mode_insert(),
-        AppMode::Search | AppMode::SearchAdvanced | AppMode::SearchReplace => TokyoNightTheme::mode_search(),
-        AppMode::Command => TokyoNightTheme::mode_command(),
-        AppMode::InputNote | AppMode::InputFolder => TokyoNightTheme::mode_input(),
+        AppMode::Search | AppMode::SearchAdvanced | AppMode::SearchReplace | AppMode::QuickOpen | AppMode::Find => TokyoNightTheme::mode_search(),
+        AppMode::Command | AppMode::CommandPalette => TokyoNightTheme::mode_command(),
+        AppMode::InputNote | AppMode::InputFolder | AppMode::Rename | AppMode::RenameConfirm
+        | AppMode::TemplatePicker | AppMode::TemplatePrompt | AppMode::SavedSearchEdit => TokyoNightTheme::mode_input(),
         AppMode::Move => TokyoNightTheme::mode_command(), // Use command style for move mode
         AppMode::Help => TokyoNightTheme::mode_search(), // Use search style for help mode
         AppMode::DeleteConfirm => Style::default().fg(TokyoNightTheme::RED).bg(TokyoNightTheme::BG_HIGHLIGHT).add_modifier(Modifier::BOLD),
+        AppMode::Tasks | AppMode::TagBrowser | AppMode::Recent => TokyoNightTheme::mode_command(),
+        AppMode::TagEdit | AppMode::TagRename => TokyoNightTheme::mode_input(),
     };
     
     // Create enhanced message display with operation result feedback
@@ -627,15 +961,21 @@ fn draw_status_bar(f: &mut Frame, app: &App, area: Rect) {
     left_spans.extend(message_spans);
 
     let right_text = if let Some(ref note) = app.current_note {
-        let cursor_info = if app.mode == AppMode::Insert {
+        let cursor_info = if app.mode == AppMode::Insert || app.focused_pane == FocusedPane::Editor {
             format!(" | {}:{}", app.editor_cursor.0 + 1, app.editor_cursor.1 + 1)
         } else {
             String::new()
         };
+        // Which match of the find in the note the cursor is at, as "3/17"
+        let find_info = match app.find_position() {
+            Some((index, total)) => format!(" | {} {}/{}", Icons::SEARCH, index, total),
+            None => String::new(),
+        };
         
-        format!("Modified: {}{} | {} {} notes", 
+        format!("Modified: {}{}{} | {} {} notes", 
                 note.modified_at.format("%m/%d %H:%M"),
                 cursor_info,
+                find_info,
                 Icons::NOTE,
                 app.notebook.notes.len())
     } else {
@@ -665,36 +1005,66 @@ fn draw_status_bar(f: &mut Frame, app: &App, area: Rect) {
 }
 
 fn draw_search_dialog(f: &mut Frame, app: &App) {
-    let area = centered_rect(70, 25, f.area());
+    // A bar over the status line, so the editor stays visible as the matches update
+    let size = f.area();
+    let height = size.height.min(3);
+    let area = Rect::new(size.x, size.bottom() - height, size.width, height);
     f.render_widget(Clear, area);
 
+    let counts = if app.input_buffer.trim().is_empty() || app.search_pending_since.is_some() {
+        String::new()
+    } else {
+        let matches: usize = app.enhanced_search_results.iter().map(|r| r.matches.len()).sum();
+        format!(" {} notes, {} matches ", app.enhanced_search_results.len(), matches)
+    };
     let block = Block::default()
-        .title(format!("{} Quick Search", Icons::SEARCH))
+        .title(format!("{} Search | Enter: keep | Esc: go back", Icons::SEARCH))
+        .title_bottom(Line::from(counts).right_aligned())
         .borders(Borders::ALL)
         .border_style(TokyoNightTheme::border_focused())
         .style(TokyoNightTheme::popup());
 
-    let help_text = "Enter search terms | Esc: Cancel | Enter: Search";
     let input_text = if app.input_buffer.is_empty() {
-        Span::styled("Search notes and content...", TokyoNightTheme::placeholder())
+        Span::styled("Search notes and content... (fuzzy: allows typos, Ctrl+F for advanced search)", TokyoNightTheme::placeholder())
     } else {
         Span::styled(app.input_buffer.as_str(), Style::default().fg(TokyoNightTheme::FG))
     };
+    let inner = block.inner(area);
+    f.render_widget(Paragraph::new(input_text).block(block), area);
+    f.set_cursor_position((inner.x + app.input_buffer.chars().count() as u16, inner.y));
+}
 
-    let content = vec![
-        Line::from(Span::styled(help_text, TokyoNightTheme::help_text())),
-        Line::from(""),
-        Line::from(input_text),
-        Line::from(""),
-        Line::from(Span::styled("Tip: Use Ctrl+F for advanced search with regex support", 
-            Style::default().fg(TokyoNightTheme::COMMENT).add_modifier(Modifier::ITALIC))),
-    ];
+fn draw_find_bar(f: &mut Frame, app: &App) {
+    // Over the status line like the search bar, keeping the note in view
+    let size = f.area();
+    let height = size.height.min(3);
+    let area = Rect::new(size.x, size.bottom() - height, size.width, height);
+    f.render_widget(Clear, area);
 
-    let input = Paragraph::new(content)
-        .block(block)
-        .alignment(Alignment::Left);
+    let counts = match app.find_position() {
+        Some((_, 0)) => " No matches ".to_string(),
+        Some((index, total)) => format!(" {}/{} ", index, total),
+        None => String::new(),
+    };
+    let title = match app.current_note {
+        Some(ref note) => format!("{} Find in {} | Enter: keep | Esc: go back", Icons::SEARCH, note.title),
+        None => format!("{} Find", Icons::SEARCH),
+    };
+    let block = Block::default()
+        .title(title)
+        .title_bottom(Line::from(counts).right_aligned())
+        .borders(Borders::ALL)
+        .border_style(TokyoNightTheme::border_focused())
+        .style(TokyoNightTheme::popup());
 
-    f.render_widget(input, area);
+    let input_text = if app.input_buffer.is_empty() {
+        Span::styled("Find in this note... (case-sensitive with an uppercase letter)", TokyoNightTheme::placeholder())
+    } else {
+        Span::styled(app.input_buffer.as_str(), Style::default().fg(TokyoNightTheme::FG))
+    };
+    let inner = block.inner(area);
+    f.render_widget(Paragraph::new(input_text).block(block), area);
+    f.set_cursor_position((inner.x + app.input_buffer.chars().count() as u16, inner.y));
 }
 
 fn draw_command_dialog(f: &mut Frame, app: &App) {
@@ -707,16 +1077,218 @@ fn draw_command_dialog(f: &mut Frame, app: &App) {
         .border_style(TokyoNightTheme::border_focused())
         .style(TokyoNightTheme::popup());
 
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Length(1), Constraint::Min(0)])
+        .split(inner);
+
     let input_text = if app.command_buffer.is_empty() {
-        Span::styled("Commands: :w :q :wq :export :backup :import <dir>...", TokyoNightTheme::placeholder())
+        Span::styled("Type a command, Tab to complete (Ctrl+K lists them all)", TokyoNightTheme::placeholder())
     } else {
         Span::styled(app.command_buffer.as_str(), Style::default().fg(TokyoNightTheme::FG))
     };
+    f.render_widget(Paragraph::new(input_text), chunks[0]);
+    let cursor_col = app.command_buffer[..app.command_cursor].chars().count() as u16;
+    f.set_cursor_position((chunks[0].x + cursor_col, chunks[0].y));
 
-    let input = Paragraph::new(input_text)
-        .block(block);
+    // Usage of the command being typed, then the candidates of an ambiguous completion
+    if let Some(hint) = crate::commands::hint(&app.command_buffer) {
+        f.render_widget(Paragraph::new(Span::styled(hint, TokyoNightTheme::help_text())), chunks[1]);
+    }
+    if !app.command_completions.is_empty() {
+        let candidates: Vec<Span> = app.command_completions
+            .iter()
+            .enumerate()
+            .flat_map(|(i, candidate)| {
+                let style = if app.command_completion_index == Some(i) {
+                    Style::default().fg(TokyoNightTheme::BG).bg(TokyoNightTheme::CYAN)
+                } else {
+                    Style::default().fg(TokyoNightTheme::CYAN)
+                };
+                [Span::styled(candidate.as_str(), style), Span::raw("  ")]
+            })
+            .collect();
+        let candidates = Line::from(candidates);
+        f.render_widget(Paragraph::new(candidates).wrap(Wrap { trim: true }), chunks[2]);
+    }
+}
 
-    f.render_widget(input, area);
+fn draw_recent_dialog(f: &mut Frame, app: &App) {
+    let area = centered_rect(60, 60, f.area());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title("🕘 Recent Notes")
+        .borders(Borders::ALL)
+        .border_style(TokyoNightTheme::border_focused())
+        .style(TokyoNightTheme::popup());
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+
+    let current_id = app.current_note.as_ref().map(|n| n.id);
+    let items: Vec<ListItem> = app.recent_notes
+        .iter()
+        .filter_map(|id| app.notebook.notes.get(id))
+        .enumerate()
+        .map(|(i, note)| {
+            let breadcrumb = note.folder_id
+                .map(|id| app.notebook.folder_path(id).join(" / "))
+                .unwrap_or_default();
+            let marker = if Some(note.id) == current_id { "● " } else { "  " };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{:>2} ", i + 1), Style::default().fg(TokyoNightTheme::COMMENT)),
+                Span::styled(marker, Style::default().fg(TokyoNightTheme::GREEN)),
+                Span::styled(format!("{} {}", Icons::NOTE, note.title), Style::default().fg(TokyoNightTheme::FG)),
+                Span::styled(format!("  {}", breadcrumb), Style::default().fg(TokyoNightTheme::COMMENT)),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items).highlight_style(TokyoNightTheme::selected());
+    let mut list_state = ListState::default();
+    list_state.select(Some(app.recent_selected));
+    f.render_stateful_widget(list, chunks[0], &mut list_state);
+
+    f.render_widget(
+        Paragraph::new(Span::styled("j/k: Move | Enter: Open | Esc: Close", TokyoNightTheme::help_text())),
+        chunks[1],
+    );
+}
+
+fn draw_template_picker_dialog(f: &mut Frame, app: &App) {
+    let area = centered_rect(60, 60, f.area());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!("📄 New Note '{}' - Choose a Template", app.pending_note_title))
+        .borders(Borders::ALL)
+        .border_style(TokyoNightTheme::border_focused())
+        .style(TokyoNightTheme::popup());
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+
+    let blank = ListItem::new(Line::from(Span::styled(
+        format!("{} Blank note", Icons::NOTE),
+        Style::default().fg(TokyoNightTheme::COMMENT),
+    )));
+    let items: Vec<ListItem> = std::iter::once(blank)
+        .chain(
+            app.template_choices
+                .iter()
+                .filter_map(|id| app.notebook.notes.get(id))
+                .map(|note| {
+                    let fields = templates::prompt_fields(&note.content);
+                    let mut spans = vec![Span::styled(format!("{} {}", Icons::NOTE, note.title), Style::default().fg(TokyoNightTheme::FG))];
+                    if !fields.is_empty() {
+                        spans.push(Span::styled(format!("  asks for {}", fields.join(", ")), Style::default().fg(TokyoNightTheme::COMMENT)));
+                    }
+                    ListItem::new(Line::from(spans))
+                }),
+        )
+        .collect();
+
+    let list = List::new(items).highlight_style(TokyoNightTheme::selected());
+    let mut list_state = ListState::default();
+    list_state.select(Some(app.template_selected));
+    f.render_stateful_widget(list, chunks[0], &mut list_state);
+
+    f.render_widget(
+        Paragraph::new(Span::styled("j/k: Move | Enter: Create | Esc: Cancel", TokyoNightTheme::help_text())),
+        chunks[1],
+    );
+}
+
+fn draw_template_prompt_dialog(f: &mut Frame, app: &App) {
+    let area = centered_rect(60, 20, f.area());
+    f.render_widget(Clear, area);
+
+    let index = app.template_values.len();
+    let field = app.template_fields.get(index).map(String::as_str).unwrap_or_default();
+    let block = Block::default()
+        .title(format!("📄 {} ({}/{})", field, index + 1, app.template_fields.len()))
+        .borders(Borders::ALL)
+        .border_style(TokyoNightTheme::border_focused())
+        .style(TokyoNightTheme::popup());
+
+    let input_text = if app.input_buffer.is_empty() {
+        Span::styled(format!("Enter {} for '{}'", field, app.pending_note_title), TokyoNightTheme::placeholder())
+    } else {
+        Span::styled(app.input_buffer.as_str(), Style::default().fg(TokyoNightTheme::FG))
+    };
+
+    f.render_widget(Paragraph::new(input_text).block(block), area);
+}
+
+fn draw_command_palette_dialog(f: &mut Frame, app: &App) {
+    let area = centered_rect(60, 60, f.area());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title("⌘ Command Palette")
+        .borders(Borders::ALL)
+        .border_style(TokyoNightTheme::border_focused())
+        .style(TokyoNightTheme::popup());
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Length(1), Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+
+    let input_line = Line::from(vec![
+        Span::styled("> ", Style::default().fg(TokyoNightTheme::BLUE)),
+        if app.input_buffer.is_empty() {
+            Span::styled("Type to find a command", TokyoNightTheme::placeholder())
+        } else {
+            Span::styled(app.input_buffer.as_str(), Style::default().fg(TokyoNightTheme::FG))
+        },
+    ]);
+    f.render_widget(Paragraph::new(input_line), chunks[0]);
+    f.set_cursor_position((chunks[0].x + 2 + app.input_buffer.chars().count() as u16, chunks[0].y));
+
+    let match_style = Style::default().fg(TokyoNightTheme::ORANGE).add_modifier(Modifier::BOLD);
+    let items: Vec<ListItem> = app.palette_matches
+        .iter()
+        .map(|m| {
+            let mut spans = highlight_positions(m.command.name, &m.name_positions, Style::default().fg(TokyoNightTheme::CYAN), match_style);
+            if !m.command.args.is_empty() {
+                spans.push(Span::styled(format!(" {}", m.command.args), Style::default().fg(TokyoNightTheme::COMMENT)));
+            }
+            spans.push(Span::styled(format!("  {}", m.command.description), Style::default().fg(TokyoNightTheme::FG)));
+            if let Some(key) = m.command.keybinding {
+                spans.push(Span::styled(format!("  [{}]", key), Style::default().fg(TokyoNightTheme::YELLOW)));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    if items.is_empty() {
+        f.render_widget(Paragraph::new(Span::styled("No matching commands", TokyoNightTheme::placeholder())), chunks[2]);
+    } else {
+        let list = List::new(items).highlight_style(TokyoNightTheme::selected());
+        let mut list_state = ListState::default();
+        list_state.select(Some(app.palette_selected));
+        f.render_stateful_widget(list, chunks[2], &mut list_state);
+    }
+
+    f.render_widget(
+        Paragraph::new(Span::styled("↑/↓: Choose | Enter: Run | Esc: Cancel", TokyoNightTheme::help_text())),
+        chunks[3],
+    );
 }
 
 fn draw_input_note_dialog(f: &mut Frame, app: &App) {
@@ -764,6 +1336,29 @@ fn draw_input_folder_dialog(f: &mut Frame, app: &App) {
 }
 
 
+fn draw_saved_search_edit_dialog(f: &mut Frame, app: &App) {
+    let area = centered_rect(60, 20, f.area());
+    f.render_widget(Clear, area);
+
+    let name = app.saved_search_edit_id
+        .and_then(|id| app.notebook.saved_searches.iter().find(|s| s.id == id))
+        .map(|s| s.name.as_str())
+        .unwrap_or_default();
+    let block = Block::default()
+        .title(format!("🔍 Edit Saved Search '{}'", name))
+        .borders(Borders::ALL)
+        .border_style(TokyoNightTheme::border_focused())
+        .style(TokyoNightTheme::popup());
+
+    let input_text = if app.input_buffer.is_empty() {
+        Span::styled("Enter a query (regex:, case: and fuzzy: work as in advanced search)", TokyoNightTheme::placeholder())
+    } else {
+        Span::styled(app.input_buffer.as_str(), Style::default().fg(TokyoNightTheme::FG))
+    };
+
+    f.render_widget(Paragraph::new(input_text).block(block), area);
+}
+
 fn draw_advanced_search_dialog(f: &mut Frame, app: &App) {
     let area = centered_rect(70, 30, f.area());
     f.render_widget(Clear, area);
@@ -774,18 +1369,24 @@ fn draw_advanced_search_dialog(f: &mut Frame, app: &App) {
         .border_style(TokyoNightTheme::border_focused())
         .style(TokyoNightTheme::popup());
 
-    let help_text = "Prefixes: regex: case: | History: ↑/↓ | Enter: Search | Esc: Cancel";
+    let help_text = "Prefixes: regex: case: fuzzy: | History: ↑/↓ | Enter: Search | Esc: Cancel";
+    let syntax_text = "tag: folder: title: created:>2026-01-01 modified:<7d | AND OR NOT ( ) \"phrase\"";
     let input_text = if app.input_buffer.is_empty() {
         Span::styled("Enter search pattern...", TokyoNightTheme::placeholder())
     } else {
         Span::styled(app.input_buffer.as_str(), Style::default().fg(TokyoNightTheme::FG))
     };
 
-    let content = vec![
+    let mut content = vec![
         Line::from(Span::styled(help_text, TokyoNightTheme::help_text())),
+        Line::from(Span::styled(syntax_text, TokyoNightTheme::help_text())),
         Line::from(""),
         Line::from(input_text),
     ];
+    if let Some(ref error) = app.search_error {
+        content.push(Line::from(""));
+        content.push(Line::from(Span::styled(format!("⚠ {}", error), Style::default().fg(TokyoNightTheme::RED))));
+    }
 
     let input = Paragraph::new(content)
         .block(block);
@@ -903,7 +1504,12 @@ fn draw_help_dialog(f: &mut Frame, app: &App) {
         Line::from(vec![
             Span::styled("    ", Style::default()),
             Span::styled("j/k ↑/↓", Style::default().fg(TokyoNightTheme::YELLOW).add_modifier(Modifier::BOLD)),
-            Span::styled("   Move up/down in lists", TokyoNightTheme::help_text()),
+            Span::styled("   Move up/down in lists (cursor line in the editor)", TokyoNightTheme::help_text()),
+        ]),
+        Line::from(vec![
+            Span::styled("    ", Style::default()),
+            Span::styled("h/l ←/→", Style::default().fg(TokyoNightTheme::YELLOW).add_modifier(Modifier::BOLD)),
+            Span::styled("   Move the cursor left/right in the editor", TokyoNightTheme::help_text()),
         ]),
         Line::from(vec![
             Span::styled("    ", Style::default()),
@@ -948,6 +1554,31 @@ fn draw_help_dialog(f: &mut Frame, app: &App) {
             Span::styled("Esc    ", Style::default().fg(TokyoNightTheme::YELLOW).add_modifier(Modifier::BOLD)),
             Span::styled("   Exit insert mode (auto-saves)", TokyoNightTheme::help_text()),
         ]),
+        Line::from(vec![
+            Span::styled("    ", Style::default()),
+            Span::styled("u      ", Style::default().fg(TokyoNightTheme::YELLOW).add_modifier(Modifier::BOLD)),
+            Span::styled("   Undo last change (Ctrl+Z in insert mode)", TokyoNightTheme::help_text()),
+        ]),
+        Line::from(vec![
+            Span::styled("    ", Style::default()),
+            Span::styled("Tab/S-Tab", Style::default().fg(TokyoNightTheme::YELLOW).add_modifier(Modifier::BOLD)),
+            Span::styled(" Indent/outdent list items; Enter continues lists", TokyoNightTheme::help_text()),
+        ]),
+        Line::from(vec![
+            Span::styled("    ", Style::default()),
+            Span::styled("Tab/S-Tab", Style::default().fg(TokyoNightTheme::YELLOW).add_modifier(Modifier::BOLD)),
+            Span::styled(" In a table: realign and move to the next/previous cell", TokyoNightTheme::help_text()),
+        ]),
+        Line::from(vec![
+            Span::styled("    ", Style::default()),
+            Span::styled("x      ", Style::default().fg(TokyoNightTheme::YELLOW).add_modifier(Modifier::BOLD)),
+            Span::styled("   Toggle the task checkbox on the cursor line", TokyoNightTheme::help_text()),
+        ]),
+        Line::from(vec![
+            Span::styled("    ", Style::default()),
+            Span::styled("Enter  ", Style::default().fg(TokyoNightTheme::YELLOW).add_modifier(Modifier::BOLD)),
+            Span::styled("   Follow the [[link]] under the cursor ([[ in insert mode to add one)", TokyoNightTheme::help_text()),
+        ]),
         Line::from(vec![
             Span::styled("    ", Style::default()),
             Span::styled("Ctrl+S ", Style::default().fg(TokyoNightTheme::YELLOW).add_modifier(Modifier::BOLD)),
@@ -958,6 +1589,46 @@ fn draw_help_dialog(f: &mut Frame, app: &App) {
             Span::styled("Ctrl+M ", Style::default().fg(TokyoNightTheme::YELLOW).add_modifier(Modifier::BOLD)),
             Span::styled("   Toggle live markdown preview", TokyoNightTheme::help_text()),
         ]),
+        Line::from(vec![
+            Span::styled("    ", Style::default()),
+            Span::styled("Ctrl+B ", Style::default().fg(TokyoNightTheme::YELLOW).add_modifier(Modifier::BOLD)),
+            Span::styled("   Toggle backlinks panel (Enter opens the linking note)", TokyoNightTheme::help_text()),
+        ]),
+        Line::from(vec![
+            Span::styled("    ", Style::default()),
+            Span::styled("Ctrl+P ", Style::default().fg(TokyoNightTheme::YELLOW).add_modifier(Modifier::BOLD)),
+            Span::styled("   Quick open: fuzzy find a note by title or folder", TokyoNightTheme::help_text()),
+        ]),
+        Line::from(vec![
+            Span::styled("    ", Style::default()),
+            Span::styled("Ctrl+O ", Style::default().fg(TokyoNightTheme::YELLOW).add_modifier(Modifier::BOLD)),
+            Span::styled("   Back to the previous note (Ctrl+N goes forward)", TokyoNightTheme::help_text()),
+        ]),
+        Line::from(vec![
+            Span::styled("    ", Style::default()),
+            Span::styled("R      ", Style::default().fg(TokyoNightTheme::YELLOW).add_modifier(Modifier::BOLD)),
+            Span::styled("   Recently opened notes", TokyoNightTheme::help_text()),
+        ]),
+        Line::from(vec![
+            Span::styled("    ", Style::default()),
+            Span::styled("D      ", Style::default().fg(TokyoNightTheme::YELLOW).add_modifier(Modifier::BOLD)),
+            Span::styled("   Today's journal entry ([ and ] step between entries)", TokyoNightTheme::help_text()),
+        ]),
+        Line::from(vec![
+            Span::styled("    ", Style::default()),
+            Span::styled("p      ", Style::default().fg(TokyoNightTheme::YELLOW).add_modifier(Modifier::BOLD)),
+            Span::styled("   Pin or unpin a note (J/K reorder the Pinned section)", TokyoNightTheme::help_text()),
+        ]),
+        Line::from(vec![
+            Span::styled("    ", Style::default()),
+            Span::styled("E      ", Style::default().fg(TokyoNightTheme::YELLOW).add_modifier(Modifier::BOLD)),
+            Span::styled("   Edit the selected saved search (:savesearch saves the last search)", TokyoNightTheme::help_text()),
+        ]),
+        Line::from(vec![
+            Span::styled("    ", Style::default()),
+            Span::styled("Ctrl+K ", Style::default().fg(TokyoNightTheme::YELLOW).add_modifier(Modifier::BOLD)),
+            Span::styled("   Command palette: search and run any command", TokyoNightTheme::help_text()),
+        ]),
         Line::from(""),
         
         // File Operations
@@ -971,6 +1642,21 @@ fn draw_help_dialog(f: &mut Frame, app: &App) {
             Span::styled("d      ", Style::default().fg(TokyoNightTheme::YELLOW).add_modifier(Modifier::BOLD)),
             Span::styled("   Delete selected note or folder (with confirmation)", TokyoNightTheme::help_text()),
         ]),
+        Line::from(vec![
+            Span::styled("    ", Style::default()),
+            Span::styled("r      ", Style::default().fg(TokyoNightTheme::YELLOW).add_modifier(Modifier::BOLD)),
+            Span::styled("   Rename note or folder (also :rename <name>); updates [[links]]", TokyoNightTheme::help_text()),
+        ]),
+        Line::from(vec![
+            Span::styled("    ", Style::default()),
+            Span::styled("t      ", Style::default().fg(TokyoNightTheme::YELLOW).add_modifier(Modifier::BOLD)),
+            Span::styled("   Edit tags of the current note (Tab completes existing tags)", TokyoNightTheme::help_text()),
+        ]),
+        Line::from(vec![
+            Span::styled("    ", Style::default()),
+            Span::styled("T      ", Style::default().fg(TokyoNightTheme::YELLOW).add_modifier(Modifier::BOLD)),
+            Span::styled("   Browse the tag tree; Enter filters, h/l fold, r renames, c clears", TokyoNightTheme::help_text()),
+        ]),
         Line::from(vec![
             Span::styled("    ", Style::default()),
             Span::styled("m      ", Style::default().fg(TokyoNightTheme::YELLOW).add_modifier(Modifier::BOLD)),
@@ -987,7 +1673,12 @@ fn draw_help_dialog(f: &mut Frame, app: &App) {
         Line::from(vec![
             Span::styled("    ", Style::default()),
             Span::styled("/      ", Style::default().fg(TokyoNightTheme::YELLOW).add_modifier(Modifier::BOLD)),
-            Span::styled("   Basic search in notes", TokyoNightTheme::help_text()),
+            Span::styled("   Search all notes (in the editor: find in the open note)", TokyoNightTheme::help_text()),
+        ]),
+        Line::from(vec![
+            Span::styled("    ", Style::default()),
+            Span::styled("n / N  ", Style::default().fg(TokyoNightTheme::YELLOW).add_modifier(Modifier::BOLD)),
+            Span::styled("   Next / previous match of the find in the note, or of the last search", TokyoNightTheme::help_text()),
         ]),
         Line::from(vec![
             Span::styled("    ", Style::default()),
@@ -1022,6 +1713,21 @@ fn draw_help_dialog(f: &mut Frame, app: &App) {
             Span::styled(":backup", Style::default().fg(TokyoNightTheme::YELLOW).add_modifier(Modifier::BOLD)),
             Span::styled("   Create backup of all data", TokyoNightTheme::help_text()),
         ]),
+        Line::from(vec![
+            Span::styled("    ", Style::default()),
+            Span::styled(":tasks ", Style::default().fg(TokyoNightTheme::YELLOW).add_modifier(Modifier::BOLD)),
+            Span::styled("   Open and done tasks across all notes", TokyoNightTheme::help_text()),
+        ]),
+        Line::from(vec![
+            Span::styled("    ", Style::default()),
+            Span::styled(":snippets", Style::default().fg(TokyoNightTheme::YELLOW).add_modifier(Modifier::BOLD)),
+            Span::styled(" Reload user snippets (Tab jumps between tab stops)", TokyoNightTheme::help_text()),
+        ]),
+        Line::from(vec![
+            Span::styled("    ", Style::default()),
+            Span::styled(":table ", Style::default().fg(TokyoNightTheme::YELLOW).add_modifier(Modifier::BOLD)),
+            Span::styled("   format | addrow | delrow | addcol | delcol | align <left|center|right|none>", TokyoNightTheme::help_text()),
+        ]),
         Line::from(vec![
             Span::styled("    ", Style::default()),
             Span::styled(":q     ", Style::default().fg(TokyoNightTheme::YELLOW).add_modifier(Modifier::BOLD)),
@@ -1097,7 +1803,9 @@ fn draw_autocomplete_popup(f: &mut Frame, app: &App, editor_area: Rect) {
         .map(|(i, suggestion)| {
             let is_selected = i == app.autocomplete_state.selected_index;
             
-            let icon = if suggestion.trigger.starts_with("#") {
+            let icon = if suggestion.snippet.is_some() {
+                "✂"
+            } else if suggestion.trigger.starts_with("#") {
                 "#"
             } else if suggestion.trigger == "-" || suggestion.trigger == "*" {
                 "•"
@@ -1138,6 +1846,395 @@ fn draw_autocomplete_popup(f: &mut Frame, app: &App, editor_area: Rect) {
     f.render_widget(list, popup_area);
 }
 
+fn draw_tasks_dialog(f: &mut Frame, app: &App) {
+    let area = centered_rect(80, 80, f.area());
+    f.render_widget(Clear, area);
+
+    let tasks = app.visible_tasks();
+    let open_count = app.task_items.iter().filter(|t| !t.done).count();
+    let done_count = app.task_items.len() - open_count;
+
+    let block = Block::default()
+        .title(format!("☑ Tasks ({} open, {} done) - grouped by {}", open_count, done_count, app.task_grouping.label()))
+        .borders(Borders::ALL)
+        .border_style(TokyoNightTheme::border_focused())
+        .style(TokyoNightTheme::popup());
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+
+    let groups: Vec<String> = tasks.iter()
+        .map(|t| crate::tasks::group_label(&app.notebook, t, app.task_grouping))
+        .collect();
+    let mut group_counts: std::collections::HashMap<&str, (usize, usize)> = std::collections::HashMap::new();
+    for (task, group) in tasks.iter().zip(&groups) {
+        let counts = group_counts.entry(group.as_str()).or_default();
+        counts.1 += 1;
+        if task.done {
+            counts.0 += 1;
+        }
+    }
+
+    // Group headers are interleaved with the tasks, so track the list row of the selection
+    let mut items: Vec<ListItem> = Vec::new();
+    let mut selected_row = 0;
+    let mut current_group: Option<&String> = None;
+
+    for (i, (task, group)) in tasks.iter().zip(&groups).enumerate() {
+        if current_group != Some(group) {
+            let (group_done, group_total) = group_counts[group.as_str()];
+            let icon = match app.task_grouping {
+                crate::tasks::TaskGrouping::Note => Icons::NOTE,
+                crate::tasks::TaskGrouping::Folder => Icons::FOLDER_OPEN,
+            };
+            items.push(ListItem::new(Line::from(vec![
+                Span::styled(format!("{} {}", icon, group), Style::default().fg(TokyoNightTheme::CYAN).add_modifier(Modifier::BOLD)),
+                Span::styled(format!("  {}/{}", group_done, group_total), Style::default().fg(TokyoNightTheme::COMMENT)),
+            ])));
+            current_group = Some(group);
+        }
+
+        if i == app.task_selected {
+            selected_row = items.len();
+        }
+
+        let (checkbox, text_style) = if task.done {
+            ("☑", Style::default().fg(TokyoNightTheme::COMMENT).add_modifier(Modifier::CROSSED_OUT))
+        } else {
+            ("☐", Style::default().fg(TokyoNightTheme::FG))
+        };
+        let mut spans = vec![
+            Span::styled(format!("   {} ", checkbox), Style::default().fg(TokyoNightTheme::GREEN)),
+            Span::styled(task.text.clone(), text_style),
+        ];
+        if app.task_grouping == crate::tasks::TaskGrouping::Folder {
+            spans.push(Span::styled(format!("  ({})", task.note_title), Style::default().fg(TokyoNightTheme::COMMENT)));
+        }
+
+        let style = if i == app.task_selected {
+            TokyoNightTheme::selected()
+        } else {
+            Style::default()
+        };
+        items.push(ListItem::new(Line::from(spans)).style(style));
+    }
+
+    if items.is_empty() {
+        items.push(ListItem::new(Span::styled(
+            "No tasks found. Add one with '- [ ] ' in any note.",
+            TokyoNightTheme::placeholder(),
+        )));
+    }
+
+    let mut list_state = ListState::default();
+    list_state.select(Some(selected_row));
+    f.render_stateful_widget(List::new(items), chunks[0], &mut list_state);
+
+    let done_hint = if app.task_show_done { "hide done" } else { "show done" };
+    let help = Paragraph::new(Span::styled(
+        format!("j/k: Move | Enter: Open | x: Toggle | Tab: Group by note/folder | d: {} | Esc: Close", done_hint),
+        TokyoNightTheme::help_text(),
+    ));
+    f.render_widget(help, chunks[1]);
+}
+
+fn draw_tag_edit_dialog(f: &mut Frame, app: &App) {
+    let area = centered_rect(60, 40, f.area());
+    f.render_widget(Clear, area);
+
+    let title = match app.current_note {
+        Some(ref note) => format!("🏷 Tags for '{}'", note.title),
+        None => "🏷 Tags".to_string(),
+    };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(TokyoNightTheme::border_focused())
+        .style(TokyoNightTheme::popup());
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Length(1), Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+
+    let input_text = if app.input_buffer.is_empty() {
+        Span::styled("Type tags separated by commas or spaces", TokyoNightTheme::placeholder())
+    } else {
+        Span::styled(app.input_buffer.as_str(), Style::default().fg(TokyoNightTheme::FG))
+    };
+    f.render_widget(Paragraph::new(input_text), chunks[0]);
+    f.set_cursor_position((chunks[0].x + app.input_buffer.chars().count() as u16, chunks[0].y));
+
+    let items: Vec<ListItem> = app.tag_suggestions
+        .iter()
+        .enumerate()
+        .map(|(i, tag)| {
+            let style = if i == app.tag_suggestion_selected {
+                Style::default().fg(TokyoNightTheme::BG).bg(TokyoNightTheme::BLUE)
+            } else {
+                Style::default().fg(TokyoNightTheme::FG)
+            };
+            ListItem::new(Line::from(Span::styled(format!("#{}", tag), style)))
+        })
+        .collect();
+    let mut list_state = ListState::default();
+    list_state.select((!app.tag_suggestions.is_empty()).then_some(app.tag_suggestion_selected));
+    f.render_stateful_widget(List::new(items), chunks[2], &mut list_state);
+
+    f.render_widget(
+        Paragraph::new(Span::styled("Tab: Complete | ↑/↓: Choose | Enter: Save | Esc: Cancel", TokyoNightTheme::help_text())),
+        chunks[3],
+    );
+}
+
+fn draw_tag_browser_dialog(f: &mut Frame, app: &App) {
+    let area = centered_rect(50, 60, f.area());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!("🏷 Tags ({})", app.tag_browser_items.len()))
+        .borders(Borders::ALL)
+        .border_style(TokyoNightTheme::border_focused())
+        .style(TokyoNightTheme::popup());
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+
+    if app.tag_browser_items.is_empty() {
+        f.render_widget(
+            Paragraph::new(Span::styled("No tags yet. Press 't' on a note to add some.", TokyoNightTheme::placeholder()))
+                .wrap(Wrap { trim: true }),
+            chunks[0],
+        );
+    } else {
+        let items: Vec<ListItem> = app.tag_browser_items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                let is_active = app.tag_filter.as_deref() == Some(item.path.as_str());
+                let style = if i == app.tag_browser_selected {
+                    TokyoNightTheme::selected()
+                } else {
+                    Style::default().fg(TokyoNightTheme::FG)
+                };
+                let expander = if !item.has_children {
+                    "  "
+                } else if app.tag_browser_collapsed.contains(&item.path) {
+                    "▸ "
+                } else {
+                    "▾ "
+                };
+                // Top-level tags carry the #, nested ones show their last segment
+                let name = if item.depth == 0 { format!("#{}", item.name) } else { item.name.clone() };
+                ListItem::new(Line::from(vec![
+                    Span::styled(if is_active { "● " } else { "  " }, Style::default().fg(TokyoNightTheme::GREEN)),
+                    Span::raw("  ".repeat(item.depth)),
+                    Span::styled(expander, Style::default().fg(TokyoNightTheme::COMMENT)),
+                    Span::styled(name, Style::default().fg(TokyoNightTheme::CYAN)),
+                    Span::styled(format!("  ({})", item.count), Style::default().fg(TokyoNightTheme::COMMENT)),
+                ])).style(style)
+            })
+            .collect();
+        let mut list_state = ListState::default();
+        list_state.select(Some(app.tag_browser_selected));
+        f.render_stateful_widget(List::new(items), chunks[0], &mut list_state);
+    }
+
+    f.render_widget(
+        Paragraph::new(Span::styled(
+            "j/k: Move | h/l: Fold | Enter: Filter tree | r: Rename | c: Clear filter | Esc: Close",
+            TokyoNightTheme::help_text(),
+        )),
+        chunks[1],
+    );
+}
+
+fn draw_quick_open_dialog(f: &mut Frame, app: &App) {
+    let area = centered_rect(60, 60, f.area());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!("{} Open Note", Icons::SEARCH))
+        .borders(Borders::ALL)
+        .border_style(TokyoNightTheme::border_focused())
+        .style(TokyoNightTheme::popup());
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Length(1), Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+
+    let input_line = Line::from(vec![
+        Span::styled("> ", Style::default().fg(TokyoNightTheme::BLUE)),
+        if app.input_buffer.is_empty() {
+            Span::styled("Type to find a note by title or folder", TokyoNightTheme::placeholder())
+        } else {
+            Span::styled(app.input_buffer.as_str(), Style::default().fg(TokyoNightTheme::FG))
+        },
+    ]);
+    f.render_widget(Paragraph::new(input_line), chunks[0]);
+    f.set_cursor_position((chunks[0].x + 2 + app.input_buffer.chars().count() as u16, chunks[0].y));
+
+    let match_style = Style::default().fg(TokyoNightTheme::ORANGE).add_modifier(Modifier::BOLD);
+    let items: Vec<ListItem> = app.quick_open_results
+        .iter()
+        .map(|result| {
+            let mut spans = vec![Span::styled(format!("{} ", Icons::NOTE), Style::default().fg(TokyoNightTheme::GREEN))];
+            spans.extend(highlight_positions(&result.title, &result.title_positions, Style::default().fg(TokyoNightTheme::FG), match_style));
+            if !result.breadcrumb.is_empty() {
+                spans.push(Span::styled("  ", Style::default()));
+                spans.extend(highlight_positions(
+                    &result.breadcrumb,
+                    &result.breadcrumb_positions,
+                    Style::default().fg(TokyoNightTheme::COMMENT),
+                    match_style,
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    if items.is_empty() {
+        f.render_widget(Paragraph::new(Span::styled("No matching notes", TokyoNightTheme::placeholder())), chunks[2]);
+    } else {
+        let list = List::new(items).highlight_style(TokyoNightTheme::selected());
+        let mut list_state = ListState::default();
+        list_state.select(Some(app.quick_open_selected));
+        f.render_stateful_widget(list, chunks[2], &mut list_state);
+    }
+
+    f.render_widget(
+        Paragraph::new(Span::styled(
+            format!("{} notes | ↑/↓: Choose | Enter: Open | Esc: Cancel", app.quick_open_results.len()),
+            TokyoNightTheme::help_text(),
+        )),
+        chunks[3],
+    );
+}
+
+/// Split text into spans, styling the chars at the given indices as matches
+fn highlight_positions(text: &str, positions: &[usize], base: Style, matched: Style) -> Vec<Span<'static>> {
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    let mut run = String::new();
+    let mut run_matched = false;
+    for (i, c) in text.chars().enumerate() {
+        let is_match = positions.contains(&i);
+        if is_match != run_matched && !run.is_empty() {
+            spans.push(Span::styled(std::mem::take(&mut run), if run_matched { matched } else { base }));
+        }
+        run_matched = is_match;
+        run.push(c);
+    }
+    if !run.is_empty() {
+        spans.push(Span::styled(run, if run_matched { matched } else { base }));
+    }
+    spans
+}
+
+fn draw_tag_rename_dialog(f: &mut Frame, app: &App) {
+    let area = centered_rect(50, 20, f.area());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!("🏷 Rename #{} and its nested tags", app.tag_rename_old))
+        .borders(Borders::ALL)
+        .border_style(TokyoNightTheme::border_focused())
+        .style(TokyoNightTheme::popup());
+
+    let input_text = if app.input_buffer.is_empty() {
+        Span::styled("Enter a new tag (Esc to cancel)", TokyoNightTheme::placeholder())
+    } else {
+        Span::styled(app.input_buffer.as_str(), Style::default().fg(TokyoNightTheme::FG))
+    };
+
+    f.render_widget(Paragraph::new(input_text).block(block), area);
+}
+
+fn draw_rename_dialog(f: &mut Frame, app: &App) {
+    let area = centered_rect(60, 20, f.area());
+    f.render_widget(Clear, area);
+
+    let (icon, kind) = match app.rename_item_type {
+        Some(TreeItemType::Folder) => ("📁", "Folder"),
+        Some(TreeItemType::SavedSearch) => ("🔍", "Saved Search"),
+        _ => (Icons::NOTE, "Note"),
+    };
+    let block = Block::default()
+        .title(format!("{} Rename {} '{}'", icon, kind, app.rename_old_name))
+        .borders(Borders::ALL)
+        .border_style(TokyoNightTheme::border_focused())
+        .style(TokyoNightTheme::popup());
+
+    let input_text = if app.input_buffer.is_empty() {
+        Span::styled("Enter a new name (Esc to cancel)", TokyoNightTheme::placeholder())
+    } else {
+        Span::styled(app.input_buffer.as_str(), Style::default().fg(TokyoNightTheme::FG))
+    };
+
+    let input = Paragraph::new(input_text)
+        .block(block);
+
+    f.render_widget(input, area);
+}
+
+fn draw_rename_confirm_dialog(f: &mut Frame, app: &App) {
+    let area = centered_rect(60, 30, f.area());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title("✏️  Update Links")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(TokyoNightTheme::YELLOW))
+        .style(TokyoNightTheme::popup());
+
+    let note_count = app.rename_link_notes.len();
+    let content = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(&app.rename_old_name, Style::default().fg(TokyoNightTheme::FG).add_modifier(Modifier::BOLD)),
+            Span::styled(" → ", TokyoNightTheme::help_text()),
+            Span::styled(&app.rename_new_name, Style::default().fg(TokyoNightTheme::GREEN).add_modifier(Modifier::BOLD)),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(
+                format!("{} {} link to this note.", note_count, if note_count == 1 { "note" } else { "notes" }),
+                Style::default().fg(TokyoNightTheme::YELLOW).add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        Line::from(Span::styled("Update their [[links]] to the new title?", TokyoNightTheme::help_text())),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("'y'", Style::default().fg(TokyoNightTheme::GREEN).add_modifier(Modifier::BOLD)),
+            Span::styled(" rename and update links, ", TokyoNightTheme::help_text()),
+            Span::styled("'n'", Style::default().fg(TokyoNightTheme::YELLOW).add_modifier(Modifier::BOLD)),
+            Span::styled(" rename only, ", TokyoNightTheme::help_text()),
+            Span::styled("Esc", Style::default().fg(TokyoNightTheme::RED).add_modifier(Modifier::BOLD)),
+            Span::styled(" cancel", TokyoNightTheme::help_text()),
+        ]),
+    ];
+
+    let paragraph = Paragraph::new(content)
+        .block(block)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: false });
+
+    f.render_widget(paragraph, area);
+}
+
 fn draw_delete_confirm_dialog(f: &mut Frame, app: &App) {
     let area = centered_rect(60, 30, f.area());
     f.render_widget(Clear, area);
@@ -1152,6 +2249,8 @@ fn draw_delete_confirm_dialog(f: &mut Frame, app: &App) {
         match item_type {
             TreeItemType::Note => "note",
             TreeItemType::Folder => "folder",
+            TreeItemType::SavedSearch => "saved search",
+            TreeItemType::Pinned | TreeItemType::SavedSearches => "section",
         }
     } else {
         "item"